@@ -0,0 +1,155 @@
+/// A single step in the diff between two sequences.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum DiffOp {
+    /// An item that is present in both sequences.
+    Unchanged { before: usize, after: usize },
+
+    /// An item that is only present in the first sequence.
+    Removed(usize),
+
+    /// An item that is only present in the second sequence.
+    Added(usize),
+}
+
+/// Compute the diff between two sequences, based on their longest common subsequence.
+///
+/// The returned steps reference items by their index in the sequence they belong to. Removals are placed before the
+/// additions that replace them.
+///
+/// This uses Hirschberg's algorithm so the memory used is linear in the length of the sequences rather than
+/// proportional to the product of their lengths.
+pub(crate) fn diff<T: PartialEq>(before: &[T], after: &[T]) -> Vec<DiffOp> {
+    let mut ops = Vec::new();
+    diff_ranges(before, after, 0, 0, &mut ops);
+
+    // Move removals before the additions next to them.
+    let mut start = 0;
+    while start < ops.len() {
+        let length = ops[start..].iter().take_while(|op| !matches!(op, DiffOp::Unchanged { .. })).count();
+        ops[start..start + length].sort_by_key(|op| matches!(op, DiffOp::Added(_)));
+        start += length.max(1);
+    }
+    ops
+}
+
+fn diff_ranges<T: PartialEq>(
+    before: &[T],
+    after: &[T],
+    before_offset: usize,
+    after_offset: usize,
+    ops: &mut Vec<DiffOp>,
+) {
+    let prefix = before.iter().zip(after).take_while(|(a, b)| a == b).count();
+    ops.extend((0..prefix).map(|i| DiffOp::Unchanged { before: before_offset + i, after: after_offset + i }));
+    let (before, after) = (&before[prefix..], &after[prefix..]);
+    let (before_offset, after_offset) = (before_offset + prefix, after_offset + prefix);
+
+    let suffix = before.iter().rev().zip(after.iter().rev()).take_while(|(a, b)| a == b).count();
+    let (before, after) = (&before[..before.len() - suffix], &after[..after.len() - suffix]);
+
+    if before.is_empty() {
+        ops.extend((0..after.len()).map(|j| DiffOp::Added(after_offset + j)));
+    } else if after.is_empty() {
+        ops.extend((0..before.len()).map(|i| DiffOp::Removed(before_offset + i)));
+    } else if before.len() == 1 {
+        match after.iter().position(|item| *item == before[0]) {
+            Some(j) => {
+                ops.extend((0..j).map(|j| DiffOp::Added(after_offset + j)));
+                ops.push(DiffOp::Unchanged { before: before_offset, after: after_offset + j });
+                ops.extend((j + 1..after.len()).map(|j| DiffOp::Added(after_offset + j)));
+            }
+            None => {
+                ops.push(DiffOp::Removed(before_offset));
+                ops.extend((0..after.len()).map(|j| DiffOp::Added(after_offset + j)));
+            }
+        }
+    } else {
+        // Split `before` in half and find the point in `after` where the longest common subsequence crosses it.
+        let middle = before.len() / 2;
+        let forward = lcs_lengths(before[..middle].iter(), after.iter());
+        let backward = lcs_lengths(before[middle..].iter().rev(), after.iter().rev());
+        let split = (0..=after.len()).max_by_key(|&j| (forward[j] + backward[after.len() - j], usize::MAX - j));
+        let split = split.unwrap_or_default();
+        diff_ranges(&before[..middle], &after[..split], before_offset, after_offset, ops);
+        diff_ranges(&before[middle..], &after[split..], before_offset + middle, after_offset + split, ops);
+    }
+
+    let (before_offset, after_offset) = (before_offset + before.len(), after_offset + after.len());
+    ops.extend((0..suffix).map(|i| DiffOp::Unchanged { before: before_offset + i, after: after_offset + i }));
+}
+
+// Get the length of the longest common subsequence between `before` and every prefix of `after`.
+fn lcs_lengths<'a, T, I, J>(before: I, after: J) -> Vec<usize>
+where
+    T: PartialEq + 'a,
+    I: Iterator<Item = &'a T>,
+    J: Iterator<Item = &'a T> + Clone,
+{
+    let length = after.clone().count();
+    let mut previous = vec![0; length + 1];
+    let mut current = vec![0; length + 1];
+    for item in before {
+        for (j, other) in after.clone().enumerate() {
+            current[j + 1] = match item == other {
+                true => previous[j] + 1,
+                false => previous[j + 1].max(current[j]),
+            };
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::equal(&["a", "b"], &["a", "b"], &[DiffOp::Unchanged { before: 0, after: 0 }, DiffOp::Unchanged { before: 1, after: 1 }])]
+    #[case::added(&["a"], &["a", "b"], &[DiffOp::Unchanged { before: 0, after: 0 }, DiffOp::Added(1)])]
+    #[case::removed(&["a", "b"], &["b"], &[DiffOp::Removed(0), DiffOp::Unchanged { before: 1, after: 0 }])]
+    #[case::replaced(&["a", "b", "c"], &["a", "x", "c"], &[
+        DiffOp::Unchanged { before: 0, after: 0 },
+        DiffOp::Removed(1),
+        DiffOp::Added(1),
+        DiffOp::Unchanged { before: 2, after: 2 },
+    ])]
+    #[case::empty(&[], &["a"], &[DiffOp::Added(0)])]
+    #[case::interleaved(&["a", "b", "c", "d"], &["x", "b", "y", "d"], &[
+        DiffOp::Removed(0),
+        DiffOp::Added(0),
+        DiffOp::Unchanged { before: 1, after: 1 },
+        DiffOp::Removed(2),
+        DiffOp::Added(2),
+        DiffOp::Unchanged { before: 3, after: 3 },
+    ])]
+    #[case::moved(&["a", "b", "c"], &["c", "a", "b"], &[
+        DiffOp::Added(0),
+        DiffOp::Unchanged { before: 0, after: 1 },
+        DiffOp::Unchanged { before: 1, after: 2 },
+        DiffOp::Removed(2),
+    ])]
+    fn diffs(#[case] before: &[&str], #[case] after: &[&str], #[case] expected: &[DiffOp]) {
+        assert_eq!(diff(before, after), expected);
+    }
+
+    #[test]
+    fn large_diff() {
+        // Every item is different so this would need a table with millions of cells without linear space.
+        let before: Vec<_> = (0..3_000).map(|i| i * 2).collect();
+        let after: Vec<_> = (0..3_000).map(|i| i * 2 + 1).collect();
+        let ops = diff(&before, &after);
+        assert_eq!(ops.len(), 6_000);
+        assert!(ops[..3_000].iter().all(|op| matches!(op, DiffOp::Removed(_))));
+    }
+
+    #[test]
+    fn common_subsequence_length() {
+        let before: Vec<_> = "the quick brown fox jumps over the lazy dog".chars().collect();
+        let after: Vec<_> = "a quick brown dog jumps over the lazy fox".chars().collect();
+        let unchanged = diff(&before, &after).iter().filter(|op| matches!(op, DiffOp::Unchanged { .. })).count();
+        let lengths = lcs_lengths(before.iter(), after.iter());
+        assert_eq!(unchanged, lengths[after.len()]);
+    }
+}
@@ -3,19 +3,26 @@
 use super::snippet::SnippetExecutorSpec;
 use crate::{
     code::snippet::{Snippet, SnippetExecution, SnippetLanguage, SnippetRepr},
-    config::{LanguageSnippetExecutionConfig, SnippetExecutorConfig},
+    config::{
+        LanguageSnippetExecutionConfig, SnippetExecutionLimitsConfig, SnippetExecutorConfig, SnippetSandboxConfig,
+    },
 };
 use once_cell::sync::Lazy;
 use os_pipe::PipeReader;
 use std::{
     collections::{BTreeMap, HashMap},
+    env,
     fmt::{self, Debug},
     fs::File,
     io::{self, BufRead, BufReader, Read, Write},
-    path::{Path, PathBuf},
-    process::{self, Child, Stdio},
-    sync::{Arc, Mutex},
+    path::PathBuf,
+    process::{self, Child, ChildStdin, Stdio},
+    sync::{
+        Arc, Mutex,
+        mpsc::{self, RecvTimeoutError},
+    },
     thread,
+    time::Duration,
 };
 use tempfile::TempDir;
 
@@ -24,7 +31,7 @@ static EXECUTORS: Lazy<BTreeMap<SnippetLanguage, LanguageSnippetExecutionConfig>
 
 /// Strip verbatim UNC prefix when drive letters
 #[cfg(windows)]
-fn strip_drive_unc_prefix(path: &Path) -> String {
+fn strip_drive_unc_prefix(path: &std::path::Path) -> String {
     // Convert to string (lossy if needed)
     let path_str = path.to_string_lossy();
 
@@ -44,6 +51,9 @@ fn strip_drive_unc_prefix(path: &Path) -> String {
 pub struct SnippetExecutor {
     executors: BTreeMap<SnippetLanguage, LanguageSnippetExecutionConfig>,
     cwd: PathBuf,
+    limits: SnippetExecutionLimitsConfig,
+    language_limits: BTreeMap<SnippetLanguage, SnippetExecutionLimitsConfig>,
+    sandbox: SnippetSandboxConfig,
 }
 
 impl SnippetExecutor {
@@ -59,7 +69,30 @@ impl SnippetExecutor {
                 Self::validate_executor_config(language, alternative)?;
             }
         }
-        Ok(Self { executors, cwd })
+        Ok(Self {
+            executors,
+            cwd,
+            limits: Default::default(),
+            language_limits: Default::default(),
+            sandbox: Default::default(),
+        })
+    }
+
+    /// Set the limits applied when executing snippets, optionally overriding them for specific languages.
+    pub fn with_limits(
+        mut self,
+        limits: SnippetExecutionLimitsConfig,
+        language_limits: BTreeMap<SnippetLanguage, SnippetExecutionLimitsConfig>,
+    ) -> Self {
+        self.limits = limits;
+        self.language_limits = language_limits;
+        self
+    }
+
+    /// Set the sandbox snippets are executed in.
+    pub fn with_sandbox(mut self, sandbox: SnippetSandboxConfig) -> Self {
+        self.sandbox = sandbox;
+        self
     }
 
     pub(crate) fn language_executor(
@@ -72,7 +105,7 @@ impl SnippetExecutor {
             .executors
             .get(language)
             .ok_or_else(|| UnsupportedExecution(language.clone(), "no executors found".into()))?;
-        let config = match spec {
+        let mut config = match spec {
             SnippetExecutorSpec::Default => language_config.executor.clone(),
             SnippetExecutorSpec::Alternative(name) => {
                 language_config.alternative.get(name).cloned().ok_or_else(|| {
@@ -81,6 +114,8 @@ impl SnippetExecutor {
             }
         };
 
+        self.apply_sandbox(language, &mut config)?;
+
         let mut env = config.environment.clone();
         env.extend(custom_env);
         Ok(LanguageSnippetExecutor {
@@ -88,9 +123,49 @@ impl SnippetExecutor {
             config,
             cwd: self.cwd.clone(),
             env,
+            stdin: None,
+            limits: self.limits(language),
         })
     }
 
+    fn apply_sandbox(
+        &self,
+        language: &SnippetLanguage,
+        config: &mut SnippetExecutorConfig,
+    ) -> Result<(), UnsupportedExecution> {
+        let enabled =
+            config.sandbox.or_else(|| self.sandbox.languages.get(language).copied()).unwrap_or(self.sandbox.enable);
+        if !enabled {
+            return Ok(());
+        }
+        if self.sandbox.runner.is_empty() {
+            return Err(UnsupportedExecution(language.clone(), "sandbox runner is empty".into()));
+        }
+        let mut runner = self.sandbox.runner.clone();
+        if runner.iter().any(|arg| arg.contains("$home")) {
+            let home = env::var("HOME").map_err(|_| {
+                UnsupportedExecution(language.clone(), "sandbox runner uses $home but HOME is not set".into())
+            })?;
+            for arg in &mut runner {
+                *arg = arg.replace("$home", &home);
+            }
+        }
+        for command in &mut config.commands {
+            command.splice(0..0, runner.iter().cloned());
+        }
+        Ok(())
+    }
+
+    fn limits(&self, language: &SnippetLanguage) -> ExecutionLimits {
+        let default = &self.limits;
+        let language = self.language_limits.get(language).unwrap_or(default);
+        ExecutionLimits {
+            timeout: language.timeout.or(default.timeout).map(|d| d.0),
+            memory: language.memory.or(default.memory).map(|s| s.0),
+            cpu_time: language.cpu_time.or(default.cpu_time).map(|d| d.0),
+        }
+    }
+
     pub(crate) fn hidden_line_prefix(&self, language: &SnippetLanguage) -> Option<&str> {
         self.executors.get(language).and_then(|lang| lang.hidden_line_prefix.as_deref())
     }
@@ -132,26 +207,48 @@ pub(crate) struct LanguageSnippetExecutor {
     config: SnippetExecutorConfig,
     cwd: PathBuf,
     env: HashMap<String, String>,
+    stdin: Option<String>,
+    limits: ExecutionLimits,
 }
 
 impl LanguageSnippetExecutor {
+    /// Set the input to be written into the standard input of the snippet when it's executed.
+    pub(crate) fn set_stdin(&mut self, input: String) {
+        self.stdin = Some(input);
+    }
+
+    /// Set how long the snippet can run for before it's killed.
+    pub(crate) fn set_timeout(&mut self, timeout: Duration) {
+        self.limits.timeout = Some(timeout);
+    }
+
+    /// How long the snippet can run for before it's killed.
+    pub(crate) fn timeout(&self) -> Option<Duration> {
+        self.limits.timeout
+    }
+
     /// Execute a piece of code asynchronously.
     pub(crate) fn execute_async(&self, snippet: &Snippet) -> Result<ExecutionHandle, CodeExecuteError> {
         let script_dir = self.write_snippet(snippet)?;
         let state: Arc<Mutex<ExecutionState>> = Default::default();
+        let stdin: Arc<Mutex<Option<ChildStdin>>> = Default::default();
+        let control: Arc<ProcessControl> = Default::default();
         let output_type = match &snippet.attributes.execution {
             SnippetExecution::Exec(args) if matches!(args.repr, SnippetRepr::Image) => OutputType::Binary,
             _ => OutputType::Lines,
         };
-        let reader_handle = CommandsRunner::spawn(
-            state.clone(),
-            script_dir,
-            self.config.commands.clone(),
-            self.env.clone(),
-            self.cwd.clone(),
+        let input = InputSource { contents: self.stdin.clone(), interactive: snippet.attributes.interactive };
+        let runner = CommandsRunner {
+            state: state.clone(),
+            stdin: stdin.clone(),
+            control: control.clone(),
+            script_directory: script_dir,
+            env: self.env.clone(),
+            cwd: self.cwd.clone(),
             output_type,
-        );
-        let handle = ExecutionHandle { state, reader_handle };
+        };
+        let reader_handle = runner.spawn(self.config.commands.clone(), input, self.limits.clone());
+        let handle = ExecutionHandle { state, stdin, control, reader_handle };
         Ok(handle)
     }
 
@@ -223,6 +320,14 @@ impl LanguageSnippetExecutor {
     }
 }
 
+/// The limits applied to a snippet's execution.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct ExecutionLimits {
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) memory: Option<u64>,
+    pub(crate) cpu_time: Option<Duration>,
+}
+
 pub(crate) struct PtySnippetContext {
     pub(crate) command: portable_pty::CommandBuilder,
     _temp: TempDir,
@@ -261,52 +366,146 @@ pub(crate) enum CodeExecuteError {
 #[derive(Debug)]
 pub(crate) struct ExecutionHandle {
     pub(crate) state: Arc<Mutex<ExecutionState>>,
+    stdin: Arc<Mutex<Option<ChildStdin>>>,
+    control: Arc<ProcessControl>,
     #[allow(dead_code)]
     reader_handle: thread::JoinHandle<()>,
 }
 
+impl ExecutionHandle {
+    /// Kill the running process, if any.
+    pub(crate) fn kill(&self) {
+        self.control.stop(ProcessStatus::Killed);
+    }
+
+    /// Whether the process is waiting for input to be sent via [ExecutionHandle::send_input].
+    pub(crate) fn accepts_input(&self) -> bool {
+        self.stdin.lock().unwrap().is_some()
+    }
+
+    /// Send input to the process' standard input.
+    pub(crate) fn send_input(&self, input: ProcessInput) {
+        let mut stdin = self.stdin.lock().unwrap();
+        let line = match input {
+            ProcessInput::Line(line) => line,
+            ProcessInput::End => {
+                *stdin = None;
+                return;
+            }
+        };
+        let Some(pipe) = stdin.as_mut() else {
+            return;
+        };
+        // Input written into a pipe isn't echoed so add it to the output as a terminal would.
+        self.state.lock().unwrap().output.extend(format!("{line}\n").into_bytes());
+        if writeln!(pipe, "{line}").and_then(|_| pipe.flush()).is_err() {
+            *stdin = None;
+        }
+    }
+}
+
+/// Input typed by the presenter for a running process.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum ProcessInput {
+    /// A line of text.
+    Line(String),
+
+    /// The end of the input, which closes the process' standard input.
+    End,
+}
+
+/// Where the standard input of the last command that runs a snippet comes from.
+struct InputSource {
+    contents: Option<String>,
+    interactive: bool,
+}
+
+/// Allows stopping the process being run for a snippet.
+#[derive(Debug, Default)]
+struct ProcessControl {
+    child: Mutex<Option<Child>>,
+    stop_reason: Mutex<Option<ProcessStatus>>,
+}
+
+impl ProcessControl {
+    fn stop(&self, reason: ProcessStatus) {
+        self.stop_reason.lock().unwrap().get_or_insert(reason);
+        if let Some(child) = self.child.lock().unwrap().as_mut() {
+            Self::kill(child);
+        }
+    }
+
+    fn stop_reason(&self) -> Option<ProcessStatus> {
+        *self.stop_reason.lock().unwrap()
+    }
+
+    #[cfg(unix)]
+    fn kill(child: &mut Child) {
+        // Processes are spawned in their own group so this takes down anything they spawned as well, which would
+        // otherwise keep the output pipe open.
+        let Ok(pid) = libc::pid_t::try_from(child.id()) else {
+            return;
+        };
+        unsafe {
+            libc::kill(-pid, libc::SIGKILL);
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn kill(child: &mut Child) {
+        let _ = child.kill();
+    }
+}
+
 /// Consumes the output of a process and stores it in a shared state.
 struct CommandsRunner {
     state: Arc<Mutex<ExecutionState>>,
+    stdin: Arc<Mutex<Option<ChildStdin>>>,
+    control: Arc<ProcessControl>,
     script_directory: TempDir,
+    env: HashMap<String, String>,
+    cwd: PathBuf,
+    output_type: OutputType,
 }
 
 impl CommandsRunner {
-    fn spawn(
-        state: Arc<Mutex<ExecutionState>>,
-        script_directory: TempDir,
-        commands: Vec<Vec<String>>,
-        env: HashMap<String, String>,
-        cwd: PathBuf,
-        output_type: OutputType,
-    ) -> thread::JoinHandle<()> {
-        let reader = Self { state, script_directory };
-        thread::spawn(move || reader.run(commands, env, cwd, output_type))
+    fn spawn(self, commands: Vec<Vec<String>>, input: InputSource, limits: ExecutionLimits) -> thread::JoinHandle<()> {
+        thread::spawn(move || self.run(commands, input, limits))
     }
 
-    fn run(self, commands: Vec<Vec<String>>, env: HashMap<String, String>, cwd: PathBuf, output_type: OutputType) {
+    fn run(self, commands: Vec<Vec<String>>, input: InputSource, limits: ExecutionLimits) {
         let mut last_result = true;
-        for command in commands {
-            last_result = self.run_command(command, &env, &cwd, output_type);
+        let mut input = Some(input);
+        let total_commands = commands.len();
+        for (index, command) in commands.into_iter().enumerate() {
+            if self.control.stop_reason().is_some() {
+                break;
+            }
+            // Only the last command actually runs the snippet, the rest are things like compiling it, so that's the
+            // only one that's limited.
+            let (input, limits) = match index == total_commands - 1 {
+                true => (input.take(), limits.clone()),
+                false => (None, ExecutionLimits::default()),
+            };
+            last_result = self.run_command(command, input, limits);
             if !last_result {
                 break;
             }
         }
-        let status = match last_result {
-            true => ProcessStatus::Success,
-            false => ProcessStatus::Failure,
+        let status = match (self.control.stop_reason(), last_result) {
+            (Some(reason), _) => reason,
+            (None, true) => ProcessStatus::Success,
+            (None, false) => ProcessStatus::Failure,
         };
+        // The input feeding thread checks the status while holding this lock, so it can't store the pipe after this.
+        let mut stdin = self.stdin.lock().unwrap();
+        *stdin = None;
         self.state.lock().unwrap().status = status;
     }
 
-    fn run_command(
-        &self,
-        command: Vec<String>,
-        env: &HashMap<String, String>,
-        cwd: &Path,
-        output_type: OutputType,
-    ) -> bool {
-        let (mut child, reader) = match self.launch_process(command, env, cwd) {
+    fn run_command(&self, command: Vec<String>, input: Option<InputSource>, limits: ExecutionLimits) -> bool {
+        let input = input.filter(|input| input.contents.is_some() || input.interactive);
+        let (mut child, reader) = match self.launch_process(command, input.is_some(), &limits) {
             Ok(inner) => inner,
             Err(e) => {
                 let mut state = self.state.lock().unwrap();
@@ -315,43 +514,130 @@ impl CommandsRunner {
                 return false;
             }
         };
-        let _ = Self::process_output(self.state.clone(), reader, output_type);
+        if let (Some(input), Some(pipe)) = (input, child.stdin.take()) {
+            self.feed_input(input, pipe);
+        }
+        *self.control.child.lock().unwrap() = Some(child);
+        // The process could have been stopped while it was being launched.
+        if let Some(reason) = self.control.stop_reason() {
+            self.control.stop(reason);
+        }
+
+        // The watchdog is notified when the sender is dropped, at the end of this function.
+        let (_sender, receiver) = mpsc::channel::<()>();
+        if let Some(timeout) = limits.timeout {
+            let control = self.control.clone();
+            thread::spawn(move || {
+                if let Err(RecvTimeoutError::Timeout) = receiver.recv_timeout(timeout) {
+                    control.stop(ProcessStatus::TimedOut);
+                }
+            });
+        }
+        let _ = Self::process_output(self.state.clone(), reader, self.output_type);
+        self.wait_process()
+    }
 
-        match child.wait() {
-            Ok(code) => code.success(),
-            _ => false,
+    fn wait_process(&self) -> bool {
+        // The child is polled rather than waited on so it can be killed in the meantime.
+        loop {
+            let mut child = self.control.child.lock().unwrap();
+            let result = match child.as_mut().map(Child::try_wait) {
+                Some(Ok(Some(status))) => status.success(),
+                Some(Ok(None)) => {
+                    drop(child);
+                    thread::sleep(Duration::from_millis(10));
+                    continue;
+                }
+                _ => false,
+            };
+            *child = None;
+            return result;
         }
     }
 
+    // This writes into the process' input in a separate thread as the pipe could fill up if the process isn't
+    // reading from it, and we need to consume its output meanwhile.
+    fn feed_input(&self, input: InputSource, mut pipe: ChildStdin) {
+        let stdin = self.stdin.clone();
+        let state = self.state.clone();
+        thread::spawn(move || {
+            if let Some(contents) = &input.contents {
+                if pipe.write_all(contents.as_bytes()).and_then(|_| pipe.flush()).is_err() {
+                    return;
+                }
+            }
+            // Otherwise the pipe is dropped here, which closes it.
+            if input.interactive {
+                let mut stdin = stdin.lock().unwrap();
+                // The process could have finished while its input was being written.
+                if !state.lock().unwrap().status.is_finished() {
+                    *stdin = Some(pipe);
+                }
+            }
+        });
+    }
+
     fn launch_process(
         &self,
         mut commands: Vec<String>,
-        env: &HashMap<String, String>,
-        cwd: &Path,
+        piped_stdin: bool,
+        limits: &ExecutionLimits,
     ) -> Result<(Child, PipeReader), CodeExecuteError> {
         let (reader, writer) = os_pipe::pipe().map_err(CodeExecuteError::Pipe)?;
         let writer_clone = writer.try_clone().map_err(CodeExecuteError::Pipe)?;
         let script_dir = self.script_directory.path().to_string_lossy();
 
         #[cfg(windows)]
-        let cwd = strip_drive_unc_prefix(cwd);
+        let cwd = strip_drive_unc_prefix(&self.cwd);
+        #[cfg(not(windows))]
+        let cwd = &self.cwd;
 
         for command in &mut commands {
             *command = command.replace("$pwd", &script_dir);
         }
         let (command, args) = commands.split_first().expect("no commands");
-        let child = process::Command::new(command)
+        let mut process = process::Command::new(command);
+        process
             .args(args)
-            .envs(env)
+            .envs(&self.env)
             .current_dir(cwd)
-            .stdin(Stdio::null())
+            .stdin(if piped_stdin { Stdio::piped() } else { Stdio::null() })
             .stdout(writer)
-            .stderr(writer_clone)
-            .spawn()
-            .map_err(|e| CodeExecuteError::SpawnProcess(command.clone(), e))?;
+            .stderr(writer_clone);
+        Self::apply_limits(&mut process, limits);
+        let child = process.spawn().map_err(|e| CodeExecuteError::SpawnProcess(command.clone(), e))?;
         Ok((child, reader))
     }
 
+    #[cfg(unix)]
+    fn apply_limits(process: &mut process::Command, limits: &ExecutionLimits) {
+        use std::os::unix::process::CommandExt;
+
+        process.process_group(0);
+        let memory = limits.memory;
+        let cpu_time = limits.cpu_time.map(cpu_time_seconds);
+        if memory.is_none() && cpu_time.is_none() {
+            return;
+        }
+        let make_limit = |value: u64| libc::rlimit { rlim_cur: value as libc::rlim_t, rlim_max: value as libc::rlim_t };
+        let check = |result: libc::c_int| if result == 0 { Ok(()) } else { Err(io::Error::last_os_error()) };
+        // SAFETY: `setrlimit` is async-signal-safe so it can be called between forking and executing the process.
+        unsafe {
+            process.pre_exec(move || {
+                if let Some(memory) = memory {
+                    check(libc::setrlimit(libc::RLIMIT_AS, &make_limit(memory)))?;
+                }
+                if let Some(seconds) = cpu_time {
+                    check(libc::setrlimit(libc::RLIMIT_CPU, &make_limit(seconds)))?;
+                }
+                Ok(())
+            });
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn apply_limits(_process: &mut process::Command, _limits: &ExecutionLimits) {}
+
     fn process_output(
         state: Arc<Mutex<ExecutionState>>,
         mut reader: os_pipe::PipeReader,
@@ -377,6 +663,13 @@ impl CommandsRunner {
     }
 }
 
+// CPU time limits can only be set in whole seconds so round up rather than cutting snippets short.
+#[cfg(unix)]
+fn cpu_time_seconds(time: Duration) -> u64 {
+    let seconds = time.as_secs() + u64::from(time.subsec_nanos() > 0);
+    seconds.max(1)
+}
+
 #[derive(Clone, Copy)]
 enum OutputType {
     Lines,
@@ -397,19 +690,25 @@ pub(crate) enum ProcessStatus {
     Running,
     Success,
     Failure,
+    TimedOut,
+    Killed,
 }
 
 impl ProcessStatus {
     /// Check whether the underlying process is finished.
     pub(crate) fn is_finished(&self) -> bool {
-        matches!(self, ProcessStatus::Success | ProcessStatus::Failure)
+        !matches!(self, ProcessStatus::Running)
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::code::snippet::{SnippetAttributes, SnippetExecution};
+    use crate::{
+        code::snippet::{SnippetAttributes, SnippetExecution},
+        utils::{ByteSize, HumanDuration},
+    };
+    use rstest::rstest;
 
     #[test]
     fn shell_code_execution() {
@@ -501,6 +800,249 @@ echo 'hello world'
         assert_eq!(state.output, expected);
     }
 
+    #[test]
+    fn shell_code_execution_reads_stdin() {
+        let snippet = Snippet {
+            contents: "read a\nread b\necho \"$b $a\"".into(),
+            language: SnippetLanguage::Shell,
+            attributes: SnippetAttributes {
+                execution: SnippetExecution::Exec(Default::default()),
+                interactive: true,
+                ..Default::default()
+            },
+        };
+        let mut executor = SnippetExecutor::default()
+            .language_executor(&snippet.language, &Default::default(), Default::default())
+            .unwrap();
+        executor.set_stdin("hello\n".into());
+        let handle = executor.execute_async(&snippet).expect("execution failed");
+        while !handle.accepts_input() {
+            thread::yield_now();
+        }
+        handle.send_input(ProcessInput::Line("world".into()));
+        let state = loop {
+            let state = handle.state.lock().unwrap();
+            if state.status.is_finished() {
+                break state;
+            }
+        };
+
+        // The line typed is echoed into the output.
+        let expected = b"world\nworld hello\n";
+        assert_eq!(state.output, expected);
+        assert!(!handle.accepts_input());
+    }
+
+    #[test]
+    fn finished_process_rejects_input() {
+        let snippet = Snippet {
+            contents: "echo hi".into(),
+            language: SnippetLanguage::Shell,
+            attributes: SnippetAttributes {
+                execution: SnippetExecution::Exec(Default::default()),
+                interactive: true,
+                ..Default::default()
+            },
+        };
+        let mut executor = SnippetExecutor::default()
+            .language_executor(&snippet.language, &Default::default(), Default::default())
+            .unwrap();
+        executor.set_stdin("hello\n".into());
+        let handle = executor.execute_async(&snippet).expect("execution failed");
+        while !handle.state.lock().unwrap().status.is_finished() {
+            thread::yield_now();
+        }
+        // Give the thread feeding the input time to finish.
+        thread::sleep(Duration::from_millis(50));
+        assert!(!handle.accepts_input());
+    }
+
+    #[test]
+    fn shell_code_execution_times_out() {
+        let snippet = Snippet {
+            contents: "echo 'hello'\nsleep 10\necho 'bye'".into(),
+            language: SnippetLanguage::Shell,
+            attributes: SnippetAttributes {
+                execution: SnippetExecution::Exec(Default::default()),
+                ..Default::default()
+            },
+        };
+        let mut executor = SnippetExecutor::default()
+            .language_executor(&snippet.language, &Default::default(), Default::default())
+            .unwrap();
+        executor.set_timeout(Duration::from_millis(200));
+        let handle = executor.execute_async(&snippet).expect("execution failed");
+        let state = loop {
+            let state = handle.state.lock().unwrap();
+            if state.status.is_finished() {
+                break state;
+            }
+        };
+
+        assert!(matches!(state.status, ProcessStatus::TimedOut), "{:?}", state.status);
+        assert_eq!(state.output, b"hello\n");
+    }
+
+    #[test]
+    fn shell_code_execution_killed() {
+        let snippet = Snippet {
+            contents: "echo 'hello'\nsleep 10".into(),
+            language: SnippetLanguage::Shell,
+            attributes: SnippetAttributes {
+                execution: SnippetExecution::Exec(Default::default()),
+                ..Default::default()
+            },
+        };
+        let executor = SnippetExecutor::default()
+            .language_executor(&snippet.language, &Default::default(), Default::default())
+            .unwrap();
+        let handle = executor.execute_async(&snippet).expect("execution failed");
+        while handle.state.lock().unwrap().output.is_empty() {
+            thread::yield_now();
+        }
+        handle.kill();
+        let state = loop {
+            let state = handle.state.lock().unwrap();
+            if state.status.is_finished() {
+                break state;
+            }
+        };
+
+        assert!(matches!(state.status, ProcessStatus::Killed), "{:?}", state.status);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn shell_code_execution_resource_limits() {
+        let snippet = Snippet {
+            contents: "ulimit -v\nulimit -t".into(),
+            language: SnippetLanguage::Shell,
+            attributes: SnippetAttributes {
+                execution: SnippetExecution::Exec(Default::default()),
+                ..Default::default()
+            },
+        };
+        let limits = SnippetExecutionLimitsConfig {
+            memory: Some(ByteSize(512 << 20)),
+            cpu_time: Some(HumanDuration(Duration::from_secs(2))),
+            ..Default::default()
+        };
+        let executor = SnippetExecutor::default()
+            .with_limits(limits, Default::default())
+            .language_executor(&snippet.language, &Default::default(), Default::default())
+            .unwrap();
+        let handle = executor.execute_async(&snippet).expect("execution failed");
+        let state = loop {
+            let state = handle.state.lock().unwrap();
+            if state.status.is_finished() {
+                break state;
+            }
+        };
+
+        // `ulimit -v` reports the limit in KiB.
+        assert_eq!(state.output, b"524288\n2\n");
+    }
+
+    #[rstest]
+    #[case::enabled(true, BTreeMap::new(), b"sandboxed\n")]
+    #[case::disabled(false, BTreeMap::new(), b"\n")]
+    #[case::language_enabled(false, BTreeMap::from([(SnippetLanguage::Shell, true)]), b"sandboxed\n")]
+    #[case::language_disabled(true, BTreeMap::from([(SnippetLanguage::Shell, false)]), b"\n")]
+    fn sandbox(#[case] enable: bool, #[case] languages: BTreeMap<SnippetLanguage, bool>, #[case] expected: &[u8]) {
+        let snippet = Snippet {
+            contents: "echo \"$SANDBOX\"".into(),
+            language: SnippetLanguage::Shell,
+            attributes: SnippetAttributes {
+                execution: SnippetExecution::Exec(Default::default()),
+                ..Default::default()
+            },
+        };
+        let sandbox =
+            SnippetSandboxConfig { enable, languages, runner: vec!["env".into(), "SANDBOX=sandboxed".into()] };
+        let executor = SnippetExecutor::default()
+            .with_sandbox(sandbox)
+            .language_executor(&snippet.language, &Default::default(), Default::default())
+            .unwrap();
+        let handle = executor.execute_async(&snippet).expect("execution failed");
+        let state = loop {
+            let state = handle.state.lock().unwrap();
+            if state.status.is_finished() {
+                break state;
+            }
+        };
+
+        assert_eq!(state.output, expected);
+    }
+
+    #[test]
+    fn sandbox_runner_home() {
+        let snippet = Snippet {
+            contents: "echo $SANDBOX_HOME".into(),
+            language: SnippetLanguage::Shell,
+            attributes: SnippetAttributes {
+                execution: SnippetExecution::Exec(Default::default()),
+                ..Default::default()
+            },
+        };
+        let runner = vec!["env".into(), "SANDBOX_HOME=$home".into()];
+        let sandbox = SnippetSandboxConfig { enable: true, runner, ..Default::default() };
+        let executor = SnippetExecutor::default()
+            .with_sandbox(sandbox)
+            .language_executor(&snippet.language, &Default::default(), Default::default())
+            .unwrap();
+        let handle = executor.execute_async(&snippet).expect("execution failed");
+        let state = loop {
+            let state = handle.state.lock().unwrap();
+            if state.status.is_finished() {
+                break state;
+            }
+        };
+
+        let expected = format!("{}\n", env::var("HOME").expect("no home"));
+        assert_eq!(state.output, expected.as_bytes());
+    }
+
+    #[test]
+    fn empty_sandbox_runner() {
+        let sandbox = SnippetSandboxConfig { enable: true, runner: Vec::new(), ..Default::default() };
+        SnippetExecutor::default()
+            .with_sandbox(sandbox)
+            .language_executor(&SnippetLanguage::Shell, &Default::default(), Default::default())
+            .expect_err("executor built");
+    }
+
+    #[test]
+    fn language_limits() {
+        let limits = SnippetExecutionLimitsConfig {
+            timeout: Some(HumanDuration(Duration::from_secs(5))),
+            memory: Some(ByteSize(1024)),
+            cpu_time: None,
+        };
+        let python_limits = SnippetExecutionLimitsConfig {
+            timeout: Some(HumanDuration(Duration::from_secs(10))),
+            ..Default::default()
+        };
+        let executor =
+            SnippetExecutor::default().with_limits(limits, BTreeMap::from([(SnippetLanguage::Python, python_limits)]));
+
+        let expected =
+            ExecutionLimits { timeout: Some(Duration::from_secs(10)), memory: Some(1024), ..Default::default() };
+        assert_eq!(executor.limits(&SnippetLanguage::Python), expected);
+        let expected =
+            ExecutionLimits { timeout: Some(Duration::from_secs(5)), memory: Some(1024), ..Default::default() };
+        assert_eq!(executor.limits(&SnippetLanguage::Shell), expected);
+    }
+
+    #[cfg(unix)]
+    #[rstest]
+    #[case::zero(Duration::ZERO, 1)]
+    #[case::below_second(Duration::from_millis(500), 1)]
+    #[case::whole(Duration::from_secs(2), 2)]
+    #[case::fractional(Duration::from_millis(1500), 2)]
+    fn cpu_time_rounding(#[case] time: Duration, #[case] expected: u64) {
+        assert_eq!(cpu_time_seconds(time), expected);
+    }
+
     #[test]
     fn built_in_executors() {
         SnippetExecutor::new(Default::default(), PathBuf::from("./")).expect("invalid default executors");
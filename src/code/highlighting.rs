@@ -12,9 +12,10 @@ use serde::Deserialize;
 use std::{cell::RefCell, collections::BTreeMap, fs, path::Path, rc::Rc};
 use syntect::{
     LoadingError,
-    easy::HighlightLines,
+    easy::{HighlightLines, ScopeRangeIterator},
     highlighting::{Style, Theme, ThemeSet},
-    parsing::SyntaxSet,
+    parsing::{ParseState, Scope, ScopeStack, SyntaxSet},
+    util::LinesWithEndings,
 };
 
 static SYNTAX_SET: Lazy<SyntaxSet> = Lazy::new(|| {
@@ -100,6 +101,40 @@ impl SnippetHighlighter {
         LanguageHighlighter::new(language.clone(), highlighter)
     }
 
+    /// Parse some code and split every line into tokens along with the syntax scopes that apply to them.
+    ///
+    /// Parsing stops at the first line that can't be parsed.
+    pub(crate) fn scoped_lines<'a>(code: &'a str, language: &SnippetLanguage) -> Vec<Vec<ScopedToken<'a>>> {
+        let extension = Self::language_extension(language);
+        let syntax = SYNTAX_SET.find_syntax_by_extension(extension).unwrap();
+        let mut state = ParseState::new(syntax);
+        let mut stack = ScopeStack::new();
+        if matches!(language, SnippetLanguage::Php) && !code.trim_start().starts_with("<?php") {
+            if let Ok(ops) = state.parse_line("<?php\n", &SYNTAX_SET) {
+                for (_, op) in &ops {
+                    let _ = stack.apply(op);
+                }
+            }
+        }
+        let mut lines = Vec::new();
+        for line in LinesWithEndings::from(code) {
+            let Ok(ops) = state.parse_line(line, &SYNTAX_SET) else {
+                break;
+            };
+            let mut tokens = Vec::new();
+            for (range, op) in ScopeRangeIterator::new(&ops, line) {
+                if stack.apply(op).is_err() {
+                    break;
+                }
+                if !range.is_empty() {
+                    tokens.push(ScopedToken { text: &line[range], scopes: stack.as_slice().to_vec() });
+                }
+            }
+            lines.push(tokens);
+        }
+        lines
+    }
+
     fn language_extension(language: &SnippetLanguage) -> &'static str {
         use SnippetLanguage::*;
         match language {
@@ -222,6 +257,20 @@ impl<'a> LanguageHighlighter<'a> {
     }
 }
 
+/// A piece of a line of code along with the syntax scopes that apply to it.
+#[derive(Debug)]
+pub(crate) struct ScopedToken<'a> {
+    pub(crate) text: &'a str,
+    pub(crate) scopes: Vec<Scope>,
+}
+
+impl ScopedToken<'_> {
+    /// Whether any of the scopes that apply to this token starts with the given one.
+    pub(crate) fn has_scope(&self, scope: Scope) -> bool {
+        self.scopes.iter().any(|s| scope.is_prefix_of(*s))
+    }
+}
+
 pub(crate) struct StyledTokens<'a> {
     pub(crate) style: TextStyle,
     pub(crate) tokens: &'a str,
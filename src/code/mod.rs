@@ -1,4 +1,6 @@
+pub(crate) mod diff;
 pub(crate) mod execute;
 pub(crate) mod highlighting;
 pub(crate) mod padding;
+pub(crate) mod region;
 pub(crate) mod snippet;
@@ -19,6 +19,10 @@ impl NumberPadder {
         output.push_str(&number.to_string());
         output
     }
+
+    pub(crate) fn pad_empty(&self) -> String {
+        " ".repeat(self.width)
+    }
 }
 
 #[cfg(test)]
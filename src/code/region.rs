@@ -0,0 +1,263 @@
+use super::{
+    highlighting::{ScopedToken, SnippetHighlighter},
+    snippet::SnippetLanguage,
+};
+use syntect::parsing::Scope;
+
+/// Extract the lines between a `region: <name>` marker and its matching `endregion` marker.
+///
+/// Markers are expected to be in comments, e.g. `// region: setup` or `# endregion`. Nested regions are allowed and
+/// any markers within the region are left out of the output.
+pub(crate) fn extract_region(code: &str, name: &str) -> Result<String, ExtractError> {
+    let mut lines = code.lines();
+    lines
+        .by_ref()
+        .find(|line| matches!(RegionMarker::parse(line), Some(RegionMarker::Start(n)) if n == name))
+        .ok_or_else(|| ExtractError::RegionNotFound(name.to_string()))?;
+    let mut depth = 0;
+    let mut output = Vec::new();
+    for line in lines {
+        match RegionMarker::parse(line) {
+            Some(RegionMarker::Start(_)) => depth += 1,
+            Some(RegionMarker::End) if depth == 0 => return Ok(dedent(&output)),
+            Some(RegionMarker::End) => depth -= 1,
+            None => output.push(line),
+        }
+    }
+    Err(ExtractError::UnterminatedRegion(name.to_string()))
+}
+
+/// Extract the definition of a function, struct, class, etc, with the given name.
+///
+/// The definition is found by looking for a token the syntax highlighter considers to be an entity name, and ends
+/// where its braces are balanced again or, for languages like python, where its indented body ends. Any comments or
+/// attributes right above the definition are included.
+pub(crate) fn extract_symbol(code: &str, language: &SnippetLanguage, name: &str) -> Result<String, ExtractError> {
+    let lines = SnippetHighlighter::scoped_lines(code, language);
+    let entity_name = Scope::new("entity.name").expect("invalid scope");
+    let start = lines
+        .iter()
+        .position(|tokens| tokens.iter().any(|token| token.text.trim() == name && token.has_scope(entity_name)))
+        .ok_or_else(|| ExtractError::SymbolNotFound(name.to_string()))?;
+    let code_lines: Vec<_> = code.lines().collect();
+    let end = SymbolBounds::find_end(&lines, &code_lines, start);
+    let start = SymbolBounds::find_start(&lines, start);
+    Ok(dedent(&code_lines[start..=end]))
+}
+
+/// An error when extracting a piece of code.
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum ExtractError {
+    #[error("region '{0}' not found")]
+    RegionNotFound(String),
+
+    #[error("region '{0}' has no matching 'endregion'")]
+    UnterminatedRegion(String),
+
+    #[error("symbol '{0}' not found")]
+    SymbolNotFound(String),
+}
+
+#[derive(Debug, PartialEq)]
+enum RegionMarker<'a> {
+    Start(&'a str),
+    End,
+}
+
+impl<'a> RegionMarker<'a> {
+    fn parse(line: &'a str) -> Option<Self> {
+        let line = line.trim();
+        // Markers must be within a comment so require some prefix like `//` or `#`.
+        let text = line.trim_start_matches(|c: char| !c.is_alphanumeric());
+        if text.len() == line.len() {
+            return None;
+        }
+        let text = text.trim_end_matches("*/").trim_end_matches("-->").trim_end();
+        if let Some(rest) = text.strip_prefix("endregion") {
+            return (rest.is_empty() || rest.starts_with([' ', ':'])).then_some(Self::End);
+        }
+        let rest = text.strip_prefix("region")?;
+        if !rest.starts_with([' ', ':']) {
+            return None;
+        }
+        let name = rest.strip_prefix(':').unwrap_or(rest).trim();
+        (!name.is_empty()).then_some(Self::Start(name))
+    }
+}
+
+struct SymbolBounds;
+
+impl SymbolBounds {
+    fn find_end(lines: &[Vec<ScopedToken>], code_lines: &[&str], start: usize) -> usize {
+        let ignored = [Scope::new("comment").expect("invalid scope"), Scope::new("string").expect("invalid scope")];
+        let mut braces = 0;
+        let mut parens = 0;
+        let mut opened = false;
+        for (index, tokens) in lines.iter().enumerate().skip(start) {
+            let mut code = String::new();
+            for token in tokens {
+                if ignored.iter().any(|scope| token.has_scope(*scope)) {
+                    continue;
+                }
+                code.push_str(token.text);
+                for c in token.text.chars() {
+                    match c {
+                        '{' => {
+                            braces += 1;
+                            opened = true;
+                        }
+                        '}' => braces -= 1,
+                        '(' | '[' => parens += 1,
+                        ')' | ']' => parens -= 1,
+                        // Declarations without a body, like `struct Foo;`.
+                        ';' if !opened && parens == 0 => return index,
+                        _ => (),
+                    };
+                }
+            }
+            if opened && braces <= 0 {
+                return index;
+            }
+            if !opened && parens == 0 && code.trim_end().ends_with(':') {
+                return Self::find_indented_end(code_lines, start, index);
+            }
+        }
+        code_lines.len().saturating_sub(1)
+    }
+
+    fn find_indented_end(code_lines: &[&str], start: usize, header_end: usize) -> usize {
+        let indentation = Self::indentation(code_lines[start]);
+        let mut end = header_end;
+        for (index, line) in code_lines.iter().enumerate().skip(header_end + 1) {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if Self::indentation(line) <= indentation {
+                break;
+            }
+            end = index;
+        }
+        end
+    }
+
+    fn find_start(lines: &[Vec<ScopedToken>], start: usize) -> usize {
+        let prefixes =
+            [Scope::new("comment").expect("invalid scope"), Scope::new("meta.annotation").expect("invalid scope")];
+        let is_prefix = |tokens: &Vec<ScopedToken>| {
+            let mut tokens = tokens.iter().filter(|token| !token.text.trim().is_empty()).peekable();
+            tokens.peek().is_some() && tokens.all(|token| prefixes.iter().any(|scope| token.has_scope(*scope)))
+        };
+        let above = lines[..start].iter().rev().take_while(|tokens| is_prefix(tokens)).count();
+        start - above
+    }
+
+    fn indentation(line: &str) -> usize {
+        line.len() - line.trim_start().len()
+    }
+}
+
+fn dedent(lines: &[&str]) -> String {
+    let indentation = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or_default();
+    lines.iter().map(|line| line.get(indentation..).unwrap_or_default()).collect::<Vec<_>>().join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::slashes("// region: setup", Some(RegionMarker::Start("setup")))]
+    #[case::no_colon("# region setup", Some(RegionMarker::Start("setup")))]
+    #[case::vscode("#region setup", Some(RegionMarker::Start("setup")))]
+    #[case::block_comment("/* region: setup */", Some(RegionMarker::Start("setup")))]
+    #[case::html("<!-- region: setup -->", Some(RegionMarker::Start("setup")))]
+    #[case::end("// endregion", Some(RegionMarker::End))]
+    #[case::named_end("// endregion: setup", Some(RegionMarker::End))]
+    #[case::no_name("// region", None)]
+    #[case::not_comment("region = 42", None)]
+    #[case::other_word("// regional", None)]
+    fn region_marker(#[case] line: &str, #[case] expected: Option<RegionMarker>) {
+        assert_eq!(RegionMarker::parse(line), expected);
+    }
+
+    #[test]
+    fn region() {
+        let code = r"
+fn main() {
+    // region: setup
+    let a = 1;
+    // region: inner
+    let b = 2;
+    // endregion
+    // endregion
+    let c = 3;
+}";
+        let region = extract_region(code, "setup").expect("region not found");
+        assert_eq!(region, "let a = 1;\nlet b = 2;");
+        let region = extract_region(code, "inner").expect("region not found");
+        assert_eq!(region, "let b = 2;");
+    }
+
+    #[rstest]
+    #[case::missing("// region: a\n// endregion", "b")]
+    #[case::unterminated("// region: a\nfoo", "a")]
+    fn invalid_region(#[case] code: &str, #[case] name: &str) {
+        extract_region(code, name).expect_err("region found");
+    }
+
+    #[rstest]
+    #[case::function("bar", "/// Docs.\n#[inline]\nfn bar(x: [u8; 2]) -> u8 {\n    x[0] // }\n}")]
+    #[case::unit_struct("Unit", "struct Unit;")]
+    #[case::method("method", "fn method(&self) {\n    let s = \"{\";\n}")]
+    fn rust_symbol(#[case] name: &str, #[case] expected: &str) {
+        let code = r#"
+use std::fmt;
+
+/// Docs.
+#[inline]
+fn bar(x: [u8; 2]) -> u8 {
+    x[0] // }
+}
+
+struct Unit;
+
+impl Unit {
+    fn method(&self) {
+        let s = "{";
+    }
+}
+"#;
+        let symbol = extract_symbol(code, &SnippetLanguage::Rust, name).expect("symbol not found");
+        assert_eq!(symbol, expected);
+    }
+
+    #[test]
+    fn python_symbol() {
+        let code = r"
+@decorator
+def foo(
+    a,
+):
+    return a
+
+    # still foo
+
+def bar():
+    pass
+";
+        let symbol = extract_symbol(code, &SnippetLanguage::Python, "foo").expect("symbol not found");
+        assert_eq!(symbol, "@decorator\ndef foo(\n    a,\n):\n    return a\n\n    # still foo");
+    }
+
+    #[test]
+    fn symbol_not_found() {
+        let code = "fn main() {\n    bar();\n}";
+        extract_symbol(code, &SnippetLanguage::Rust, "bar").expect_err("symbol found");
+    }
+}
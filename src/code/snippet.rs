@@ -1,4 +1,5 @@
 use super::{
+    diff::{DiffOp, diff},
     highlighting::{LanguageHighlighter, StyledTokens},
     padding::NumberPadder,
 };
@@ -14,9 +15,12 @@ use crate::{
         properties::WindowSize,
     },
     theme::{Alignment, CodeBlockStyle},
+    utils::{HumanDuration, ParseDurationError},
 };
 use serde::Deserialize;
-use std::{cell::RefCell, convert::Infallible, fmt::Write, ops::Range, path::PathBuf, rc::Rc, str::FromStr};
+use std::{
+    cell::RefCell, convert::Infallible, fmt::Write, ops::Range, path::PathBuf, rc::Rc, str::FromStr, time::Duration,
+};
 use strum::{EnumDiscriminants, EnumIter};
 use unicode_width::UnicodeWidthStr;
 
@@ -61,21 +65,85 @@ impl<'a> SnippetSplitter<'a> {
             }
             line.push('\n');
             let line_number = Some(index as u16 + 1);
-            lines.push(SnippetLine { prefix, code: line, right_padding_length: padding.len() as u16, line_number });
+            lines.push(SnippetLine {
+                prefix,
+                code: line,
+                right_padding_length: padding.len() as u16,
+                line_number,
+                change: None,
+            });
+        }
+    }
+
+    /// Split a snippet into lines, displaying it as a diff against a previous version of it.
+    ///
+    /// Every line is prefixed with a marker that indicates whether it was added, removed, or left unchanged.
+    pub(crate) fn split_diff(&self, before: &Snippet, after: &Snippet) -> Vec<SnippetLine> {
+        let mut lines = Vec::new();
+        let vertical_padding = self.style.padding.vertical;
+        if vertical_padding > 0 {
+            lines.push(SnippetLine::empty());
+        }
+        let padding = " ".repeat(self.style.padding.horizontal as usize);
+        let before_lines: Vec<_> = before.visible_lines(self.hidden_line_prefix).collect();
+        let after_lines: Vec<_> = after.visible_lines(self.hidden_line_prefix).collect();
+        let padder = NumberPadder::new(after_lines.len());
+        for op in diff(&before_lines, &after_lines) {
+            let (line, index, change) = match op {
+                DiffOp::Unchanged { after, .. } => (after_lines[after], Some(after), None),
+                DiffOp::Added(index) => (after_lines[index], Some(index), Some(LineChange::Added)),
+                DiffOp::Removed(index) => (before_lines[index], None, Some(LineChange::Removed)),
+            };
+            let marker = match change {
+                Some(LineChange::Added) => '+',
+                Some(LineChange::Removed) => '-',
+                None => ' ',
+            };
+            let mut prefix = format!("{padding}{marker} ");
+            if after.attributes.line_numbers {
+                match index {
+                    Some(index) => prefix.push_str(&padder.pad_right(index + 1)),
+                    None => prefix.push_str(&padder.pad_empty()),
+                };
+                prefix.push(' ');
+            }
+            let mut line = line.replace('\t', "    ");
+            line.push('\n');
+            // Removed lines don't have a line number so they're always dimmed.
+            let line_number = index.map(|index| index as u16 + 1);
+            lines.push(SnippetLine {
+                prefix,
+                code: line,
+                right_padding_length: padding.len() as u16,
+                line_number,
+                change,
+            });
+        }
+        if vertical_padding > 0 {
+            lines.push(SnippetLine::empty());
         }
+        lines
     }
 }
 
+/// The way a line changed when displaying a snippet as a diff.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum LineChange {
+    Added,
+    Removed,
+}
+
 pub(crate) struct SnippetLine {
     pub(crate) prefix: String,
     pub(crate) code: String,
     pub(crate) right_padding_length: u16,
     pub(crate) line_number: Option<u16>,
+    pub(crate) change: Option<LineChange>,
 }
 
 impl SnippetLine {
     pub(crate) fn empty() -> Self {
-        Self { prefix: String::new(), code: "\n".into(), right_padding_length: 0, line_number: None }
+        Self { prefix: String::new(), code: "\n".into(), right_padding_length: 0, line_number: None, change: None }
     }
 
     pub(crate) fn width(&self) -> usize {
@@ -86,10 +154,10 @@ impl SnippetLine {
         &self,
         code_highlighter: &mut LanguageHighlighter,
         block_style: &CodeBlockStyle,
-        font_size: u8,
+        style: &TextStyle,
     ) -> WeightedLine {
         let mut line = code_highlighter.style_line(&self.code, block_style);
-        line.apply_style(&TextStyle::default().size(font_size));
+        line.apply_style(style);
         line.into()
     }
 
@@ -263,6 +331,9 @@ impl SnippetParser {
                 Id(id) => {
                     attributes.id = Some(id);
                 }
+                DiffFrom(id) => {
+                    attributes.diff_from = Some(id);
+                }
                 Validate(spec) => {
                     if attributes.validate.is_some() {
                         return Err(SnippetBlockParseError::DuplicateAttribute("+validate"));
@@ -290,6 +361,13 @@ impl SnippetParser {
                 Width(width) => attributes.width = Some(width),
                 ExpectedExecutionResult(result) => attributes.expected_execution_result = result,
                 Env(path) => attributes.env_file = Some(path),
+                Stdin(_) | StdinFrom(_) if attributes.stdin.is_some() => {
+                    return Err(SnippetBlockParseError::DuplicateAttribute("+stdin"));
+                }
+                Stdin(path) => attributes.stdin = Some(SnippetStdin::File(path)),
+                StdinFrom(id) => attributes.stdin = Some(SnippetStdin::Snippet(id)),
+                Interactive => attributes.interactive = true,
+                Timeout(timeout) => attributes.timeout = Some(timeout),
             };
             processed_attributes.push(discriminant);
             input = rest;
@@ -316,6 +394,7 @@ impl SnippetParser {
                     "no_background" => SnippetAttribute::NoBackground,
                     "acquire_terminal" => SnippetAttribute::AcquireTerminal(SnippetExecutorSpec::default()),
                     "pty" => SnippetAttribute::ExecPty(SnippetExecutorSpec::default(), Default::default()),
+                    "interactive" => SnippetAttribute::Interactive,
                     other => {
                         let (attribute, parameter) = other
                             .split_once(':')
@@ -329,6 +408,7 @@ impl SnippetParser {
                                 SnippetAttribute::ExecReplace(SnippetExecutorSpec::Alternative(parameter.to_string()))
                             }
                             "id" => SnippetAttribute::Id(parameter.to_string()),
+                            "diff_from" => SnippetAttribute::DiffFrom(parameter.to_string()),
                             "validate" => {
                                 SnippetAttribute::Validate(SnippetExecutorSpec::Alternative(parameter.to_string()))
                             }
@@ -354,6 +434,13 @@ impl SnippetParser {
                             },
                             "pty" => SnippetAttribute::ExecPty(SnippetExecutorSpec::default(), parameter.parse()?),
                             "env" => SnippetAttribute::Env(parameter.to_string().into()),
+                            "stdin" => SnippetAttribute::Stdin(parameter.to_string().into()),
+                            "stdin_from" => SnippetAttribute::StdinFrom(parameter.to_string()),
+                            "timeout" => {
+                                let timeout: HumanDuration =
+                                    parameter.parse().map_err(SnippetBlockParseError::InvalidTimeout)?;
+                                SnippetAttribute::Timeout(timeout.0)
+                            }
                             _ => return Err(SnippetBlockParseError::InvalidToken(Self::next_identifier(input).into())),
                         }
                     }
@@ -444,6 +531,9 @@ pub enum SnippetBlockParseError {
     #[error("invalid width: {0}")]
     InvalidWidth(PercentParseError),
 
+    #[error("invalid timeout: {0}")]
+    InvalidTimeout(ParseDurationError),
+
     #[error("invalid pty args, expected '[standby:]<columns>:<rows>'")]
     InvalidPtyArgs,
 
@@ -473,7 +563,12 @@ enum SnippetAttribute {
     AcquireTerminal(SnippetExecutorSpec),
     ExpectedExecutionResult(ExpectedSnippetExecutionResult),
     Id(String),
+    DiffFrom(String),
     Env(PathBuf),
+    Stdin(PathBuf),
+    StdinFrom(String),
+    Interactive,
+    Timeout(Duration),
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
@@ -721,8 +816,30 @@ pub(crate) struct SnippetAttributes {
     /// The identifier for a snippet.
     pub(crate) id: Option<String>,
 
+    /// The identifier of the snippet this one should be displayed as a diff against.
+    pub(crate) diff_from: Option<String>,
+
     /// The environment file to load.
     pub(crate) env_file: Option<PathBuf>,
+
+    /// Where to read the input for this snippet's execution from.
+    pub(crate) stdin: Option<SnippetStdin>,
+
+    /// Whether lines can be typed into the snippet's standard input while it runs.
+    pub(crate) interactive: bool,
+
+    /// How long the snippet can run for before it's killed.
+    pub(crate) timeout: Option<Duration>,
+}
+
+/// The source of the input for a snippet's execution.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum SnippetStdin {
+    /// A file.
+    File(PathBuf),
+
+    /// The contents of the snippet with the given identifier.
+    Snippet(String),
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
@@ -850,6 +967,8 @@ pub(crate) struct ExternalFile {
     pub(crate) language: SnippetLanguage,
     pub(crate) start_line: Option<usize>,
     pub(crate) end_line: Option<usize>,
+    pub(crate) region: Option<String>,
+    pub(crate) symbol: Option<String>,
 }
 
 #[cfg(test)]
@@ -897,6 +1016,64 @@ mod test {
         }
     }
 
+    #[test]
+    fn diff_lines() {
+        let before = Snippet {
+            contents: "a\nb\nc\n".into(),
+            language: SnippetLanguage::Unknown("".to_string()),
+            attributes: Default::default(),
+        };
+        let after = Snippet {
+            contents: "a\nx\nc\n".into(),
+            language: SnippetLanguage::Unknown("".to_string()),
+            attributes: SnippetAttributes { line_numbers: true, ..Default::default() },
+        };
+        let lines = SnippetSplitter::new(&Default::default(), None).split_diff(&before, &after);
+        let lines: Vec<_> = lines.iter().map(|line| (line.prefix.as_str(), line.code.as_str(), line.change)).collect();
+        let expected = &[
+            ("  1 ", "a\n", None),
+            ("-   ", "b\n", Some(LineChange::Removed)),
+            ("+ 2 ", "x\n", Some(LineChange::Added)),
+            ("  3 ", "c\n", None),
+        ];
+        assert_eq!(lines, expected);
+    }
+
+    #[rstest]
+    #[case::file("bash +exec +stdin:input.txt", Some(SnippetStdin::File("input.txt".into())), false)]
+    #[case::snippet("bash +exec +stdin_from:foo", Some(SnippetStdin::Snippet("foo".into())), false)]
+    #[case::interactive("bash +exec +interactive", None, true)]
+    fn stdin_attributes(#[case] input: &str, #[case] stdin: Option<SnippetStdin>, #[case] interactive: bool) {
+        let attributes = parse_attributes(input);
+        assert_eq!(attributes.stdin, stdin);
+        assert_eq!(attributes.interactive, interactive);
+    }
+
+    #[rstest]
+    #[case::seconds("bash +exec +timeout:5s", Duration::from_secs(5))]
+    #[case::millis("bash +exec +timeout:500ms", Duration::from_millis(500))]
+    #[case::no_unit("bash +exec +timeout:2", Duration::from_secs(2))]
+    fn timeout(#[case] input: &str, #[case] expected: Duration) {
+        let attributes = parse_attributes(input);
+        assert_eq!(attributes.timeout, Some(expected));
+    }
+
+    #[test]
+    fn invalid_timeout() {
+        try_parse_attributes("bash +exec +timeout:5x").expect_err("parse succeeded");
+    }
+
+    #[test]
+    fn multiple_stdin() {
+        try_parse_attributes("bash +exec +stdin:input.txt +stdin_from:foo").expect_err("parse succeeded");
+    }
+
+    #[test]
+    fn diff_from() {
+        let attributes = parse_attributes("rust +diff_from:foo");
+        assert_eq!(attributes.diff_from.as_deref(), Some("foo"));
+    }
+
     #[test]
     fn unknown_language() {
         assert_eq!(parse_language("potato"), SnippetLanguage::Unknown("potato".to_string()));
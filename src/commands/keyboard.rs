@@ -1,17 +1,65 @@
 use super::listener::{Command, CommandDiscriminants};
-use crate::config::KeyBindingsConfig;
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, poll, read};
-use std::{fmt, io, iter, mem, str::FromStr, time::Duration};
+use crate::{config::KeyBindingsConfig, terminal::appearance::Appearance};
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind, poll, read,
+};
+use std::{
+    fmt, io, iter, mem,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+// The longest reply to a terminal query that we expect.
+const MAX_TERMINAL_REPLY_LENGTH: usize = 64;
+
+// How long to wait for a reply to a terminal query before treating input as regular key presses again.
+const TERMINAL_REPLY_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How key presses are interpreted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum InputMode {
+    /// Key presses are matched against key bindings.
+    #[default]
+    Bindings,
+
+    /// Like [InputMode::Bindings] but the bindings that move between search matches take precedence.
+    Searching,
+
+    /// Arrow keys move a selection around and enter confirms it. Any other key is matched against key bindings.
+    Navigation,
+
+    /// Arrow keys and the mouse move a pointer around and other keys draw with it. Any other key is matched against
+    /// key bindings.
+    Annotating,
+
+    /// Key presses are treated as text being typed.
+    TextInput,
+}
 
 /// A keyboard command listener.
 pub struct KeyboardListener {
     bindings: CommandKeyBindings,
     events: Vec<KeyEvent>,
+    mode: InputMode,
+    reply: TerminalReply,
 }
 
 impl KeyboardListener {
     pub fn new(bindings: CommandKeyBindings) -> Self {
-        Self { bindings, events: Vec::new() }
+        Self { bindings, events: Vec::new(), mode: Default::default(), reply: Default::default() }
+    }
+
+    /// Set how key presses are interpreted.
+    pub(crate) fn set_input_mode(&mut self, mode: InputMode) {
+        if self.mode != mode {
+            self.mode = mode;
+            self.events.clear();
+        }
+    }
+
+    /// Treat the next input that looks like a reply to a terminal query as such.
+    pub(crate) fn expect_terminal_reply(&mut self) {
+        self.reply.expect();
     }
 
     /// Polls for the next input command coming from the keyboard.
@@ -21,10 +69,46 @@ impl KeyboardListener {
 
     /// Blocks waiting for the next command.
     pub(crate) fn next_command(&mut self) -> io::Result<Option<Command>> {
+        let event = read()?;
+        Ok(self.process_event(event))
+    }
+
+    fn process_event(&mut self, event: Event) -> Option<Command> {
+        if let Event::Key(event) = &event {
+            match self.reply.feed(event) {
+                ReplyProgress::Unrelated => (),
+                ReplyProgress::Pending => return None,
+                ReplyProgress::Done(body) => {
+                    return Appearance::from_background_reply(&body).map(Command::UpdateAppearance);
+                }
+            };
+        }
         let mut events = mem::take(&mut self.events);
-        let (command, events) = match read()? {
+        let (command, events) = match event {
             // Ignore release events
             Event::Key(event) if event.kind == KeyEventKind::Release => (None, events),
+            Event::Key(event) if self.mode == InputMode::TextInput => {
+                (TextInputEvent::from_key(&event).map(Command::TextInput), events)
+            }
+            Event::Key(KeyEvent { code: KeyCode::Enter, .. }) if self.mode == InputMode::Navigation => {
+                (Some(Command::CloseModal), vec![])
+            }
+            Event::Key(event) if self.mode == InputMode::Navigation && Direction::from_key(&event).is_some() => {
+                (Direction::from_key(&event).map(Command::MoveSelection), vec![])
+            }
+            Event::Key(event) if self.mode == InputMode::Annotating && PointerEvent::from_key(&event).is_some() => {
+                (PointerEvent::from_key(&event).map(Command::Pointer), vec![])
+            }
+            Event::Mouse(event) if self.mode == InputMode::Annotating => {
+                (PointerEvent::from_mouse(&event).map(Command::Pointer), events)
+            }
+            // Scrolling changes slides, which isn't what's expected while typing.
+            Event::Mouse(MouseEvent { kind: MouseEventKind::ScrollDown | MouseEventKind::ScrollUp, .. })
+                if self.mode == InputMode::TextInput =>
+            {
+                (None, events)
+            }
+            Event::Mouse(event) => (Self::mouse_command(&event), events),
             Event::Key(event) => {
                 events.push(event);
                 self.match_events(events)
@@ -33,11 +117,22 @@ impl KeyboardListener {
             _ => (None, vec![]),
         };
         self.events = events;
-        Ok(command)
+        command
+    }
+
+    fn mouse_command(event: &MouseEvent) -> Option<Command> {
+        let MouseEvent { column, row, .. } = *event;
+        let command = match event.kind {
+            MouseEventKind::Down(MouseButton::Left) => Command::Click { column, row },
+            MouseEventKind::ScrollDown => Command::NextFast,
+            MouseEventKind::ScrollUp => Command::PreviousFast,
+            _ => return None,
+        };
+        Some(command)
     }
 
     fn match_events(&self, events: Vec<KeyEvent>) -> (Option<Command>, Vec<KeyEvent>) {
-        match self.bindings.apply(&events) {
+        match self.bindings.apply(&events, self.mode == InputMode::Searching) {
             InputAction::Emit(command) => (Some(command), Vec::new()),
             InputAction::Buffer => (None, events),
             InputAction::Reset => (None, Vec::new()),
@@ -45,6 +140,188 @@ impl KeyboardListener {
     }
 }
 
+/// A reply to a query sent to the terminal.
+///
+/// Replies look like `ESC ] <body> ESC \` or `ESC ] <body> BEL`, which are read as `alt+]`, every character in the
+/// body, and either `alt+\` or `control+g`. Input is only treated as a reply while one is expected, so pressing
+/// `alt+]` at any other time isn't swallowed.
+#[derive(Debug, Default)]
+struct TerminalReply {
+    body: Option<String>,
+    deadline: Option<Instant>,
+}
+
+enum ReplyProgress {
+    /// The event is not part of a reply.
+    Unrelated,
+
+    /// The event is part of a reply that's not finished yet.
+    Pending,
+
+    /// The reply is finished and this is its body.
+    Done(String),
+}
+
+impl TerminalReply {
+    fn expect(&mut self) {
+        self.deadline = Some(Instant::now() + TERMINAL_REPLY_TIMEOUT);
+    }
+
+    fn feed(&mut self, event: &KeyEvent) -> ReplyProgress {
+        if self.deadline.is_some_and(|deadline| deadline < Instant::now()) {
+            // The terminal never replied, or took too long to finish doing so.
+            self.deadline = None;
+            self.body = None;
+        }
+        let Some(body) = &mut self.body else {
+            if self.deadline.is_some() && event.code == KeyCode::Char(']') && event.modifiers == KeyModifiers::ALT {
+                self.body = Some(String::new());
+                return ReplyProgress::Pending;
+            }
+            return ReplyProgress::Unrelated;
+        };
+        match (event.code, event.modifiers) {
+            (KeyCode::Char('\\'), KeyModifiers::ALT) | (KeyCode::Char('g'), KeyModifiers::CONTROL) => {
+                self.deadline = None;
+                ReplyProgress::Done(self.body.take().unwrap_or_default())
+            }
+            (KeyCode::Char(c), KeyModifiers::NONE | KeyModifiers::SHIFT) if body.len() < MAX_TERMINAL_REPLY_LENGTH => {
+                body.push(c);
+                ReplyProgress::Pending
+            }
+            // Anything else means this wasn't a reply after all, e.g. someone pressed `alt+]`.
+            _ => {
+                self.body = None;
+                ReplyProgress::Unrelated
+            }
+        }
+    }
+}
+
+/// An event produced while typing text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum TextInputEvent {
+    /// A character was typed.
+    Character(char),
+
+    /// The last character was deleted.
+    Backspace,
+
+    /// Move to the next entry.
+    Next,
+
+    /// Move to the previous entry.
+    Previous,
+
+    /// Complete the text being typed.
+    Complete,
+
+    /// The text is done being typed.
+    Submit,
+
+    /// Stop typing text.
+    Cancel,
+
+    /// There's no more text to be typed.
+    EndOfInput,
+}
+
+impl TextInputEvent {
+    fn from_key(event: &KeyEvent) -> Option<Self> {
+        let control = event.modifiers.contains(KeyModifiers::CONTROL);
+        let event = match event.code {
+            KeyCode::Char('n') if control => Self::Next,
+            KeyCode::Char('p') if control => Self::Previous,
+            KeyCode::Char('c') if control => Self::Cancel,
+            KeyCode::Char('d') if control => Self::EndOfInput,
+            KeyCode::Char(_) if control || event.modifiers.contains(KeyModifiers::ALT) => return None,
+            KeyCode::Char(c) => Self::Character(c),
+            KeyCode::Backspace => Self::Backspace,
+            KeyCode::Down => Self::Next,
+            KeyCode::Up => Self::Previous,
+            KeyCode::Tab => Self::Complete,
+            KeyCode::Enter => Self::Submit,
+            KeyCode::Esc => Self::Cancel,
+            _ => return None,
+        };
+        Some(event)
+    }
+}
+
+/// A direction to move a selection in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    fn from_key(event: &KeyEvent) -> Option<Self> {
+        if !event.modifiers.is_empty() {
+            return None;
+        }
+        let direction = match event.code {
+            KeyCode::Up => Self::Up,
+            KeyCode::Down => Self::Down,
+            KeyCode::Left => Self::Left,
+            KeyCode::Right => Self::Right,
+            _ => return None,
+        };
+        Some(direction)
+    }
+}
+
+/// An event that moves the annotation pointer or draws with it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum PointerEvent {
+    /// Move the pointer one cell.
+    Move(Direction),
+
+    /// Move the pointer to a specific cell.
+    MoveTo { column: u16, row: u16 },
+
+    /// Mark the cell under the pointer.
+    Mark,
+
+    /// Start drawing a box at the pointer or finish the one being drawn.
+    Corner,
+
+    /// The mouse button was pressed on a cell.
+    Press { column: u16, row: u16 },
+
+    /// The mouse button was released on a cell.
+    Release { column: u16, row: u16 },
+}
+
+impl PointerEvent {
+    fn from_key(event: &KeyEvent) -> Option<Self> {
+        if let Some(direction) = Direction::from_key(event) {
+            return Some(Self::Move(direction));
+        }
+        if !event.modifiers.is_empty() {
+            return None;
+        }
+        match event.code {
+            KeyCode::Char(' ') => Some(Self::Mark),
+            KeyCode::Enter => Some(Self::Corner),
+            _ => None,
+        }
+    }
+
+    fn from_mouse(event: &MouseEvent) -> Option<Self> {
+        let MouseEvent { column, row, .. } = *event;
+        let event = match event.kind {
+            MouseEventKind::Moved | MouseEventKind::Drag(MouseButton::Left) => Self::MoveTo { column, row },
+            MouseEventKind::Down(MouseButton::Left) => Self::Press { column, row },
+            MouseEventKind::Up(MouseButton::Left) => Self::Release { column, row },
+            _ => return None,
+        };
+        Some(event)
+    }
+}
+
 enum InputAction {
     Buffer,
     Reset,
@@ -53,12 +330,15 @@ enum InputAction {
 
 pub struct CommandKeyBindings {
     bindings: Vec<(KeyBinding, CommandDiscriminants)>,
+    // These are only used while searching so they can reuse keys that are bound to something else.
+    search_bindings: Vec<(KeyBinding, CommandDiscriminants)>,
 }
 
 impl CommandKeyBindings {
-    fn apply(&self, events: &[KeyEvent]) -> InputAction {
+    fn apply(&self, events: &[KeyEvent], searching: bool) -> InputAction {
         let mut any_partials = false;
-        for (binding, identifier) in &self.bindings {
+        let search_bindings = self.search_bindings.iter().filter(|_| searching);
+        for (binding, identifier) in search_bindings.chain(&self.bindings) {
             match binding.match_events(events) {
                 BindingMatch::Full(context) => return Self::instantiate(identifier, context),
                 BindingMatch::Partial => any_partials = true,
@@ -71,13 +351,6 @@ impl CommandKeyBindings {
     fn instantiate(discriminant: &CommandDiscriminants, context: MatchContext) -> InputAction {
         use CommandDiscriminants::*;
         let command = match discriminant {
-            Redraw => Command::Redraw,
-            Next => Command::Next,
-            NextFast => Command::NextFast,
-            Previous => Command::Previous,
-            PreviousFast => Command::PreviousFast,
-            FirstSlide => Command::FirstSlide,
-            LastSlide => Command::LastSlide,
             GoToSlide => {
                 match context {
                     // this means the command is malformed and this should have been caught earlier
@@ -86,17 +359,10 @@ impl CommandKeyBindings {
                     MatchContext::Number(number) => Command::GoToSlide(number),
                 }
             }
-            RenderAsyncOperations => Command::RenderAsyncOperations,
-            Exit => Command::Exit,
-            Suspend => Command::Suspend,
-            Reload => Command::Reload,
-            HardReload => Command::HardReload,
-            ToggleSlideIndex => Command::ToggleSlideIndex,
-            ToggleKeyBindingsConfig => Command::ToggleKeyBindingsConfig,
-            ToggleLayoutGrid => Command::ToggleLayoutGrid,
-            CloseModal => Command::CloseModal,
-            SkipPauses => Command::SkipPauses,
-            GoToSlideChunk => panic!("go to slide chunk is not configurable"),
+            other => match Command::from_discriminant(*other) {
+                Some(command) => command,
+                None => panic!("{other:?} is not configurable"),
+            },
         };
         InputAction::Emit(command)
     }
@@ -126,6 +392,9 @@ impl TryFrom<KeyBindingsConfig> for CommandKeyBindings {
         if !config.go_to_slide.iter().all(|k| k.expects_number()) {
             return Err(KeyBindingsValidationError::Invalid("go_to_slide", "<number> matcher required"));
         }
+        if config.mouse.previous_area > 100 {
+            return Err(KeyBindingsValidationError::Invalid("mouse.previous_area", "must be a percentage"));
+        }
         let KeyBindingsConfig {
             next,
             next_fast,
@@ -135,14 +404,24 @@ impl TryFrom<KeyBindingsConfig> for CommandKeyBindings {
             last_slide,
             go_to_slide,
             execute_code,
+            kill_execution,
             reload,
             toggle_slide_index,
+            toggle_overview,
+            toggle_annotations,
+            toggle_theme_picker,
+            snippet_input,
             toggle_bindings,
+            command_palette,
             toggle_layout_grid,
             close_modal,
             exit,
             suspend,
             skip_pauses,
+            search,
+            search_next,
+            search_previous,
+            mouse: _,
         } = config;
         let bindings: Vec<_> = iter::empty()
             .chain(zip(CommandDiscriminants::Next, next))
@@ -156,14 +435,26 @@ impl TryFrom<KeyBindingsConfig> for CommandKeyBindings {
             .chain(zip(CommandDiscriminants::Suspend, suspend))
             .chain(zip(CommandDiscriminants::HardReload, reload))
             .chain(zip(CommandDiscriminants::ToggleSlideIndex, toggle_slide_index))
+            .chain(zip(CommandDiscriminants::ToggleOverview, toggle_overview))
+            .chain(zip(CommandDiscriminants::ToggleAnnotations, toggle_annotations))
+            .chain(zip(CommandDiscriminants::ToggleThemePicker, toggle_theme_picker))
+            .chain(zip(CommandDiscriminants::SnippetInput, snippet_input))
             .chain(zip(CommandDiscriminants::ToggleKeyBindingsConfig, toggle_bindings))
+            .chain(zip(CommandDiscriminants::OpenCommandPalette, command_palette))
             .chain(zip(CommandDiscriminants::ToggleLayoutGrid, toggle_layout_grid))
             .chain(zip(CommandDiscriminants::RenderAsyncOperations, execute_code))
+            .chain(zip(CommandDiscriminants::KillExecution, kill_execution))
             .chain(zip(CommandDiscriminants::CloseModal, close_modal))
             .chain(zip(CommandDiscriminants::SkipPauses, skip_pauses))
+            .chain(zip(CommandDiscriminants::Search, search))
+            .collect();
+        let search_bindings: Vec<_> = iter::empty()
+            .chain(zip(CommandDiscriminants::SearchNext, search_next))
+            .chain(zip(CommandDiscriminants::SearchPrevious, search_previous))
             .collect();
         Self::validate_conflicts(bindings.iter().map(|binding| &binding.0))?;
-        Ok(Self { bindings })
+        Self::validate_conflicts(search_bindings.iter().map(|binding| &binding.0))?;
+        Ok(Self { bindings, search_bindings })
     }
 }
 
@@ -592,6 +883,87 @@ mod test {
         assert!(result.is_ok(), "got error: {result:?}");
     }
 
+    #[test]
+    fn search_bindings() {
+        let bindings = CommandKeyBindings::try_from(KeyBindingsConfig::default()).expect("invalid bindings");
+        let events = &['n'.into_event()];
+        assert!(matches!(bindings.apply(events, false), InputAction::Emit(Command::NextFast)));
+        assert!(matches!(bindings.apply(events, true), InputAction::Emit(Command::SearchNext)));
+    }
+
+    #[rstest]
+    #[case::scroll_down(InputMode::Bindings, MouseEventKind::ScrollDown, Some(Command::NextFast))]
+    #[case::scroll_up(InputMode::Bindings, MouseEventKind::ScrollUp, Some(Command::PreviousFast))]
+    #[case::scroll_down_typing(InputMode::TextInput, MouseEventKind::ScrollDown, None)]
+    #[case::scroll_up_typing(InputMode::TextInput, MouseEventKind::ScrollUp, None)]
+    #[case::click_typing(
+        InputMode::TextInput,
+        MouseEventKind::Down(MouseButton::Left),
+        Some(Command::Click { column: 1, row: 2 })
+    )]
+    fn mouse_events(#[case] mode: InputMode, #[case] kind: MouseEventKind, #[case] expected: Option<Command>) {
+        let bindings = CommandKeyBindings::try_from(KeyBindingsConfig::default()).expect("invalid bindings");
+        let mut listener = KeyboardListener::new(bindings);
+        listener.set_input_mode(mode);
+        let event = MouseEvent { kind, column: 1, row: 2, modifiers: KeyModifiers::empty() };
+        assert_eq!(listener.process_event(Event::Mouse(event)), expected);
+    }
+
+    #[test]
+    fn terminal_reply() {
+        let mut reply = TerminalReply::default();
+        let mut open = ']'.into_event();
+        open.modifiers = KeyModifiers::ALT;
+        reply.expect();
+        assert!(matches!(reply.feed(&open), ReplyProgress::Pending));
+        for c in "11;rgb:ffff/ffff/ffff".chars() {
+            assert!(matches!(reply.feed(&c.into_event()), ReplyProgress::Pending));
+        }
+        let ReplyProgress::Done(body) = reply.feed(&'g'.into_event().with_control()) else {
+            panic!("reply not finished");
+        };
+        assert_eq!(body, "11;rgb:ffff/ffff/ffff");
+
+        // Regular key presses aren't swallowed.
+        assert!(matches!(reply.feed(&'l'.into_event()), ReplyProgress::Unrelated));
+        reply.expect();
+        reply.feed(&open);
+        assert!(matches!(reply.feed(&KeyCode::Enter.into_event()), ReplyProgress::Unrelated));
+        assert!(matches!(reply.feed(&'a'.into_event()), ReplyProgress::Unrelated));
+    }
+
+    #[test]
+    fn unexpected_terminal_reply() {
+        let mut reply = TerminalReply::default();
+        let mut open = ']'.into_event();
+        open.modifiers = KeyModifiers::ALT;
+        assert!(matches!(reply.feed(&open), ReplyProgress::Unrelated));
+        assert!(matches!(reply.feed(&'1'.into_event()), ReplyProgress::Unrelated));
+
+        // Once a reply is finished, another one isn't expected.
+        reply.expect();
+        reply.feed(&open);
+        assert!(matches!(reply.feed(&'g'.into_event().with_control()), ReplyProgress::Done(_)));
+        assert!(matches!(reply.feed(&open), ReplyProgress::Unrelated));
+    }
+
+    #[test]
+    fn terminal_reply_timeout() {
+        let mut reply = TerminalReply::default();
+        let mut open = ']'.into_event();
+        open.modifiers = KeyModifiers::ALT;
+        reply.deadline = Some(Instant::now() - Duration::from_millis(1));
+        assert!(matches!(reply.feed(&open), ReplyProgress::Unrelated));
+
+        // A reply that started in time but didn't finish is dropped.
+        reply.expect();
+        assert!(matches!(reply.feed(&open), ReplyProgress::Pending));
+        assert!(matches!(reply.feed(&'1'.into_event()), ReplyProgress::Pending));
+        reply.deadline = Some(Instant::now() - Duration::from_millis(1));
+        assert!(matches!(reply.feed(&'1'.into_event()), ReplyProgress::Unrelated));
+        assert!(reply.body.is_none());
+    }
+
     #[rstest]
     #[case("<number>G")]
     #[case("<PageUp>potato")]
@@ -1,16 +1,25 @@
 use super::{
-    keyboard::{CommandKeyBindings, KeyBindingsValidationError, KeyboardListener},
-    speaker_notes::{SpeakerNotesEvent, SpeakerNotesEventListener},
+    keyboard::{
+        CommandKeyBindings, Direction, InputMode, KeyBindingsValidationError, KeyboardListener, PointerEvent,
+        TextInputEvent,
+    },
+    remote::RemoteControlListener,
+    speaker_notes::{SpeakerNotesControlListener, SpeakerNotesEvent, SpeakerNotesEventListener},
+};
+use crate::{
+    config::KeyBindingsConfig, presentation::clock::PacingStatus, presenter::PresentationError,
+    terminal::appearance::Appearance,
 };
-use crate::{config::KeyBindingsConfig, presenter::PresentationError};
 use serde::Deserialize;
 use std::time::Duration;
-use strum::EnumDiscriminants;
+use strum::{EnumDiscriminants, EnumIter, IntoStaticStr};
 
 /// A command listener that allows polling all command sources in a single place.
 pub struct CommandListener {
     keyboard: KeyboardListener,
     speaker_notes_event_listener: Option<SpeakerNotesEventListener>,
+    speaker_notes_control_listener: Option<SpeakerNotesControlListener>,
+    remote_control_listener: Option<RemoteControlListener>,
 }
 
 impl CommandListener {
@@ -18,9 +27,26 @@ impl CommandListener {
     pub fn new(
         config: KeyBindingsConfig,
         speaker_notes_event_listener: Option<SpeakerNotesEventListener>,
+        speaker_notes_control_listener: Option<SpeakerNotesControlListener>,
+        remote_control_listener: Option<RemoteControlListener>,
     ) -> Result<Self, KeyBindingsValidationError> {
         let bindings = CommandKeyBindings::try_from(config)?;
-        Ok(Self { keyboard: KeyboardListener::new(bindings), speaker_notes_event_listener })
+        Ok(Self {
+            keyboard: KeyboardListener::new(bindings),
+            speaker_notes_event_listener,
+            speaker_notes_control_listener,
+            remote_control_listener,
+        })
+    }
+
+    /// Set how key presses are interpreted.
+    pub(crate) fn set_input_mode(&mut self, mode: InputMode) {
+        self.keyboard.set_input_mode(mode);
+    }
+
+    /// Treat the next input that looks like a reply to a terminal query as such.
+    pub(crate) fn expect_terminal_reply(&mut self) {
+        self.keyboard.expect_terminal_reply();
     }
 
     /// Try to get the next command.
@@ -31,11 +57,22 @@ impl CommandListener {
             if let Some(msg) = receiver.try_recv()? {
                 let command = match msg {
                     SpeakerNotesEvent::GoTo { slide, chunk } => Command::GoToSlideChunk { slide, chunk },
+                    SpeakerNotesEvent::Clock { elapsed, pacing } => Command::UpdateClock { elapsed, pacing },
                     SpeakerNotesEvent::Exit => Command::Exit,
                 };
                 return Ok(Some(command));
             }
         }
+        if let Some(receiver) = &self.speaker_notes_control_listener {
+            if let Some(event) = receiver.try_recv()? {
+                return Ok(Some(event.into()));
+            }
+        }
+        if let Some(receiver) = &self.remote_control_listener {
+            if let Some(command) = receiver.try_recv() {
+                return Ok(Some(command.into()));
+            }
+        }
         match self.keyboard.poll_next_command(Duration::from_millis(20))? {
             Some(command) => Ok(Some(command)),
             None => Ok(None),
@@ -45,7 +82,7 @@ impl CommandListener {
 
 /// A command.
 #[derive(Clone, Debug, PartialEq, Eq, EnumDiscriminants)]
-#[strum_discriminants(derive(Deserialize))]
+#[strum_discriminants(derive(Deserialize, EnumIter, IntoStaticStr), strum(serialize_all = "snake_case"))]
 pub(crate) enum Command {
     /// Redraw the presentation.
     ///
@@ -76,9 +113,18 @@ pub(crate) enum Command {
     /// Go to one particular slide + chunk.
     GoToSlideChunk { slide: u32, chunk: u32 },
 
+    /// Update the presentation's elapsed time and pacing.
+    UpdateClock { elapsed: Duration, pacing: Option<PacingStatus> },
+
+    /// The terminal reported whether its background is dark or light.
+    UpdateAppearance(Appearance),
+
     /// Render any async render operations in the current slide.
     RenderAsyncOperations,
 
+    /// Kill any snippet running in the current slide.
+    KillExecution,
+
     /// Exit the presentation.
     Exit,
 
@@ -96,9 +142,33 @@ pub(crate) enum Command {
     /// Toggle the slide index view.
     ToggleSlideIndex,
 
+    /// Toggle the slide overview.
+    ToggleOverview,
+
+    /// Move the selection in the slide overview.
+    MoveSelection(Direction),
+
+    /// Toggle the annotation mode.
+    ToggleAnnotations,
+
+    /// Toggle the theme picker.
+    ToggleThemePicker,
+
+    /// Start typing input for the snippet running in the current slide.
+    SnippetInput,
+
+    /// Move the annotation pointer or draw with it.
+    Pointer(PointerEvent),
+
+    /// The mouse was clicked on a cell.
+    Click { column: u16, row: u16 },
+
     /// Toggle the key bindings config view.
     ToggleKeyBindingsConfig,
 
+    /// Open the command palette.
+    OpenCommandPalette,
+
     /// Toggle layout grid.
     ToggleLayoutGrid,
 
@@ -107,4 +177,54 @@ pub(crate) enum Command {
 
     /// Skip pauses in the current slide.
     SkipPauses,
+
+    /// Start searching.
+    Search,
+
+    /// Go to the next search match.
+    SearchNext,
+
+    /// Go to the previous search match.
+    SearchPrevious,
+
+    /// Text typed while searching.
+    TextInput(TextInputEvent),
+}
+
+impl Command {
+    /// Build the command for a discriminant, as long as the command doesn't carry any data.
+    pub(crate) fn from_discriminant(discriminant: CommandDiscriminants) -> Option<Self> {
+        use CommandDiscriminants::*;
+        let command = match discriminant {
+            Redraw => Self::Redraw,
+            Next => Self::Next,
+            NextFast => Self::NextFast,
+            Previous => Self::Previous,
+            PreviousFast => Self::PreviousFast,
+            FirstSlide => Self::FirstSlide,
+            LastSlide => Self::LastSlide,
+            RenderAsyncOperations => Self::RenderAsyncOperations,
+            KillExecution => Self::KillExecution,
+            Exit => Self::Exit,
+            Suspend => Self::Suspend,
+            Reload => Self::Reload,
+            HardReload => Self::HardReload,
+            ToggleSlideIndex => Self::ToggleSlideIndex,
+            ToggleOverview => Self::ToggleOverview,
+            ToggleAnnotations => Self::ToggleAnnotations,
+            ToggleThemePicker => Self::ToggleThemePicker,
+            SnippetInput => Self::SnippetInput,
+            ToggleKeyBindingsConfig => Self::ToggleKeyBindingsConfig,
+            OpenCommandPalette => Self::OpenCommandPalette,
+            ToggleLayoutGrid => Self::ToggleLayoutGrid,
+            CloseModal => Self::CloseModal,
+            SkipPauses => Self::SkipPauses,
+            Search => Self::Search,
+            SearchNext => Self::SearchNext,
+            SearchPrevious => Self::SearchPrevious,
+            GoToSlide | GoToSlideChunk | UpdateClock | UpdateAppearance | MoveSelection | Pointer | Click
+            | TextInput => return None,
+        };
+        Some(command)
+    }
 }
@@ -1,3 +1,4 @@
 pub(crate) mod keyboard;
 pub(crate) mod listener;
+pub(crate) mod remote;
 pub(crate) mod speaker_notes;
@@ -0,0 +1,274 @@
+use super::listener::Command;
+use crate::http::{Request, Response};
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{self, BufReader},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{Receiver, Sender, channel},
+    },
+    thread,
+    time::Duration,
+};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+const MAX_CLIENTS: usize = 16;
+
+/// A remote control server that lets HTTP clients drive the presentation.
+pub struct RemoteControlServer;
+
+impl RemoteControlServer {
+    /// Launch a server on the given address that requires requests to provide the given token.
+    pub fn launch(address: SocketAddr, token: String) -> io::Result<(RemoteControlListener, RemoteControlPublisher)> {
+        Self::launch_on(TcpListener::bind(address)?, token)
+    }
+
+    fn launch_on(listener: TcpListener, token: String) -> io::Result<(RemoteControlListener, RemoteControlPublisher)> {
+        let (sender, receiver) = channel();
+        let state = Arc::new(Mutex::new(RemoteState::default()));
+        let handler = Arc::new(RequestHandler { token, sender, state: state.clone(), clients: Default::default() });
+        thread::Builder::new().name("remote-control".into()).spawn(move || handler.run(listener))?;
+        Ok((RemoteControlListener { receiver }, RemoteControlPublisher { state }))
+    }
+}
+
+/// Receives the commands sent by remote control clients.
+pub struct RemoteControlListener {
+    receiver: Receiver<RemoteCommand>,
+}
+
+impl RemoteControlListener {
+    pub(crate) fn try_recv(&self) -> Option<RemoteCommand> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+/// Publishes the presentation's state so remote control clients can query it.
+pub struct RemoteControlPublisher {
+    state: Arc<Mutex<RemoteState>>,
+}
+
+impl RemoteControlPublisher {
+    pub(crate) fn publish(&self, state: RemoteState) {
+        *self.state.lock().unwrap() = state;
+    }
+}
+
+/// The state of the presentation as seen by remote control clients.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub(crate) struct RemoteState {
+    pub(crate) slide: u32,
+    pub(crate) total_slides: u32,
+    pub(crate) chunk: u32,
+    pub(crate) total_chunks: u32,
+    pub(crate) speaker_notes: Vec<String>,
+}
+
+/// A command sent by a remote control client.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub(crate) enum RemoteCommand {
+    Next,
+    Previous,
+    GoToSlide { slide: u32 },
+}
+
+impl From<RemoteCommand> for Command {
+    fn from(command: RemoteCommand) -> Self {
+        match command {
+            RemoteCommand::Next => Self::Next,
+            RemoteCommand::Previous => Self::Previous,
+            RemoteCommand::GoToSlide { slide } => Self::GoToSlide(slide),
+        }
+    }
+}
+
+struct RequestHandler {
+    token: String,
+    sender: Sender<RemoteCommand>,
+    state: Arc<Mutex<RemoteState>>,
+    clients: AtomicUsize,
+}
+
+impl RequestHandler {
+    fn run(self: Arc<Self>, listener: TcpListener) {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else {
+                continue;
+            };
+            let Some(client) = self.add_client() else {
+                let _ = stream.set_write_timeout(Some(REQUEST_TIMEOUT));
+                let _ = Response::error(503, "too many clients").write(&stream);
+                continue;
+            };
+            // Serve each client on its own thread so a slow one can't stall everyone else.
+            let _ = thread::Builder::new().name("remote-control-client".into()).spawn(move || {
+                // Errors here are the client's problem so there's nothing else to do with them.
+                let _ = client.0.serve(stream);
+            });
+        }
+    }
+
+    fn add_client(self: &Arc<Self>) -> Option<ClientGuard> {
+        self.clients
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |clients| {
+                (clients < MAX_CLIENTS).then_some(clients + 1)
+            })
+            .ok()?;
+        Some(ClientGuard(self.clone()))
+    }
+
+    fn serve(&self, stream: TcpStream) -> io::Result<()> {
+        stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+        stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+        let mut reader = BufReader::new(&stream);
+        let response = match Request::read(&mut reader) {
+            Ok(request) => self.handle(&request),
+            Err(e) => Response::error(400, &e.to_string()),
+        };
+        response.write(&stream)
+    }
+
+    fn handle(&self, request: &Request) -> Response {
+        if !request.has_token(&self.token) {
+            return Response::error(401, "invalid token");
+        }
+        match (request.method.as_str(), request.path.as_str()) {
+            ("GET", "/state") => {
+                let state = self.state.lock().unwrap().clone();
+                Response::json(&state)
+            }
+            ("POST", "/command") => match serde_json::from_slice::<RemoteCommand>(&request.body) {
+                Ok(command) => {
+                    // The receiving end only goes away when the presentation is exiting.
+                    let _ = self.sender.send(command);
+                    Response::new(202, "{}".into())
+                }
+                Err(e) => Response::error(400, &format!("invalid command: {e}")),
+            },
+            (_, "/state" | "/command") => Response::error(405, "method not allowed"),
+            _ => Response::error(404, "not found"),
+        }
+    }
+}
+
+struct ClientGuard(Arc<RequestHandler>);
+
+impl Drop for ClientGuard {
+    fn drop(&mut self) {
+        self.0.clients.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use std::io::{Read, Write};
+
+    fn make_handler() -> (RequestHandler, Receiver<RemoteCommand>) {
+        let (sender, receiver) = channel();
+        let state = Arc::new(Mutex::new(RemoteState::default()));
+        (RequestHandler { token: "secret".into(), sender, state, clients: Default::default() }, receiver)
+    }
+
+    fn parse(input: &str) -> Request {
+        Request::read(&mut input.as_bytes()).expect("invalid request")
+    }
+
+    #[rstest]
+    #[case::no_token("GET /state HTTP/1.1\r\n\r\n")]
+    #[case::bad_header_token("GET /state HTTP/1.1\r\nAuthorization: Bearer nope\r\n\r\n")]
+    #[case::bad_query_token("GET /state?token=secre HTTP/1.1\r\n\r\n")]
+    fn unauthorized(#[case] input: &str) {
+        let (handler, _receiver) = make_handler();
+        let response = handler.handle(&parse(input));
+        assert_eq!(response.status, 401);
+    }
+
+    #[rstest]
+    #[case::header("GET /state HTTP/1.1\r\nAuthorization: Bearer secret\r\n\r\n")]
+    #[case::query("GET /state?token=secret HTTP/1.1\r\n\r\n")]
+    fn state(#[case] input: &str) {
+        let (handler, _receiver) = make_handler();
+        let state = RemoteState {
+            slide: 2,
+            total_slides: 5,
+            chunk: 1,
+            total_chunks: 3,
+            speaker_notes: vec!["remember this".into()],
+        };
+        *handler.state.lock().unwrap() = state.clone();
+
+        let response = handler.handle(&parse(input));
+        assert_eq!(response.status, 200);
+        let parsed: RemoteState = serde_json::from_str(&response.body).expect("invalid state");
+        assert_eq!(parsed, state);
+    }
+
+    #[rstest]
+    #[case::next(r#"{"command":"next"}"#, RemoteCommand::Next)]
+    #[case::previous(r#"{"command":"previous"}"#, RemoteCommand::Previous)]
+    #[case::go_to_slide(r#"{"command":"go_to_slide","slide":3}"#, RemoteCommand::GoToSlide { slide: 3 })]
+    fn command(#[case] body: &str, #[case] expected: RemoteCommand) {
+        let (handler, receiver) = make_handler();
+        let input = format!("POST /command?token=secret HTTP/1.1\r\nContent-Length: {}\r\n\r\n{body}", body.len());
+        let response = handler.handle(&parse(&input));
+        assert_eq!(response.status, 202);
+        assert_eq!(receiver.try_recv().ok(), Some(expected));
+    }
+
+    #[rstest]
+    #[case::invalid_command("POST /command?token=secret HTTP/1.1\r\nContent-Length: 2\r\n\r\n{}", 400)]
+    #[case::wrong_method("GET /command?token=secret HTTP/1.1\r\n\r\n", 405)]
+    #[case::unknown_path("GET /potato?token=secret HTTP/1.1\r\n\r\n", 404)]
+    fn invalid_requests(#[case] input: &str, #[case] status: u16) {
+        let (handler, receiver) = make_handler();
+        let response = handler.handle(&parse(input));
+        assert_eq!(response.status, status);
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn client_limit() {
+        let handler = Arc::new(make_handler().0);
+        let clients: Vec<_> = (0..MAX_CLIENTS).map(|_| handler.add_client().expect("no client")).collect();
+        assert!(handler.add_client().is_none());
+
+        drop(clients);
+        assert_eq!(handler.clients.load(Ordering::Relaxed), 0);
+        assert!(handler.add_client().is_some());
+    }
+
+    #[test]
+    fn end_to_end() {
+        let socket = TcpListener::bind("127.0.0.1:0").expect("bind failed");
+        let address = socket.local_addr().expect("no local address");
+        let (listener, publisher) = RemoteControlServer::launch_on(socket, "secret".into()).expect("launch failed");
+        publisher.publish(RemoteState { slide: 1, total_slides: 2, ..Default::default() });
+
+        let body = r#"{"command":"next"}"#;
+        let mut stream = TcpStream::connect(address).expect("connect failed");
+        write!(
+            stream,
+            "POST /command HTTP/1.1\r\nAuthorization: Bearer secret\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+        .expect("write failed");
+        let mut response = String::new();
+        stream.read_to_string(&mut response).expect("read failed");
+        assert!(response.starts_with("HTTP/1.1 202 Accepted\r\n"), "{response}");
+        assert_eq!(listener.try_recv(), Some(RemoteCommand::Next));
+
+        // A client that never sends its request doesn't block anyone else.
+        let _idle = TcpStream::connect(address).expect("connect failed");
+        let mut stream = TcpStream::connect(address).expect("connect failed");
+        write!(stream, "GET /state?token=secret HTTP/1.1\r\n\r\n").expect("write failed");
+        let mut response = String::new();
+        stream.read_to_string(&mut response).expect("read failed");
+        assert!(response.ends_with(r#""slide":1,"total_slides":2,"chunk":0,"total_chunks":0,"speaker_notes":[]}"#));
+    }
+}
@@ -1,9 +1,12 @@
-use serde::{Deserialize, Serialize};
+use super::listener::Command;
+use crate::presentation::clock::PacingStatus;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use socket2::{Domain, Protocol, Socket, Type};
 use std::{
     io,
     net::{SocketAddr, UdpSocket},
-    path::PathBuf,
+    path::{Path, PathBuf},
+    time::Duration,
 };
 
 pub struct SpeakerNotesEventPublisher {
@@ -13,22 +16,11 @@ pub struct SpeakerNotesEventPublisher {
 
 impl SpeakerNotesEventPublisher {
     pub fn new(address: SocketAddr, presentation_path: PathBuf) -> io::Result<Self> {
-        let socket = UdpSocket::bind("127.0.0.1:0")?;
-        socket.set_broadcast(true)?;
-        socket.connect(address)?;
-        Ok(Self { socket, presentation_path })
+        Ok(Self { socket: connect_publisher(address)?, presentation_path })
     }
 
     pub(crate) fn send(&self, event: SpeakerNotesEvent) -> io::Result<()> {
-        // Wrap this event in an envelope that contains the presentation path so listeners can
-        // ignore unrelated events.
-        let envelope = SpeakerNotesEventEnvelope { event, presentation_path: self.presentation_path.clone() };
-        let data = serde_json::to_string(&envelope).expect("serialization failed");
-        match self.socket.send(data.as_bytes()) {
-            Ok(_) => Ok(()),
-            Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => Ok(()),
-            Err(e) => Err(e),
-        }
+        send_event(&self.socket, &self.presentation_path, event)
     }
 }
 
@@ -39,50 +31,160 @@ pub struct SpeakerNotesEventListener {
 
 impl SpeakerNotesEventListener {
     pub fn new(address: SocketAddr, presentation_path: PathBuf) -> io::Result<Self> {
-        let s = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
-        // Use SO_REUSEADDR so we can have multiple listeners on the same port.
-        #[cfg(not(target_os = "macos"))]
-        s.set_reuse_address(true)?;
-        // Don't block so we can listen to the keyboard and this socket at the same time.
-        s.set_nonblocking(true)?;
-        s.bind(&address.into())?;
-        Ok(Self { socket: s.into(), presentation_path })
+        Ok(Self { socket: bind_listener(address)?, presentation_path })
     }
 
     pub(crate) fn try_recv(&self) -> io::Result<Option<SpeakerNotesEvent>> {
-        let mut buffer = [0; 1024];
-        let bytes_read = match self.socket.recv(&mut buffer) {
-            Ok(bytes_read) => bytes_read,
-            Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(None),
-            Err(e) => return Err(e),
-        };
-        // Ignore garbage. Odds are this is someone else sending garbage rather than presenterm
-        // itself.
-        let Ok(envelope) = serde_json::from_slice::<SpeakerNotesEventEnvelope>(&buffer[0..bytes_read]) else {
-            return Ok(None);
-        };
-        if envelope.presentation_path == self.presentation_path { Ok(Some(envelope.event)) } else { Ok(None) }
+        try_recv_event(&self.socket, &self.presentation_path)
+    }
+}
+
+/// Publishes control events from a speaker notes instance so the main instance can follow them.
+pub struct SpeakerNotesControlPublisher {
+    socket: UdpSocket,
+    presentation_path: PathBuf,
+}
+
+impl SpeakerNotesControlPublisher {
+    pub fn new(address: SocketAddr, presentation_path: PathBuf) -> io::Result<Self> {
+        Ok(Self { socket: connect_publisher(address)?, presentation_path })
+    }
+
+    pub(crate) fn send(&self, event: SpeakerNotesControlEvent) -> io::Result<()> {
+        send_event(&self.socket, &self.presentation_path, event)
     }
 }
 
+/// Listens for control events sent by speaker notes instances.
+pub struct SpeakerNotesControlListener {
+    socket: UdpSocket,
+    presentation_path: PathBuf,
+}
+
+impl SpeakerNotesControlListener {
+    pub fn new(address: SocketAddr, presentation_path: PathBuf) -> io::Result<Self> {
+        Ok(Self { socket: bind_listener(address)?, presentation_path })
+    }
+
+    pub(crate) fn try_recv(&self) -> io::Result<Option<SpeakerNotesControlEvent>> {
+        try_recv_event(&self.socket, &self.presentation_path)
+    }
+}
+
+fn connect_publisher(address: SocketAddr) -> io::Result<UdpSocket> {
+    let socket = UdpSocket::bind("127.0.0.1:0")?;
+    socket.set_broadcast(true)?;
+    socket.connect(address)?;
+    Ok(socket)
+}
+
+fn bind_listener(address: SocketAddr) -> io::Result<UdpSocket> {
+    let s = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+    // Use SO_REUSEADDR so we can have multiple listeners on the same port.
+    #[cfg(not(target_os = "macos"))]
+    s.set_reuse_address(true)?;
+    // Don't block so we can listen to the keyboard and this socket at the same time.
+    s.set_nonblocking(true)?;
+    s.bind(&address.into())?;
+    Ok(s.into())
+}
+
+fn send_event<E: Serialize>(socket: &UdpSocket, presentation_path: &Path, event: E) -> io::Result<()> {
+    // Wrap this event in an envelope that contains the presentation path so listeners can
+    // ignore unrelated events.
+    let envelope = EventEnvelope { event, presentation_path: presentation_path.to_path_buf() };
+    let data = serde_json::to_string(&envelope).expect("serialization failed");
+    match socket.send(data.as_bytes()) {
+        Ok(_) => Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+fn try_recv_event<E: DeserializeOwned>(socket: &UdpSocket, presentation_path: &Path) -> io::Result<Option<E>> {
+    let mut buffer = [0; 1024];
+    let bytes_read = match socket.recv(&mut buffer) {
+        Ok(bytes_read) => bytes_read,
+        Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    // Ignore garbage. Odds are this is someone else sending garbage rather than presenterm
+    // itself.
+    let Ok(envelope) = serde_json::from_slice::<EventEnvelope<E>>(&buffer[0..bytes_read]) else {
+        return Ok(None);
+    };
+    if envelope.presentation_path == presentation_path { Ok(Some(envelope.event)) } else { Ok(None) }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "command")]
 pub(crate) enum SpeakerNotesEvent {
     GoTo { slide: u32, chunk: u32 },
+    Clock { elapsed: Duration, pacing: Option<PacingStatus> },
     Exit,
 }
 
+/// An event sent by a speaker notes instance to drive the main instance.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "command")]
+pub(crate) enum SpeakerNotesControlEvent {
+    Next,
+    NextFast,
+    Previous,
+    PreviousFast,
+    FirstSlide,
+    LastSlide,
+    GoToSlide { slide: u32 },
+    ExecuteCode,
+}
+
+impl SpeakerNotesControlEvent {
+    /// Build the control event that forwards the given command, if it can be forwarded.
+    pub(crate) fn from_command(command: &Command) -> Option<Self> {
+        let event = match command {
+            Command::Next => Self::Next,
+            Command::NextFast => Self::NextFast,
+            Command::Previous => Self::Previous,
+            Command::PreviousFast => Self::PreviousFast,
+            Command::FirstSlide => Self::FirstSlide,
+            Command::LastSlide => Self::LastSlide,
+            Command::GoToSlide(slide) => Self::GoToSlide { slide: *slide },
+            Command::RenderAsyncOperations => Self::ExecuteCode,
+            _ => return None,
+        };
+        Some(event)
+    }
+}
+
+impl From<SpeakerNotesControlEvent> for Command {
+    fn from(event: SpeakerNotesControlEvent) -> Self {
+        match event {
+            SpeakerNotesControlEvent::Next => Self::Next,
+            SpeakerNotesControlEvent::NextFast => Self::NextFast,
+            SpeakerNotesControlEvent::Previous => Self::Previous,
+            SpeakerNotesControlEvent::PreviousFast => Self::PreviousFast,
+            SpeakerNotesControlEvent::FirstSlide => Self::FirstSlide,
+            SpeakerNotesControlEvent::LastSlide => Self::LastSlide,
+            SpeakerNotesControlEvent::GoToSlide { slide } => Self::GoToSlide(slide),
+            SpeakerNotesControlEvent::ExecuteCode => Self::RenderAsyncOperations,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
-struct SpeakerNotesEventEnvelope {
+struct EventEnvelope<E> {
     presentation_path: PathBuf,
-    event: SpeakerNotesEvent,
+    event: E,
 }
 
 #[cfg(not(target_os = "macos"))]
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{default_speaker_notes_listen_address, default_speaker_notes_publish_address};
+    use crate::config::{
+        default_speaker_notes_control_address, default_speaker_notes_listen_address,
+        default_speaker_notes_publish_address,
+    };
     use std::{thread::sleep, time::Duration};
 
     fn make_listener(path: PathBuf) -> SpeakerNotesEventListener {
@@ -93,6 +195,13 @@ mod tests {
         SpeakerNotesEventPublisher::new(default_speaker_notes_publish_address(), path).expect("building publisher")
     }
 
+    // Tests run in parallel so each one needs its own port to avoid receiving another one's events.
+    fn control_address(port: u16) -> SocketAddr {
+        let mut address = default_speaker_notes_control_address();
+        address.set_port(port);
+        address
+    }
+
     #[test]
     fn bind_multiple() {
         let _l1 = make_listener("".into());
@@ -112,4 +221,45 @@ mod tests {
         assert_eq!(l1.try_recv().expect("recv first failed"), Some(event.clone()));
         assert_eq!(l2.try_recv().expect("recv second failed"), Some(event));
     }
+
+    #[test]
+    fn control() {
+        let path = PathBuf::from("/tmp/control.md");
+        let address = control_address(59420);
+        let listener = SpeakerNotesControlListener::new(address, path.clone()).expect("building listener");
+        let publisher = SpeakerNotesControlPublisher::new(address, path).expect("building publisher");
+        let event = SpeakerNotesControlEvent::GoToSlide { slide: 3 };
+        publisher.send(event.clone()).expect("send failed");
+        sleep(Duration::from_millis(100));
+
+        assert_eq!(listener.try_recv().expect("recv failed"), Some(event));
+    }
+
+    #[test]
+    fn control_ignores_other_presentations() {
+        let address = control_address(59421);
+        let listener =
+            SpeakerNotesControlListener::new(address, "/tmp/control-a.md".into()).expect("building listener");
+        let publisher =
+            SpeakerNotesControlPublisher::new(address, "/tmp/control-b.md".into()).expect("building publisher");
+        publisher.send(SpeakerNotesControlEvent::Next).expect("send failed");
+        sleep(Duration::from_millis(100));
+
+        assert_eq!(listener.try_recv().expect("recv failed"), None);
+    }
+
+    #[test]
+    fn clock_serde() {
+        let pacing = PacingStatus {
+            slide_elapsed: Duration::from_secs(10),
+            slide_budget: Some(Duration::from_secs(60)),
+            elapsed: Duration::from_secs(130),
+            budget: Duration::from_secs(120),
+            behind: true,
+        };
+        let event = SpeakerNotesEvent::Clock { elapsed: Duration::from_secs(140), pacing: Some(pacing) };
+        let data = serde_json::to_string(&event).expect("serialization failed");
+        let parsed: SpeakerNotesEvent = serde_json::from_str(&data).expect("deserialization failed");
+        assert_eq!(parsed, event);
+    }
 }
@@ -2,6 +2,7 @@ use crate::{
     code::snippet::SnippetLanguage,
     commands::keyboard::KeyBinding,
     terminal::{GraphicsMode, emulator::TerminalEmulator, image::protocols::kitty::KittyMode},
+    utils::{ByteSize, HumanDuration},
 };
 use clap::ValueEnum;
 use serde::Deserialize;
@@ -42,6 +43,12 @@ pub struct Config {
     #[serde(default)]
     pub speaker_notes: SpeakerNotesConfig,
 
+    #[serde(default)]
+    pub remote_control: RemoteControlConfig,
+
+    #[serde(default)]
+    pub audience_mirror: AudienceMirrorConfig,
+
     #[serde(default)]
     pub export: ExportConfig,
 
@@ -93,6 +100,11 @@ pub enum ThemeConfig {
         /// Light/Dark detection timeout in ms.
         #[cfg_attr(feature = "json-schema", validate(range(min = 1)))]
         timeout: Option<u64>,
+        /// How often to check whether the terminal switched between dark and light, in ms.
+        ///
+        /// When not set, detection only happens on launch.
+        #[cfg_attr(feature = "json-schema", validate(range(min = 1)))]
+        poll_interval: Option<u64>,
     },
 }
 
@@ -256,6 +268,10 @@ pub struct OptionsConfig {
 
     /// Whether the first `h1` header on a slide should be considered a slide title.
     pub h1_slide_titles: Option<bool>,
+
+    /// Automatically move through the presentation, waiting this long before every step.
+    #[cfg_attr(feature = "json-schema", schemars(with = "Option<String>"))]
+    pub auto_advance: Option<HumanDuration>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
@@ -290,6 +306,88 @@ pub struct SnippetExecConfig {
     /// Custom snippet executors.
     #[serde(default)]
     pub custom: BTreeMap<SnippetLanguage, LanguageSnippetExecutionConfig>,
+
+    /// The limits applied to every executed snippet.
+    #[serde(default)]
+    pub limits: SnippetExecutionLimitsConfig,
+
+    /// Per language limits, which take precedence over the ones in `limits`.
+    #[serde(default)]
+    pub language_limits: BTreeMap<SnippetLanguage, SnippetExecutionLimitsConfig>,
+
+    /// The sandbox to run executed snippets in.
+    #[serde(default)]
+    pub sandbox: SnippetSandboxConfig,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct SnippetSandboxConfig {
+    /// Whether to run executed snippets inside the sandbox.
+    #[serde(default)]
+    pub enable: bool,
+
+    /// Per language overrides for `enable`.
+    #[serde(default)]
+    pub languages: BTreeMap<SnippetLanguage, bool>,
+
+    /// The command that every command used to execute a snippet is prefixed with so it runs in isolation.
+    ///
+    /// Any `$pwd` in it is replaced with the temporary directory the snippet is written to, and any `$home` with the
+    /// user's home directory.
+    #[serde(default = "default_sandbox_runner")]
+    pub runner: Vec<String>,
+}
+
+impl Default for SnippetSandboxConfig {
+    fn default() -> Self {
+        Self { enable: false, languages: Default::default(), runner: default_sandbox_runner() }
+    }
+}
+
+fn default_sandbox_runner() -> Vec<String> {
+    [
+        "bwrap",
+        "--ro-bind",
+        "/",
+        "/",
+        "--dev",
+        "/dev",
+        "--proc",
+        "/proc",
+        "--tmpfs",
+        "/tmp",
+        // Hide the home directory, which is where things like ssh keys and access tokens usually live.
+        "--tmpfs",
+        "$home",
+        "--bind",
+        "$pwd",
+        "$pwd",
+        "--unshare-all",
+        "--die-with-parent",
+        "--",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct SnippetExecutionLimitsConfig {
+    /// How long a snippet can run for before it's killed.
+    #[cfg_attr(feature = "json-schema", schemars(with = "Option<String>"))]
+    pub timeout: Option<HumanDuration>,
+
+    /// The maximum amount of memory a snippet can use, e.g. `512M`.
+    #[cfg_attr(feature = "json-schema", schemars(with = "Option<String>"))]
+    pub memory: Option<ByteSize>,
+
+    /// The maximum amount of CPU time a snippet can use, rounded up to whole seconds.
+    #[cfg_attr(feature = "json-schema", schemars(with = "Option<String>"))]
+    pub cpu_time: Option<HumanDuration>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
@@ -418,6 +516,10 @@ pub struct SnippetExecutorConfig {
 
     /// The commands to be ran when executing snippets for this programming language.
     pub commands: Vec<Vec<String>>,
+
+    /// Whether to run these commands in the sandbox, overriding `snippet.exec.sandbox`.
+    #[serde(default)]
+    pub sandbox: Option<bool>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, ValueEnum)]
@@ -508,6 +610,10 @@ pub struct KeyBindingsConfig {
     #[serde(default = "default_execute_code_bindings")]
     pub(crate) execute_code: Vec<KeyBinding>,
 
+    /// The key binding to kill any snippet running in the current slide.
+    #[serde(default = "default_kill_execution_bindings")]
+    pub(crate) kill_execution: Vec<KeyBinding>,
+
     /// The key binding to reload the presentation.
     #[serde(default = "default_reload_bindings")]
     pub(crate) reload: Vec<KeyBinding>,
@@ -516,10 +622,30 @@ pub struct KeyBindingsConfig {
     #[serde(default = "default_toggle_index_bindings")]
     pub(crate) toggle_slide_index: Vec<KeyBinding>,
 
+    /// The key binding to toggle the slide overview, which shows every slide as a thumbnail.
+    #[serde(default = "default_toggle_overview_bindings")]
+    pub(crate) toggle_overview: Vec<KeyBinding>,
+
+    /// The key binding to toggle the annotation mode, which lets you point at and draw on top of the current slide.
+    #[serde(default = "default_toggle_annotations_bindings")]
+    pub(crate) toggle_annotations: Vec<KeyBinding>,
+
+    /// The key binding to toggle the theme picker, which lets you switch the presentation's theme.
+    #[serde(default = "default_toggle_theme_picker_bindings")]
+    pub(crate) toggle_theme_picker: Vec<KeyBinding>,
+
+    /// The key binding to type a line of input for the `+interactive` snippet running in the current slide.
+    #[serde(default = "default_snippet_input_bindings")]
+    pub(crate) snippet_input: Vec<KeyBinding>,
+
     /// The key binding to toggle the key bindings modal.
     #[serde(default = "default_toggle_bindings_modal_bindings")]
     pub(crate) toggle_bindings: Vec<KeyBinding>,
 
+    /// The key binding to open the command palette, where commands like `goto 12` or `theme dark` can be typed.
+    #[serde(default = "default_command_palette_bindings")]
+    pub(crate) command_palette: Vec<KeyBinding>,
+
     /// The key binding to toggle the layout grid.
     #[serde(default = "default_toggle_layout_grid")]
     pub(crate) toggle_layout_grid: Vec<KeyBinding>,
@@ -539,6 +665,26 @@ pub struct KeyBindingsConfig {
     /// The key binding to show the entire slide, after skipping any pauses in it.
     #[serde(default = "default_skip_pauses")]
     pub(crate) skip_pauses: Vec<KeyBinding>,
+
+    /// The key binding to start searching, either in the presentation or in the slide index modal.
+    #[serde(default = "default_search_bindings")]
+    pub(crate) search: Vec<KeyBinding>,
+
+    /// The key binding to jump to the next search match.
+    ///
+    /// This is only used while searching so it can use keys that are also bound to something else.
+    #[serde(default = "default_search_next_bindings")]
+    pub(crate) search_next: Vec<KeyBinding>,
+
+    /// The key binding to jump to the previous search match.
+    ///
+    /// This is only used while searching so it can use keys that are also bound to something else.
+    #[serde(default = "default_search_previous_bindings")]
+    pub(crate) search_previous: Vec<KeyBinding>,
+
+    /// The mouse bindings.
+    #[serde(default)]
+    pub(crate) mouse: MouseBindingsConfig,
 }
 
 impl Default for KeyBindingsConfig {
@@ -552,18 +698,50 @@ impl Default for KeyBindingsConfig {
             last_slide: default_last_slide_bindings(),
             go_to_slide: default_go_to_slide_bindings(),
             execute_code: default_execute_code_bindings(),
+            kill_execution: default_kill_execution_bindings(),
             reload: default_reload_bindings(),
             toggle_slide_index: default_toggle_index_bindings(),
+            toggle_overview: default_toggle_overview_bindings(),
+            toggle_annotations: default_toggle_annotations_bindings(),
+            toggle_theme_picker: default_toggle_theme_picker_bindings(),
+            snippet_input: default_snippet_input_bindings(),
             toggle_bindings: default_toggle_bindings_modal_bindings(),
+            command_palette: default_command_palette_bindings(),
             toggle_layout_grid: default_toggle_layout_grid(),
             close_modal: default_close_modal_bindings(),
             exit: default_exit_bindings(),
             suspend: default_suspend_bindings(),
             skip_pauses: default_skip_pauses(),
+            search: default_search_bindings(),
+            search_next: default_search_next_bindings(),
+            search_previous: default_search_previous_bindings(),
+            mouse: Default::default(),
         }
     }
 }
 
+#[derive(Clone, Debug, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct MouseBindingsConfig {
+    /// Whether to capture mouse events so the mouse can be used to navigate the presentation.
+    ///
+    /// Capturing the mouse prevents selecting text in the terminal unless a modifier key, typically shift, is held.
+    #[serde(default)]
+    pub(crate) enable: bool,
+
+    /// The percentage of the screen's width, starting from the left edge, where clicking moves backwards in the
+    /// presentation. Clicking anywhere else moves forwards.
+    #[serde(default = "default_mouse_previous_area")]
+    pub(crate) previous_area: u8,
+}
+
+impl Default for MouseBindingsConfig {
+    fn default() -> Self {
+        Self { enable: false, previous_area: default_mouse_previous_area() }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize)]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[serde(deny_unknown_fields)]
@@ -579,6 +757,18 @@ pub struct SpeakerNotesConfig {
     /// Whether to always publish speaker notes.
     #[serde(default)]
     pub always_publish: bool,
+
+    /// The layout to use when listening for speaker notes.
+    #[serde(default)]
+    pub layout: SpeakerNotesLayout,
+
+    /// Whether speaker notes instances can control the main presentation.
+    #[serde(default)]
+    pub enable_control: bool,
+
+    /// The address in which speaker notes instances send control events to the main presentation.
+    #[serde(default = "default_speaker_notes_control_address")]
+    pub control_address: SocketAddr,
 }
 
 impl Default for SpeakerNotesConfig {
@@ -587,10 +777,70 @@ impl Default for SpeakerNotesConfig {
             listen_address: default_speaker_notes_listen_address(),
             publish_address: default_speaker_notes_publish_address(),
             always_publish: false,
+            layout: Default::default(),
+            enable_control: false,
+            control_address: default_speaker_notes_control_address(),
         }
     }
 }
 
+/// The layout to use when listening for speaker notes.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum SpeakerNotesLayout {
+    /// Only display the speaker notes.
+    #[default]
+    Notes,
+
+    /// Display the speaker notes along with a preview of the next slide, the slide counter, and a timer.
+    SpeakerView,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct RemoteControlConfig {
+    /// Whether to enable the remote control server.
+    #[serde(default)]
+    pub enable: bool,
+
+    /// The address in which to listen for remote control requests.
+    #[serde(default = "default_remote_control_address")]
+    pub address: SocketAddr,
+
+    /// The token that remote control clients must provide in every request.
+    pub token: Option<String>,
+}
+
+impl Default for RemoteControlConfig {
+    fn default() -> Self {
+        Self { enable: false, address: default_remote_control_address(), token: None }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct AudienceMirrorConfig {
+    /// Whether to serve the presentation being displayed to browsers.
+    #[serde(default)]
+    pub enable: bool,
+
+    /// The address in which to serve the presentation.
+    #[serde(default = "default_audience_mirror_address")]
+    pub address: SocketAddr,
+
+    /// The token that browsers must provide in order to view the presentation.
+    pub token: Option<String>,
+}
+
+impl Default for AudienceMirrorConfig {
+    fn default() -> Self {
+        Self { enable: false, address: default_audience_mirror_address(), token: None }
+    }
+}
+
 /// The export configuration.
 #[derive(Clone, Debug, Default, Deserialize)]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
@@ -610,6 +860,14 @@ pub struct ExportConfig {
     /// The PDF specific export configs.
     #[serde(default)]
     pub pdf: PdfExportConfig,
+
+    /// The GIF specific export configs.
+    #[serde(default)]
+    pub gif: GifExportConfig,
+
+    /// The SVG and PNG specific export configs.
+    #[serde(default)]
+    pub images: ImagesExportConfig,
 }
 
 /// The policy for pauses when exporting.
@@ -657,6 +915,91 @@ pub struct ExportDimensionsConfig {
 pub struct PdfExportConfig {
     /// The path to the font file to be used.
     pub fonts: Option<ExportFontsConfig>,
+
+    /// The backend used to generate PDF files.
+    #[serde(default)]
+    pub backend: PdfExportBackend,
+}
+
+/// The backend used to generate PDF files.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum PdfExportBackend {
+    /// Generate an HTML file and convert it into PDF using weasyprint.
+    #[default]
+    Weasyprint,
+
+    /// Write the PDF directly, without needing any external tools.
+    Native,
+}
+
+/// The GIF export specific configs.
+#[derive(Clone, Debug, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub struct GifExportConfig {
+    /// The fonts to be used. If none are set, a monospace font installed in the system will be used.
+    pub fonts: Option<ExportFontsConfig>,
+
+    /// The font size, in pixels.
+    #[serde(default = "default_gif_font_size")]
+    pub font_size: u16,
+
+    /// The amount of time every slide chunk is displayed for.
+    #[serde(default = "default_gif_chunk_duration_millis")]
+    pub chunk_duration_millis: u64,
+
+    /// Whether to include the configured slide transition when moving between slides.
+    #[serde(default)]
+    pub transitions: bool,
+}
+
+impl Default for GifExportConfig {
+    fn default() -> Self {
+        Self {
+            fonts: None,
+            font_size: default_gif_font_size(),
+            chunk_duration_millis: default_gif_chunk_duration_millis(),
+            transitions: false,
+        }
+    }
+}
+
+/// The SVG and PNG export specific configs.
+#[derive(Clone, Debug, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub struct ImagesExportConfig {
+    /// The name of every generated file, without extension.
+    ///
+    /// `{slide}` is replaced with the slide number and `{chunk}` with the chunk number.
+    #[serde(default = "default_images_file_name")]
+    pub file_name: String,
+
+    /// Whether to generate an image for every slide chunk rather than one for every slide.
+    #[serde(default)]
+    pub chunks: bool,
+
+    /// The fonts to be used for PNG files. If none are set, a monospace font installed in the system will be used.
+    ///
+    /// SVG files prefer the family of the normal font, if set.
+    pub fonts: Option<ExportFontsConfig>,
+
+    /// The font size, in pixels, for PNG files.
+    #[serde(default = "default_images_font_size")]
+    pub font_size: u16,
+}
+
+impl Default for ImagesExportConfig {
+    fn default() -> Self {
+        Self {
+            file_name: default_images_file_name(),
+            chunks: false,
+            fonts: None,
+            font_size: default_images_font_size(),
+        }
+    }
 }
 
 /// The fonts used for exports.
@@ -707,6 +1050,9 @@ pub enum SlideTransitionStyleConfig {
 
     /// Collapse the current slide into the center of the screen.
     CollapseHorizontal,
+
+    /// Keep the lines both slides share in place while removing and adding the rest one at a time.
+    MagicMove,
 }
 
 fn make_keybindings<const N: usize>(raw_bindings: [&str; N]) -> Vec<KeyBinding> {
@@ -749,6 +1095,10 @@ fn default_execute_code_bindings() -> Vec<KeyBinding> {
     make_keybindings(["<c-e>"])
 }
 
+fn default_kill_execution_bindings() -> Vec<KeyBinding> {
+    make_keybindings(["<c-k>"])
+}
+
 fn default_reload_bindings() -> Vec<KeyBinding> {
     make_keybindings(["<c-r>"])
 }
@@ -757,10 +1107,34 @@ fn default_toggle_index_bindings() -> Vec<KeyBinding> {
     make_keybindings(["<c-p>"])
 }
 
+fn default_toggle_overview_bindings() -> Vec<KeyBinding> {
+    make_keybindings(["o"])
+}
+
+fn default_mouse_previous_area() -> u8 {
+    25
+}
+
+fn default_toggle_annotations_bindings() -> Vec<KeyBinding> {
+    make_keybindings(["a"])
+}
+
+fn default_toggle_theme_picker_bindings() -> Vec<KeyBinding> {
+    make_keybindings(["t"])
+}
+
+fn default_snippet_input_bindings() -> Vec<KeyBinding> {
+    make_keybindings(["i"])
+}
+
 fn default_toggle_bindings_modal_bindings() -> Vec<KeyBinding> {
     make_keybindings(["?"])
 }
 
+fn default_command_palette_bindings() -> Vec<KeyBinding> {
+    make_keybindings([":"])
+}
+
 fn default_toggle_layout_grid() -> Vec<KeyBinding> {
     make_keybindings(["T"])
 }
@@ -781,6 +1155,18 @@ fn default_skip_pauses() -> Vec<KeyBinding> {
     make_keybindings(["s"])
 }
 
+fn default_search_bindings() -> Vec<KeyBinding> {
+    make_keybindings(["/"])
+}
+
+fn default_search_next_bindings() -> Vec<KeyBinding> {
+    make_keybindings(["n"])
+}
+
+fn default_search_previous_bindings() -> Vec<KeyBinding> {
+    make_keybindings(["N"])
+}
+
 fn default_transition_duration_millis() -> u16 {
     1000
 }
@@ -789,6 +1175,22 @@ fn default_transition_frames() -> usize {
     30
 }
 
+fn default_gif_font_size() -> u16 {
+    16
+}
+
+fn default_gif_chunk_duration_millis() -> u64 {
+    2000
+}
+
+fn default_images_file_name() -> String {
+    "slide-{slide}".into()
+}
+
+fn default_images_font_size() -> u16 {
+    16
+}
+
 #[cfg(target_os = "linux")]
 pub(crate) fn default_speaker_notes_listen_address() -> SocketAddr {
     SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 255, 255, 255)), 59418)
@@ -809,6 +1211,24 @@ pub(crate) fn default_speaker_notes_publish_address() -> SocketAddr {
     SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 59418)
 }
 
+#[cfg(target_os = "linux")]
+pub(crate) fn default_speaker_notes_control_address() -> SocketAddr {
+    SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 255, 255, 255)), 59419)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn default_speaker_notes_control_address() -> SocketAddr {
+    SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 59419)
+}
+
+fn default_remote_control_address() -> SocketAddr {
+    SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 59420)
+}
+
+fn default_audience_mirror_address() -> SocketAddr {
+    SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 59421)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
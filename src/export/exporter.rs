@@ -1,8 +1,18 @@
 use crate::{
     MarkdownParser, Resources,
     code::execute::SnippetExecutor,
-    config::{KeyBindingsConfig, PauseExportPolicy, PdfExportConfig, SnippetsExportPolicy},
-    export::output::{ExportRenderer, OutputFormat},
+    config::{
+        GifExportConfig, ImagesExportConfig, KeyBindingsConfig, PauseExportPolicy, PdfExportBackend, PdfExportConfig,
+        SlideTransitionConfig, SlideTransitionStyleConfig, SnippetsExportPolicy,
+    },
+    export::{
+        fonts::{ExportFonts, LoadFontError},
+        gif::GifWriter,
+        images::{FileNamePattern, ImageExportFormat, SlideImageWriter},
+        output::{ExportRenderer, OutputFormat},
+        pdf::PdfDocument,
+        raster::GridRasterizer,
+    },
     markdown::text_style::Color,
     presentation::{
         Presentation,
@@ -11,13 +21,23 @@ use crate::{
     },
     render::{
         RenderError,
+        engine::RenderEngine,
         operation::{AsRenderOperations, PollableState, RenderOperation},
         properties::WindowSize,
     },
-    terminal::image::printer::{ImagePrinter, ImageRegistry},
+    terminal::{
+        image::printer::{ImagePrinter, ImageRegistry},
+        printer::TerminalIo,
+        virt::{ImageBehavior, TerminalGrid, VirtualTerminal},
+    },
     theme::{ProcessingThemeError, raw::PresentationTheme},
     third_party::ThirdPartyRender,
     tools::{ExecutionError, ThirdPartyTools},
+    transitions::{
+        AnimateTransition, AnimationFrame, LinesFrame, TransitionDirection,
+        collapse_horizontal::CollapseHorizontalAnimation, fade::FadeAnimation, magic_move::MagicMoveAnimation,
+        slide_horizontal::SlideHorizontalAnimation,
+    },
 };
 use crossterm::{
     cursor::{MoveToColumn, MoveToNextLine, MoveUp},
@@ -31,6 +51,7 @@ use std::{
     path::{Path, PathBuf},
     rc::Rc,
     sync::Arc,
+    time::Duration,
 };
 use tempfile::TempDir;
 
@@ -90,6 +111,8 @@ impl<'a> Exporter<'a> {
     ) -> Self {
         // We don't want dynamically highlighted code blocks.
         options.allow_mutations = false;
+        // There's no one to type input so snippets can't wait for it.
+        options.enable_interactive_snippets = false;
         options.theme_options.font_size_supported = true;
         options.pause_create_new_slide = match pause_policy {
             PauseExportPolicy::Ignore => false,
@@ -114,12 +137,7 @@ impl<'a> Exporter<'a> {
         }
     }
 
-    fn build_renderer(
-        &mut self,
-        presentation_path: &Path,
-        output_directory: OutputDirectory,
-        renderer: OutputFormat,
-    ) -> Result<ExportRenderer, ExportError> {
+    fn build_presentation(&mut self, presentation_path: &Path) -> Result<Presentation, ExportError> {
         let mut presentation = PresentationBuilder::new(
             self.default_theme,
             self.resources.clone(),
@@ -132,15 +150,25 @@ impl<'a> Exporter<'a> {
             self.options.clone(),
         )?
         .build(presentation_path)?;
-        Self::validate_theme_colors(&presentation)?;
 
-        let mut render = ExportRenderer::new(self.dimensions, output_directory, renderer);
         Self::log("waiting for images to be generated and code to be executed, if any...")?;
         match self.snippet_policy {
             SnippetsExportPolicy::Parallel => Self::wait_async_renders_parallel(&mut presentation)?,
             SnippetsExportPolicy::Sequential => Self::wait_async_renders_sequential(&mut presentation)?,
         };
+        Ok(presentation)
+    }
 
+    fn build_renderer(
+        &mut self,
+        presentation_path: &Path,
+        output_directory: OutputDirectory,
+        renderer: OutputFormat,
+    ) -> Result<ExportRenderer, ExportError> {
+        let presentation = self.build_presentation(presentation_path)?;
+        Self::validate_theme_colors(&presentation)?;
+
+        let mut render = ExportRenderer::new(self.dimensions, output_directory, renderer);
         for (index, slide) in presentation.into_slides().into_iter().enumerate() {
             let index = index + 1;
             Self::log(&format!("processing slide {index}..."))?;
@@ -164,18 +192,33 @@ impl<'a> Exporter<'a> {
             self.dimensions.rows, self.dimensions.columns, self.dimensions.width, self.dimensions.height
         );
 
-        println!("checking for weasyprint...");
-        Self::validate_weasyprint_exists()?;
-        Self::log("weasyprint installation found")?;
-
-        let render = self.build_renderer(presentation_path, output_directory, OutputFormat::Pdf)?;
-
         let pdf_path = match output_path {
             Some(path) => path.to_path_buf(),
             None => presentation_path.with_extension("pdf"),
         };
-
-        render.generate(&pdf_path, &config.fonts)?;
+        match config.backend {
+            PdfExportBackend::Weasyprint => {
+                println!("checking for weasyprint...");
+                Self::validate_weasyprint_exists()?;
+                Self::log("weasyprint installation found")?;
+
+                let render = self.build_renderer(presentation_path, output_directory, OutputFormat::Pdf)?;
+                render.generate(&pdf_path, &config.fonts)?;
+            }
+            PdfExportBackend::Native => {
+                let fonts = ExportFonts::load(config.fonts.as_ref())?;
+                let presentation = self.build_presentation(presentation_path)?;
+                Self::validate_theme_colors(&presentation)?;
+
+                let mut document = PdfDocument::new(fonts);
+                for (index, slide) in presentation.iter_slides().enumerate() {
+                    Self::log(&format!("processing slide {}...", index + 1))?;
+                    let grid = self.render_operations(slide.iter_operations())?;
+                    document.add_page(&grid)?;
+                }
+                fs::write(&pdf_path, document.finish())?;
+            }
+        };
 
         execute!(
             io::stdout(),
@@ -216,6 +259,213 @@ impl<'a> Exporter<'a> {
         Ok(())
     }
 
+    /// Export the given presentation into an animated GIF.
+    pub fn export_gif(
+        mut self,
+        presentation_path: &Path,
+        output_path: Option<&Path>,
+        config: GifExportConfig,
+        transition: Option<SlideTransitionConfig>,
+    ) -> Result<(), ExportError> {
+        let rasterizer = GridRasterizer::new(ExportFonts::load(config.fonts.as_ref())?, config.font_size);
+        // Use the real pixel size so images take up the same space they'll be drawn with.
+        let (cell_width, cell_height) = rasterizer.cell_size();
+        self.dimensions.width = (self.dimensions.columns as u32 * cell_width).try_into().unwrap_or(u16::MAX);
+        self.dimensions.height = (self.dimensions.rows as u32 * cell_height).try_into().unwrap_or(u16::MAX);
+        println!(
+            "exporting using rows={}, columns={}, width={}, height={}",
+            self.dimensions.rows, self.dimensions.columns, self.dimensions.width, self.dimensions.height
+        );
+
+        // Every chunk and highlighted line group gets its own frame so these need to be kept.
+        self.options.pause_create_new_slide = false;
+        self.options.allow_mutations = true;
+        let mut presentation = self.build_presentation(presentation_path)?;
+
+        let output_path = match output_path {
+            Some(path) => path.to_path_buf(),
+            None => presentation_path.with_extension("gif"),
+        };
+        let mut writer = GifWriter::create(&output_path, rasterizer)?;
+        let chunk_duration = Duration::from_millis(config.chunk_duration_millis);
+        let transition = transition.filter(|_| config.transitions);
+
+        presentation.jump_first_slide();
+        Self::log("processing slide 1...")?;
+        let mut current = self.render_operations(presentation.current_slide().iter_visible_operations())?;
+        loop {
+            let slide_index = presentation.current_slide_index();
+            writer.push(current.clone(), chunk_duration)?;
+            if !presentation.jump_next() {
+                break;
+            }
+            let next = self.render_operations(presentation.current_slide().iter_visible_operations())?;
+            if presentation.current_slide_index() != slide_index {
+                Self::log(&format!("processing slide {}...", presentation.current_slide_index() + 1))?;
+                if let Some(transition) = &transition {
+                    self.write_transition(&mut writer, current, next.clone(), transition)?;
+                }
+            }
+            current = next;
+        }
+        writer.finish()?;
+
+        execute!(
+            io::stdout(),
+            PrintStyledContent(
+                format!("output file is at {}\n", output_path.display()).stylize().with(Color::Green.into())
+            )
+        )?;
+        Ok(())
+    }
+
+    /// Export every slide in the given presentation into its own SVG or PNG file.
+    pub fn export_images(
+        mut self,
+        presentation_path: &Path,
+        output_path: Option<&Path>,
+        format: ImageExportFormat,
+        config: ImagesExportConfig,
+    ) -> Result<(), ExportError> {
+        let mut file_names = FileNamePattern::new(config.file_name, format, config.chunks)?;
+        let writer = match format {
+            ImageExportFormat::Svg => {
+                SlideImageWriter::Svg { font_families: ExportFonts::family_names(config.fonts.as_ref()) }
+            }
+            ImageExportFormat::Png => {
+                let fonts = ExportFonts::load(config.fonts.as_ref())?;
+                SlideImageWriter::Png(Box::new(GridRasterizer::new(fonts, config.font_size)))
+            }
+        };
+        // Use the real pixel size so images take up the same space they'll be drawn with.
+        let (cell_width, cell_height) = writer.cell_size();
+        self.dimensions.width = (self.dimensions.columns as f64 * cell_width).min(u16::MAX as f64) as u16;
+        self.dimensions.height = (self.dimensions.rows as f64 * cell_height).min(u16::MAX as f64) as u16;
+        println!(
+            "exporting using rows={}, columns={}, width={}, height={}",
+            self.dimensions.rows, self.dimensions.columns, self.dimensions.width, self.dimensions.height
+        );
+
+        if config.chunks {
+            // Every chunk and highlighted line group gets its own image so these need to be kept.
+            self.options.pause_create_new_slide = false;
+            self.options.allow_mutations = true;
+        }
+        let mut presentation = self.build_presentation(presentation_path)?;
+        Self::validate_theme_colors(&presentation)?;
+
+        let output_directory = match output_path {
+            Some(path) => path.to_path_buf(),
+            None => {
+                let name = presentation_path.file_stem().unwrap_or_default().to_string_lossy();
+                presentation_path.with_file_name(format!("{name}-slides"))
+            }
+        };
+        fs::create_dir_all(&output_directory)?;
+        file_names.set_total_slides(presentation.iter_slides().count());
+
+        if config.chunks {
+            presentation.jump_first_slide();
+            let mut chunk = 1;
+            loop {
+                let slide_index = presentation.current_slide_index();
+                if chunk == 1 {
+                    Self::log(&format!("processing slide {}...", slide_index + 1))?;
+                }
+                let grid = self.render_operations(presentation.current_slide().iter_visible_operations())?;
+                writer.write(&grid, &output_directory.join(file_names.file_name(slide_index + 1, chunk)))?;
+                if !presentation.jump_next() {
+                    break;
+                }
+                chunk = if presentation.current_slide_index() == slide_index { chunk + 1 } else { 1 };
+            }
+        } else {
+            for (index, slide) in presentation.iter_slides().enumerate() {
+                Self::log(&format!("processing slide {}...", index + 1))?;
+                let grid = self.render_operations(slide.iter_operations())?;
+                writer.write(&grid, &output_directory.join(file_names.file_name(index + 1, 1)))?;
+            }
+        }
+
+        execute!(
+            io::stdout(),
+            PrintStyledContent(
+                format!("output files are at {}\n", output_directory.display()).stylize().with(Color::Green.into())
+            )
+        )?;
+        Ok(())
+    }
+
+    fn render_operations<'b>(
+        &self,
+        operations: impl Iterator<Item = &'b RenderOperation>,
+    ) -> Result<TerminalGrid, ExportError> {
+        let mut terminal = VirtualTerminal::new(self.dimensions, ImageBehavior::Store);
+        let engine = RenderEngine::new(&mut terminal, self.dimensions, Default::default());
+        engine.render(operations)?;
+        Ok(terminal.into_contents())
+    }
+
+    fn write_transition(
+        &self,
+        writer: &mut GifWriter,
+        left: TerminalGrid,
+        right: TerminalGrid,
+        config: &SlideTransitionConfig,
+    ) -> Result<(), ExportError> {
+        let first = left.clone();
+        let direction = TransitionDirection::Next;
+        match &config.animation {
+            SlideTransitionStyleConfig::SlideHorizontal => {
+                let animation = SlideHorizontalAnimation::new(left, right, self.dimensions, direction);
+                self.write_animation(writer, first, animation, config)
+            }
+            SlideTransitionStyleConfig::Fade => {
+                self.write_animation(writer, first, FadeAnimation::new(left, right, direction), config)
+            }
+            SlideTransitionStyleConfig::CollapseHorizontal => {
+                let animation = CollapseHorizontalAnimation::new(left, right, direction);
+                self.write_animation(writer, first, animation, config)
+            }
+            SlideTransitionStyleConfig::MagicMove => {
+                self.write_animation(writer, first, MagicMoveAnimation::new(left, right, direction), config)
+            }
+        }
+    }
+
+    fn write_animation<T>(
+        &self,
+        writer: &mut GifWriter,
+        first: TerminalGrid,
+        animation: T,
+        config: &SlideTransitionConfig,
+    ) -> Result<(), ExportError>
+    where
+        T: AnimateTransition,
+    {
+        let frames = config.frames.max(1);
+        let total_frames = animation.total_frames();
+        let step = Duration::from_millis(config.duration_millis as u64) / frames as u32;
+
+        // Frames only contain what changed since the last one so apply them on top of each other.
+        let mut terminal = VirtualTerminal::new(self.dimensions, ImageBehavior::Store);
+        for command in LinesFrame::from(&first).build_commands() {
+            terminal.execute(&command).map_err(RenderError::from)?;
+        }
+        let mut last_frame_index = 0;
+        let mut frame_index = 1;
+        while frame_index < total_frames {
+            let frame = animation.build_frame(frame_index, last_frame_index);
+            for command in frame.build_commands() {
+                terminal.execute(&command).map_err(RenderError::from)?;
+            }
+            writer.push(terminal.contents(), step)?;
+            last_frame_index = frame_index;
+            frame_index += total_frames.div_ceil(frames);
+        }
+        Ok(())
+    }
+
     fn wait_async_renders_parallel(presentation: &mut Presentation) -> Result<(), ExportError> {
         let poller = Poller::launch();
         let mut pollables = Vec::new();
@@ -343,6 +593,12 @@ pub enum ExportError {
     #[error("weasyprint not found")]
     WeasyprintMissing,
 
+    #[error("file name pattern must contain {0}")]
+    InvalidFileName(&'static str),
+
+    #[error("loading font: {0}")]
+    LoadFont(#[from] LoadFontError),
+
     #[error("processing theme: {0}")]
     ProcessingTheme(#[from] ProcessingThemeError),
 
@@ -0,0 +1,255 @@
+use crate::{config::ExportFontsConfig, markdown::text_style::TextStyle};
+use ab_glyph::{Font, FontVec};
+use fontdb::{Database, Family, Query, Style, Weight};
+use std::{
+    collections::HashSet,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+// The font families we look for when no font is configured, before falling back to any monospace one.
+const SYSTEM_FONT_FAMILIES: &[&str] = &["DejaVu Sans Mono", "Liberation Mono", "Noto Sans Mono", "Menlo", "Consolas"];
+
+/// A variant of a font.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum FontVariant {
+    Normal,
+    Bold,
+    Italic,
+    BoldItalic,
+}
+
+impl FontVariant {
+    fn is_bold(&self) -> bool {
+        matches!(self, Self::Bold | Self::BoldItalic)
+    }
+
+    fn is_italic(&self) -> bool {
+        matches!(self, Self::Italic | Self::BoldItalic)
+    }
+}
+
+/// The font picked to draw a character.
+pub(crate) struct SelectedFont<'a> {
+    pub(crate) font: &'a FontVec,
+    pub(crate) variant: FontVariant,
+    /// Whether the style requires bold text but the font isn't bold.
+    pub(crate) synthetic_bold: bool,
+    /// Whether the style requires italic text but the font isn't italic.
+    pub(crate) synthetic_italics: bool,
+}
+
+/// The fonts used to draw presentations when exporting them.
+pub(crate) struct ExportFonts {
+    normal: FontVec,
+    bold: Option<FontVec>,
+    italic: Option<FontVec>,
+    bold_italic: Option<FontVec>,
+}
+
+impl ExportFonts {
+    /// Load the given fonts, or find a monospace one installed in the system if none are provided.
+    pub(crate) fn load(config: Option<&ExportFontsConfig>) -> Result<Self, LoadFontError> {
+        match config {
+            Some(config) => Self::load_files(config),
+            None => Self::find_system(),
+        }
+    }
+
+    /// Get the names of the font families to use, in order of preference.
+    ///
+    /// These are the families of the given fonts, if any, followed by the ones we look for in the system.
+    pub(crate) fn family_names(config: Option<&ExportFontsConfig>) -> Vec<String> {
+        let mut database = Database::new();
+        if let Some(config) = config {
+            // This is only a preference so there's nothing to do if the font can't be loaded.
+            let _ = database.load_font_file(&config.normal);
+        }
+        let mut names: Vec<String> =
+            database.faces().flat_map(|face| face.families.iter().map(|(name, _)| name.clone())).collect();
+        names.extend(SYSTEM_FONT_FAMILIES.iter().map(|name| name.to_string()));
+        // A configured font can also be one of the system ones so duplicates aren't necessarily next to each other.
+        let mut seen = HashSet::new();
+        names.retain(|name| seen.insert(name.clone()));
+        names
+    }
+
+    /// Get the font for a variant, if it was loaded.
+    pub(crate) fn variant(&self, variant: FontVariant) -> Option<&FontVec> {
+        match variant {
+            FontVariant::Normal => Some(&self.normal),
+            FontVariant::Bold => self.bold.as_ref(),
+            FontVariant::Italic => self.italic.as_ref(),
+            FontVariant::BoldItalic => self.bold_italic.as_ref(),
+        }
+    }
+
+    /// The font used for text that has no bold/italics.
+    pub(crate) fn normal(&self) -> &FontVec {
+        &self.normal
+    }
+
+    /// Pick the font to draw a character that uses the given style.
+    pub(crate) fn select(&self, style: &TextStyle, character: char) -> SelectedFont<'_> {
+        let (bold, italics) = (style.is_bold(), style.is_italics());
+        let candidates = [
+            (bold && italics, FontVariant::BoldItalic),
+            (bold, FontVariant::Bold),
+            (italics, FontVariant::Italic),
+            (true, FontVariant::Normal),
+        ];
+        for (wanted, variant) in candidates {
+            let Some(font) = self.variant(variant).filter(|_| wanted) else {
+                continue;
+            };
+            // Glyph 0 is the "missing glyph" one so fall back to the normal font if this one doesn't have it.
+            if variant != FontVariant::Normal && font.glyph_id(character).0 == 0 {
+                continue;
+            }
+            return SelectedFont {
+                font,
+                variant,
+                synthetic_bold: bold && !variant.is_bold(),
+                synthetic_italics: italics && !variant.is_italic(),
+            };
+        }
+        unreachable!("normal font is always selectable")
+    }
+
+    /// Load a font that ships with the source code so tests don't depend on the fonts installed in the system.
+    #[cfg(test)]
+    pub(crate) fn fixture() -> Self {
+        let data = include_bytes!("fixtures/Tuffy.ttf").to_vec();
+        let normal = FontVec::try_from_vec(data).expect("invalid fixture font");
+        Self { normal, bold: None, italic: None, bold_italic: None }
+    }
+
+    /// Like [ExportFonts::fixture] but using a font that has CFF outlines.
+    #[cfg(test)]
+    pub(crate) fn cff_fixture() -> Self {
+        let data = include_bytes!("fixtures/CffTest.otf").to_vec();
+        let normal = FontVec::try_from_vec(data).expect("invalid fixture font");
+        Self { normal, bold: None, italic: None, bold_italic: None }
+    }
+
+    fn load_files(config: &ExportFontsConfig) -> Result<Self, LoadFontError> {
+        let load_optional = |path: &Option<PathBuf>| path.as_deref().map(Self::load_file).transpose();
+        Ok(Self {
+            normal: Self::load_file(&config.normal)?,
+            bold: load_optional(&config.bold)?,
+            italic: load_optional(&config.italic)?,
+            bold_italic: load_optional(&config.bold_italic)?,
+        })
+    }
+
+    fn load_file(path: &Path) -> Result<FontVec, LoadFontError> {
+        let data = fs::read(path).map_err(|e| LoadFontError::Io(path.into(), e))?;
+        if is_collection(&data) {
+            return Err(LoadFontError::Collection(path.into()));
+        }
+        FontVec::try_from_vec(data).map_err(|_| LoadFontError::Invalid(path.into()))
+    }
+
+    fn find_system() -> Result<Self, LoadFontError> {
+        let mut database = Database::new();
+        database.load_system_fonts();
+        let normal = Self::find_face(&database, false, false).ok_or(LoadFontError::NotFound)?;
+        Ok(Self {
+            normal,
+            bold: Self::find_face(&database, true, false),
+            italic: Self::find_face(&database, false, true),
+            bold_italic: Self::find_face(&database, true, true),
+        })
+    }
+
+    fn find_face(database: &Database, bold: bool, italic: bool) -> Option<FontVec> {
+        let weight = if bold { Weight::BOLD } else { Weight::NORMAL };
+        let style = if italic { Style::Italic } else { Style::Normal };
+        let mut families: Vec<_> = SYSTEM_FONT_FAMILIES.iter().map(|name| Family::Name(name)).collect();
+        families.push(Family::Monospace);
+        let query = Query { families: &families, weight, style, ..Default::default() };
+        // Queries return the closest match so make sure we don't pick a regular face when looking for a bold one.
+        let matches = |face: &&fontdb::FaceInfo| {
+            face.monospaced && (face.weight >= Weight::SEMIBOLD) == bold && (face.style != Style::Normal) == italic
+        };
+        let preferred = database.query(&query).and_then(|id| database.face(id));
+        preferred
+            .into_iter()
+            .chain(database.faces())
+            .filter(matches)
+            .find_map(|face| database.with_face_data(face.id, Self::parse_face).flatten())
+    }
+
+    fn parse_face(data: &[u8], index: u32) -> Option<FontVec> {
+        if is_collection(data) {
+            return None;
+        }
+        FontVec::try_from_vec_and_index(data.to_vec(), index).ok()
+    }
+}
+
+// Font collections can't be embedded in PDFs as a single font so they're not supported.
+fn is_collection(data: &[u8]) -> bool {
+    data.starts_with(b"ttcf")
+}
+
+/// Whether a font uses CFF outlines rather than TrueType ones, which changes how it's embedded in PDFs.
+pub(crate) fn has_cff_outlines(font: &FontVec) -> bool {
+    font.as_slice().starts_with(b"OTTO")
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum LoadFontError {
+    #[error("reading font {0:?}: {1}")]
+    Io(PathBuf, io::Error),
+
+    #[error("invalid font file {0:?}")]
+    Invalid(PathBuf),
+
+    #[error("font collection {0:?} is not supported, please use a single font file")]
+    Collection(PathBuf),
+
+    #[error("no monospace font found in the system, please configure one")]
+    NotFound,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use std::io::Write;
+
+    fn fixture_path() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("src/export/fixtures/Tuffy.ttf")
+    }
+
+    #[test]
+    fn load_font_file() {
+        let font = ExportFonts::load_file(&fixture_path()).expect("load failed");
+        assert_ne!(font.glyph_id('a').0, 0);
+    }
+
+    #[test]
+    fn family_names() {
+        let config = ExportFontsConfig { normal: fixture_path(), bold: None, italic: None, bold_italic: None };
+        let names = ExportFonts::family_names(Some(&config));
+        assert_eq!(names[0], "Tuffy");
+        assert_eq!(&names[1..], SYSTEM_FONT_FAMILIES);
+    }
+
+    #[rstest]
+    #[case::truetype(ExportFonts::fixture(), false)]
+    #[case::cff(ExportFonts::cff_fixture(), true)]
+    fn cff_outlines(#[case] fonts: ExportFonts, #[case] expected: bool) {
+        assert_eq!(has_cff_outlines(fonts.normal()), expected);
+        assert_ne!(fonts.normal().glyph_id('a').0, 0);
+    }
+
+    #[test]
+    fn reject_collection() {
+        let mut file = tempfile::NamedTempFile::new().expect("failed to create tempfile");
+        file.write_all(b"ttcf\x00\x01\x00\x00").expect("write failed");
+        let result = ExportFonts::load_file(file.path());
+        assert!(matches!(result, Err(LoadFontError::Collection(_))));
+    }
+}
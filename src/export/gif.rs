@@ -0,0 +1,57 @@
+use super::raster::GridRasterizer;
+use crate::terminal::virt::TerminalGrid;
+use image::{
+    Delay, Frame, ImageError,
+    codecs::gif::{GifEncoder, Repeat},
+};
+use std::{fs::File, io::BufWriter, path::Path, time::Duration};
+
+// The encoding speed, from 1 to 30. Higher values are faster but produce lower quality images.
+const ENCODING_SPEED: i32 = 10;
+
+/// Writes rendered grids as frames of an animated GIF.
+pub(crate) struct GifWriter {
+    encoder: GifEncoder<BufWriter<File>>,
+    rasterizer: GridRasterizer,
+    pending: Option<(TerminalGrid, Duration)>,
+}
+
+impl GifWriter {
+    /// Create a GIF in the given path.
+    pub(crate) fn create(path: &Path, rasterizer: GridRasterizer) -> Result<Self, ImageError> {
+        let file = BufWriter::new(File::create(path)?);
+        let mut encoder = GifEncoder::new_with_speed(file, ENCODING_SPEED);
+        encoder.set_repeat(Repeat::Infinite)?;
+        Ok(Self { encoder, rasterizer, pending: None })
+    }
+
+    /// Push a frame that will be displayed for the given duration.
+    ///
+    /// Consecutive identical frames are merged into a single one.
+    pub(crate) fn push(&mut self, grid: TerminalGrid, duration: Duration) -> Result<(), ImageError> {
+        match &mut self.pending {
+            Some((pending, pending_duration)) if *pending == grid => {
+                *pending_duration += duration;
+                Ok(())
+            }
+            _ => match self.pending.replace((grid, duration)) {
+                Some((grid, duration)) => self.encode(&grid, duration),
+                None => Ok(()),
+            },
+        }
+    }
+
+    /// Write any pending frames.
+    pub(crate) fn finish(mut self) -> Result<(), ImageError> {
+        if let Some((grid, duration)) = self.pending.take() {
+            self.encode(&grid, duration)?;
+        }
+        Ok(())
+    }
+
+    fn encode(&mut self, grid: &TerminalGrid, duration: Duration) -> Result<(), ImageError> {
+        let image = self.rasterizer.rasterize(grid)?;
+        let frame = Frame::from_parts(image, 0, 0, Delay::from_saturating_duration(duration));
+        self.encoder.encode_frame(frame)
+    }
+}
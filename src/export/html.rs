@@ -53,6 +53,39 @@ impl fmt::Display for HtmlText {
     }
 }
 
+/// A piece of text that links to a URL.
+///
+/// Only web, email, and relative links are rendered as links, anything else is rendered as plain text.
+pub(crate) struct HtmlLink<'a> {
+    pub(crate) url: &'a str,
+    pub(crate) text: HtmlText,
+}
+
+impl HtmlLink<'_> {
+    const ALLOWED_SCHEMES: &'static [&'static str] = &["http", "https", "mailto"];
+
+    fn is_allowed(&self) -> bool {
+        // Browsers ignore whitespace and control characters in the scheme so `java\tscript:` is still javascript.
+        let url: String = self.url.chars().filter(|c| !c.is_whitespace() && !c.is_control()).collect();
+        match url.split_once(':') {
+            Some((scheme, _)) if !scheme.contains(['/', '?', '#']) => {
+                Self::ALLOWED_SCHEMES.iter().any(|allowed| scheme.eq_ignore_ascii_case(allowed))
+            }
+            _ => true,
+        }
+    }
+}
+
+impl fmt::Display for HtmlLink<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.is_allowed() {
+            return write!(f, "{}", self.text);
+        }
+        let url = self.url.replace('&', "&amp;").replace('"', "&quot;").replace('<', "&lt;").replace('>', "&gt;");
+        write!(f, "<a href=\"{url}\">{}</a>", self.text)
+    }
+}
+
 pub(crate) enum FontSize {
     Pixels(u16),
 }
@@ -66,24 +99,29 @@ impl FontSize {
 }
 
 pub(crate) fn color_to_html(color: &Color) -> String {
+    let (r, g, b) = color_to_rgb(color);
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+pub(crate) fn color_to_rgb(color: &Color) -> (u8, u8, u8) {
     match color {
-        Color::Black => "#000000".into(),
-        Color::DarkGrey => "#5a5a5a".into(),
-        Color::Red => "#ff0000".into(),
-        Color::DarkRed => "#8b0000".into(),
-        Color::Green => "#00ff00".into(),
-        Color::DarkGreen => "#006400".into(),
-        Color::Yellow => "#ffff00".into(),
-        Color::DarkYellow => "#8b8000".into(),
-        Color::Blue => "#0000ff".into(),
-        Color::DarkBlue => "#00008b".into(),
-        Color::Magenta => "#ff00ff".into(),
-        Color::DarkMagenta => "#8b008b".into(),
-        Color::Cyan => "#00ffff".into(),
-        Color::DarkCyan => "#008b8b".into(),
-        Color::White => "#ffffff".into(),
-        Color::Grey => "#808080".into(),
-        Color::Rgb { r, g, b } => format!("#{r:02x}{g:02x}{b:02x}"),
+        Color::Black => (0x00, 0x00, 0x00),
+        Color::DarkGrey => (0x5a, 0x5a, 0x5a),
+        Color::Red => (0xff, 0x00, 0x00),
+        Color::DarkRed => (0x8b, 0x00, 0x00),
+        Color::Green => (0x00, 0xff, 0x00),
+        Color::DarkGreen => (0x00, 0x64, 0x00),
+        Color::Yellow => (0xff, 0xff, 0x00),
+        Color::DarkYellow => (0x8b, 0x80, 0x00),
+        Color::Blue => (0x00, 0x00, 0xff),
+        Color::DarkBlue => (0x00, 0x00, 0x8b),
+        Color::Magenta => (0xff, 0x00, 0xff),
+        Color::DarkMagenta => (0x8b, 0x00, 0x8b),
+        Color::Cyan => (0x00, 0xff, 0xff),
+        Color::DarkCyan => (0x00, 0x8b, 0x8b),
+        Color::White => (0xff, 0xff, 0xff),
+        Color::Grey => (0x80, 0x80, 0x80),
+        Color::Rgb { r, g, b } => (*r, *g, *b),
     }
 }
 
@@ -121,4 +159,28 @@ mod test {
         let rendered = html_text.to_string();
         assert_eq!(rendered, "<span style=\"font-weight: bold\">hi</span>");
     }
+
+    #[test]
+    fn render_link() {
+        let text = HtmlText::new("hi", &TextStyle::default(), FontSize::Pixels(1));
+        let rendered = HtmlLink { url: "https://example.com/?a=1&b=\"2\"", text }.to_string();
+        assert_eq!(rendered, "<a href=\"https://example.com/?a=1&amp;b=&quot;2&quot;\">hi</a>");
+    }
+
+    #[rstest]
+    #[case::http("http://example.com", true)]
+    #[case::mailto("mailto:foo@example.com", true)]
+    #[case::uppercase("HTTPS://example.com", true)]
+    #[case::relative("slides/intro.html", true)]
+    #[case::relative_with_colon("slides/a:b.html", true)]
+    #[case::fragment("#intro", true)]
+    #[case::javascript("javascript:alert(1)", false)]
+    #[case::javascript_obfuscated(" java\tscript:alert(1)", false)]
+    #[case::data("data:text/html,hi", false)]
+    fn link_schemes(#[case] url: &str, #[case] is_link: bool) {
+        let text = HtmlText::new("hi", &TextStyle::default(), FontSize::Pixels(1));
+        let rendered = HtmlLink { url, text }.to_string();
+        assert_eq!(rendered.starts_with("<a href="), is_link, "{rendered}");
+        assert!(rendered.contains("hi"));
+    }
 }
@@ -0,0 +1,114 @@
+use super::{exporter::ExportError, output::LINE_HEIGHT, raster::GridRasterizer, svg::SvgSlide};
+use crate::terminal::virt::TerminalGrid;
+use std::{fs, path::Path};
+
+const SLIDE_PLACEHOLDER: &str = "{slide}";
+const CHUNK_PLACEHOLDER: &str = "{chunk}";
+
+/// The format used when exporting every slide into its own image.
+#[derive(Clone, Copy, Debug)]
+pub enum ImageExportFormat {
+    Svg,
+    Png,
+}
+
+impl ImageExportFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Svg => "svg",
+            Self::Png => "png",
+        }
+    }
+}
+
+/// Writes rendered grids into image files.
+pub(crate) enum SlideImageWriter {
+    Svg { font_families: Vec<String> },
+    Png(Box<GridRasterizer>),
+}
+
+impl SlideImageWriter {
+    /// The size in pixels of every cell in the grid.
+    pub(crate) fn cell_size(&self) -> (f64, f64) {
+        match self {
+            Self::Svg { .. } => (SvgSlide::cell_width(), LINE_HEIGHT as f64),
+            Self::Png(rasterizer) => {
+                let (width, height) = rasterizer.cell_size();
+                (width as f64, height as f64)
+            }
+        }
+    }
+
+    /// Write a grid into the given path.
+    pub(crate) fn write(&self, grid: &TerminalGrid, path: &Path) -> Result<(), ExportError> {
+        match self {
+            Self::Svg { font_families } => fs::write(path, SvgSlide::new(grid, font_families).to_string())?,
+            Self::Png(rasterizer) => rasterizer.rasterize(grid)?.save(path)?,
+        };
+        Ok(())
+    }
+}
+
+/// The pattern used to name the files generated for every slide.
+pub(crate) struct FileNamePattern {
+    pattern: String,
+    extension: &'static str,
+    slide_digits: usize,
+}
+
+impl FileNamePattern {
+    /// Construct a new pattern, making sure it produces a different name for every file.
+    pub(crate) fn new(pattern: String, format: ImageExportFormat, chunks: bool) -> Result<Self, ExportError> {
+        if !pattern.contains(SLIDE_PLACEHOLDER) {
+            return Err(ExportError::InvalidFileName(SLIDE_PLACEHOLDER));
+        }
+        if chunks && !pattern.contains(CHUNK_PLACEHOLDER) {
+            return Err(ExportError::InvalidFileName(CHUNK_PLACEHOLDER));
+        }
+        Ok(Self { pattern, extension: format.extension(), slide_digits: 1 })
+    }
+
+    /// Set the total number of slides, which is used to pad slide numbers.
+    pub(crate) fn set_total_slides(&mut self, total_slides: usize) {
+        self.slide_digits = total_slides.to_string().len();
+    }
+
+    /// Get the file name for a slide and chunk, both 1-based.
+    ///
+    /// Slide numbers are zero padded so files are sorted by slide.
+    pub(crate) fn file_name(&self, slide: usize, chunk: usize) -> String {
+        let slide = format!("{slide:0width$}", width = self.slide_digits);
+        let name = self.pattern.replace(SLIDE_PLACEHOLDER, &slide).replace(CHUNK_PLACEHOLDER, &chunk.to_string());
+        format!("{name}.{}", self.extension)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::single_digit(5, "slide-{slide}", 3, 1, "slide-3.svg")]
+    #[case::padded(12, "slide-{slide}", 3, 1, "slide-03.svg")]
+    #[case::chunks(12, "{slide}_{chunk}", 11, 2, "11_2.svg")]
+    fn file_names(
+        #[case] total_slides: usize,
+        #[case] pattern: &str,
+        #[case] slide: usize,
+        #[case] chunk: usize,
+        #[case] expected: &str,
+    ) {
+        let mut pattern = FileNamePattern::new(pattern.into(), ImageExportFormat::Svg, false).unwrap();
+        pattern.set_total_slides(total_slides);
+        assert_eq!(pattern.file_name(slide, chunk), expected);
+    }
+
+    #[rstest]
+    #[case::no_slide("foo", false)]
+    #[case::no_chunk("slide-{slide}", true)]
+    fn invalid_pattern(#[case] pattern: &str, #[case] chunks: bool) {
+        let result = FileNamePattern::new(pattern.into(), ImageExportFormat::Png, chunks);
+        assert!(result.is_err());
+    }
+}
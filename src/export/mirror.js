@@ -0,0 +1,33 @@
+document.addEventListener('DOMContentLoaded', function() {
+  const body = document.querySelector("body");
+  const slide = document.getElementById("slide");
+  let originalWidth = 0;
+  let originalHeight = 0;
+
+  function scaleToFit() {
+    if (originalWidth == 0 || originalHeight == 0) return;
+    const w = window.innerWidth;
+    const h = window.innerHeight;
+    const scale = Math.min(w / originalWidth, h / originalHeight);
+    const offsetX = (w - originalWidth * scale) / 2;
+    const offsetY = (h - originalHeight * scale) / 2;
+    body.style.transform = `translate(${offsetX}px, ${offsetY}px) scale(${scale})`;
+  }
+
+  function showFrame(frame) {
+    originalWidth = frame.width;
+    originalHeight = frame.height;
+    document.documentElement.style.backgroundColor = frame.background_color;
+    body.style.backgroundColor = frame.background_color;
+    body.style.width = `${frame.width}px`;
+    body.style.height = `${frame.height}px`;
+    slide.innerHTML = frame.html;
+    scaleToFit();
+  }
+
+  // EventSource reconnects on its own if the connection is dropped. The query string carries the token.
+  const events = new EventSource("/events" + window.location.search);
+  events.onmessage = (event) => showFrame(JSON.parse(event.data));
+
+  window.addEventListener("resize", scaleToFit);
+});
@@ -0,0 +1,340 @@
+use super::output::{FONT_SIZE, FONT_SIZE_WIDTH, HtmlSlide, LINE_HEIGHT};
+use crate::{
+    http::{Request, Response, write_event_stream_headers},
+    render::properties::WindowSize,
+    terminal::virt::TerminalGrid,
+};
+use serde::Serialize;
+use std::{
+    io::{self, BufReader, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{
+        Arc, Condvar, Mutex,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    thread,
+    time::Duration,
+};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+// How often to ping clients when nothing changes so we notice when they go away.
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+const MAX_CLIENTS: usize = 64;
+
+/// A server that mirrors the slide being presented to browsers.
+pub struct AudienceMirrorServer;
+
+impl AudienceMirrorServer {
+    /// Launch a server on the given address that requires requests to provide the given token.
+    pub fn launch(address: SocketAddr, token: String) -> io::Result<AudienceMirror> {
+        Self::launch_on(TcpListener::bind(address)?, token)
+    }
+
+    fn launch_on(listener: TcpListener, token: String) -> io::Result<AudienceMirror> {
+        let shared = Arc::new(Shared { token, ..Default::default() });
+        let server_shared = shared.clone();
+        thread::Builder::new().name("audience-mirror".into()).spawn(move || Self::run(listener, server_shared))?;
+        Ok(AudienceMirror { shared })
+    }
+
+    fn run(listener: TcpListener, shared: Arc<Shared>) {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else {
+                continue;
+            };
+            let shared = shared.clone();
+            // Event streams are long lived so each connection needs its own thread. Errors here are the client's
+            // problem so there's nothing else to do with them.
+            let _ = thread::Builder::new().name("audience-mirror-client".into()).spawn(move || {
+                let _ = Self::serve(stream, &shared);
+            });
+        }
+    }
+
+    fn serve(stream: TcpStream, shared: &Shared) -> io::Result<()> {
+        stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+        stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+        let request = match Request::read(&mut BufReader::new(&stream)) {
+            Ok(request) => request,
+            Err(e) => return Response::error(400, &e.to_string()).write(&stream),
+        };
+        if !request.has_token(&shared.token) {
+            return Response::error(401, "invalid token").write(&stream);
+        }
+        match (request.method.as_str(), request.path.as_str()) {
+            ("GET", "/") => Response::html(Self::page()).write(&stream),
+            ("GET", "/events") => Self::stream_events(stream, shared),
+            _ => Response::error(404, "not found").write(&stream),
+        }
+    }
+
+    fn stream_events(mut stream: TcpStream, shared: &Shared) -> io::Result<()> {
+        let Some(_client) = shared.add_client() else {
+            return Response::error(503, "too many clients").write(&stream);
+        };
+        write_event_stream_headers(&mut stream)?;
+        let mut last_version = 0;
+        loop {
+            let frame = shared.wait_for_frame(last_version, KEEPALIVE_INTERVAL);
+            match frame {
+                Some(frame) => {
+                    last_version = frame.version;
+                    let data = serde_json::to_string(&frame).expect("serialization failed");
+                    write!(stream, "data: {data}\n\n")?;
+                }
+                None => write!(stream, ": keepalive\n\n")?,
+            };
+            stream.flush()?;
+        }
+    }
+
+    fn page() -> String {
+        let script = include_str!("mirror.js");
+        format!(
+            r#"<html>
+<head>
+<meta charset="UTF-8">
+<meta name="viewport" content="width=device-width, initial-scale=1.0">
+<title>presenterm</title>
+<style>
+    pre {{
+        margin: 0;
+        padding: 0;
+    }}
+
+    span {{
+        display: inline-block;
+    }}
+
+    html {{
+        overflow: hidden;
+        background-color: black;
+    }}
+
+    body {{
+        margin: 0;
+        font-size: {FONT_SIZE}px;
+        line-height: {LINE_HEIGHT}px;
+        transform-origin: top left;
+    }}
+
+    .content-line {{
+        line-height: {LINE_HEIGHT}px;
+        height: {LINE_HEIGHT}px;
+        margin: 0px;
+    }}
+</style>
+</head>
+<body>
+<div id="slide"></div>
+<script>
+{script}
+</script>
+</body>
+</html>"#
+        )
+    }
+}
+
+/// A handle to publish the slide being presented to the audience mirror's clients.
+pub struct AudienceMirror {
+    shared: Arc<Shared>,
+}
+
+impl AudienceMirror {
+    /// Publish a rendered slide.
+    ///
+    /// Clients will only be notified if this is different from the last published one.
+    pub(crate) fn publish(&self, grid: TerminalGrid, dimensions: WindowSize) {
+        let Ok(slide) = HtmlSlide::new(grid) else {
+            return;
+        };
+        let frame = Frame {
+            version: 0,
+            width: (dimensions.columns as f64 * FONT_SIZE as f64 * FONT_SIZE_WIDTH).ceil() as u32,
+            height: dimensions.rows as u32 * LINE_HEIGHT as u32,
+            background_color: slide.background_color.unwrap_or_else(|| "black".into()),
+            html: slide.rows.join("\n"),
+        };
+        self.shared.publish(frame);
+    }
+
+    /// Check whether any clients are connected.
+    pub(crate) fn has_clients(&self) -> bool {
+        self.shared.clients.load(Ordering::Relaxed) > 0
+    }
+
+    /// Check whether a slide needs to be published because a client connected while there were none.
+    ///
+    /// Slides aren't published while nobody is watching so the last published one may be outdated.
+    pub(crate) fn take_refresh_request(&self) -> bool {
+        self.shared.refresh.swap(false, Ordering::Relaxed)
+    }
+}
+
+#[derive(Default)]
+struct Shared {
+    token: String,
+    frame: Mutex<Frame>,
+    changed: Condvar,
+    clients: AtomicUsize,
+    refresh: AtomicBool,
+}
+
+impl Shared {
+    fn add_client(&self) -> Option<ClientGuard<'_>> {
+        let previous = self
+            .clients
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |clients| {
+                (clients < MAX_CLIENTS).then_some(clients + 1)
+            })
+            .ok()?;
+        if previous == 0 {
+            self.refresh.store(true, Ordering::Relaxed);
+        }
+        Some(ClientGuard(self))
+    }
+
+    fn publish(&self, mut frame: Frame) {
+        let mut current = self.frame.lock().unwrap();
+        frame.version = current.version;
+        if *current == frame {
+            return;
+        }
+        frame.version += 1;
+        *current = frame;
+        self.changed.notify_all();
+    }
+
+    fn wait_for_frame(&self, last_version: u64, timeout: Duration) -> Option<Frame> {
+        let current = self.frame.lock().unwrap();
+        let (current, _) =
+            self.changed.wait_timeout_while(current, timeout, |frame| frame.version == last_version).unwrap();
+        if current.version == last_version { None } else { Some(current.clone()) }
+    }
+}
+
+// Keeps a client counted while it's connected.
+struct ClientGuard<'a>(&'a Shared);
+
+impl Drop for ClientGuard<'_> {
+    fn drop(&mut self) {
+        self.0.clients.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+struct Frame {
+    #[serde(skip)]
+    version: u64,
+    width: u32,
+    height: u32,
+    background_color: String,
+    html: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use std::io::Read;
+
+    fn make_frame(html: &str) -> Frame {
+        Frame { html: html.into(), ..Default::default() }
+    }
+
+    #[test]
+    fn publish_deduplicates() {
+        let shared = Shared::default();
+        shared.publish(make_frame("a"));
+        shared.publish(make_frame("a"));
+        assert_eq!(shared.frame.lock().unwrap().version, 1);
+
+        shared.publish(make_frame("b"));
+        assert_eq!(shared.frame.lock().unwrap().version, 2);
+    }
+
+    #[test]
+    fn wait_for_frame() {
+        let shared = Shared::default();
+        assert_eq!(shared.wait_for_frame(0, Duration::from_millis(1)), None);
+
+        shared.publish(make_frame("a"));
+        let frame = shared.wait_for_frame(0, Duration::from_millis(1)).expect("no frame");
+        assert_eq!(frame.html, "a");
+        assert_eq!(shared.wait_for_frame(frame.version, Duration::from_millis(1)), None);
+    }
+
+    #[test]
+    fn client_limit() {
+        let shared = Shared::default();
+        let clients: Vec<_> = (0..MAX_CLIENTS).map(|_| shared.add_client().expect("no client")).collect();
+        assert!(shared.add_client().is_none());
+
+        drop(clients);
+        assert_eq!(shared.clients.load(Ordering::Relaxed), 0);
+        assert!(shared.add_client().is_some());
+    }
+
+    #[test]
+    fn refresh_on_first_client() {
+        let mirror = AudienceMirror { shared: Default::default() };
+        assert!(!mirror.has_clients());
+
+        let first = mirror.shared.add_client().expect("no client");
+        assert!(mirror.has_clients());
+        assert!(mirror.take_refresh_request());
+        assert!(!mirror.take_refresh_request());
+
+        let _second = mirror.shared.add_client().expect("no client");
+        assert!(!mirror.take_refresh_request());
+        drop(first);
+        assert!(mirror.has_clients());
+    }
+
+    #[rstest]
+    #[case::no_token("GET / HTTP/1.1\r\n\r\n")]
+    #[case::bad_token("GET /events?token=nope HTTP/1.1\r\n\r\n")]
+    fn unauthorized(#[case] request: &str) {
+        let socket = TcpListener::bind("127.0.0.1:0").expect("bind failed");
+        let address = socket.local_addr().expect("no local address");
+        let _mirror = AudienceMirrorServer::launch_on(socket, "secret".into()).expect("launch failed");
+
+        let mut stream = TcpStream::connect(address).expect("connect failed");
+        write!(stream, "{request}").expect("write failed");
+        let mut response = String::new();
+        stream.read_to_string(&mut response).expect("read failed");
+        assert!(response.starts_with("HTTP/1.1 401 Unauthorized\r\n"), "{response}");
+    }
+
+    #[test]
+    fn stream() {
+        let socket = TcpListener::bind("127.0.0.1:0").expect("bind failed");
+        let address = socket.local_addr().expect("no local address");
+        let mirror = AudienceMirrorServer::launch_on(socket, "secret".into()).expect("launch failed");
+        let grid = TerminalGrid {
+            rows: vec![vec!['h'.into(), 'i'.into()]],
+            background_color: None,
+            images: Default::default(),
+            links: Default::default(),
+        };
+        mirror.publish(grid, WindowSize { rows: 1, columns: 2, height: 10, width: 10 });
+
+        let mut stream = TcpStream::connect(address).expect("connect failed");
+        write!(stream, "GET /events?token=secret HTTP/1.1\r\n\r\n").expect("write failed");
+        let mut buffer = [0; 1024];
+        let mut response = String::new();
+        while !response.ends_with("\n\n") || !response.contains("data:") {
+            let bytes_read = stream.read(&mut buffer).expect("read failed");
+            assert_ne!(bytes_read, 0, "connection closed: {response}");
+            response.push_str(&String::from_utf8_lossy(&buffer[0..bytes_read]));
+        }
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n"), "{response}");
+        let data = response.split("data: ").nth(1).expect("no data").trim();
+        let frame: serde_json::Value = serde_json::from_str(data).expect("invalid frame");
+        assert_eq!(frame["height"], 12);
+        assert!(frame["html"].as_str().unwrap().contains("hi"));
+    }
+}
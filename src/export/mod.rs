@@ -1,3 +1,10 @@
 pub mod exporter;
+pub(crate) mod fonts;
+pub(crate) mod gif;
 pub(crate) mod html;
+pub mod images;
+pub mod mirror;
 pub(crate) mod output;
+pub(crate) mod pdf;
+pub(crate) mod raster;
+pub(crate) mod svg;
@@ -1,6 +1,6 @@
 use super::{
     exporter::{ExportError, OutputDirectory},
-    html::{FontSize, color_to_html},
+    html::{FontSize, HtmlLink, color_to_html},
 };
 use crate::{
     config::ExportFontsConfig,
@@ -10,7 +10,7 @@ use crate::{
     render::{engine::RenderEngine, properties::WindowSize},
     terminal::{
         image::printer::TerminalImage,
-        virt::{TerminalGrid, VirtualTerminal},
+        virt::{StyledChar, TerminalGrid, VirtualTerminal},
     },
     tools::ThirdPartyTools,
 };
@@ -25,50 +25,43 @@ const FONT_NAME: &str = "presenterm-font";
 //
 // There's probably something somewhere that specifies what the relationship
 // really is but I found this by trial and error an I'm okay with that.
-const FONT_SIZE_WIDTH: f64 = 0.605;
+pub(crate) const FONT_SIZE_WIDTH: f64 = 0.605;
 
-const FONT_SIZE: u16 = 10;
-const LINE_HEIGHT: u16 = 12;
+pub(crate) const FONT_SIZE: u16 = 10;
+pub(crate) const LINE_HEIGHT: u16 = 12;
 
-struct HtmlSlide {
-    rows: Vec<String>,
-    background_color: Option<String>,
+pub(crate) struct HtmlSlide {
+    pub(crate) rows: Vec<String>,
+    pub(crate) background_color: Option<String>,
 }
 
 impl HtmlSlide {
-    fn new(grid: TerminalGrid) -> Result<Self, ExportError> {
+    pub(crate) fn new(grid: TerminalGrid) -> Result<Self, ExportError> {
         let mut rows = Vec::new();
         rows.push(String::from("<div class=\"container\">"));
-        for (y, row) in grid.rows.into_iter().enumerate() {
+        for y in 0..grid.rows.len() {
             let mut finalized_row = "<div class=\"content-line\"><pre>".to_string();
-            let mut current_style = row.first().map(|c| c.style).unwrap_or_default();
-            let mut current_string = String::new();
-            let mut x = 0;
-            while x < row.len() {
-                let c = row[x];
-                if c.style != current_style {
-                    finalized_row.push_str(&Self::finalize_string(&current_string, &current_style));
-                    current_string = String::new();
-                    current_style = c.style;
-                }
-                match c.character {
-                    '<' => current_string.push_str("&lt;"),
-                    '>' => current_string.push_str("&gt;"),
-                    other => current_string.push(other),
-                }
-                if let Some(image) = grid.images.get(&(y as u16, x as u16)) {
-                    let TerminalImage::Raw(raw_image) = image.image.image() else { panic!("not in raw image mode") };
-                    let image_contents = raw_image.to_inline_html();
-                    let width_pixels = (image.width_columns as f64 * FONT_SIZE as f64 * FONT_SIZE_WIDTH).ceil();
-                    let image_tag = format!(
-                        "<img width=\"{width_pixels}\" src=\"{image_contents}\" style=\"position: absolute\" />"
-                    );
-                    current_string.push_str(&image_tag);
+            for span in CellSpan::split(&grid, y) {
+                let mut current_string = String::new();
+                for (x, character) in span.iter_columns() {
+                    match character {
+                        '<' => current_string.push_str("&lt;"),
+                        '>' => current_string.push_str("&gt;"),
+                        other => current_string.push(other),
+                    }
+                    if let Some(image) = grid.images.get(&(y as u16, x as u16)) {
+                        let TerminalImage::Raw(raw_image) = image.image.image() else {
+                            panic!("not in raw image mode")
+                        };
+                        let image_contents = raw_image.to_inline_html();
+                        let width_pixels = (image.width_columns as f64 * FONT_SIZE as f64 * FONT_SIZE_WIDTH).ceil();
+                        let image_tag = format!(
+                            "<img width=\"{width_pixels}\" src=\"{image_contents}\" style=\"position: absolute\" />"
+                        );
+                        current_string.push_str(&image_tag);
+                    }
                 }
-                x += c.style.size as usize;
-            }
-            if !current_string.is_empty() {
-                finalized_row.push_str(&Self::finalize_string(&current_string, &current_style));
+                finalized_row.push_str(&Self::finalize_string(&current_string, &span.style, span.link));
             }
             finalized_row.push_str("</pre></div>");
             rows.push(finalized_row);
@@ -78,8 +71,53 @@ impl HtmlSlide {
         Ok(HtmlSlide { rows, background_color: grid.background_color.as_ref().map(color_to_html) })
     }
 
-    fn finalize_string(s: &str, style: &TextStyle) -> String {
-        HtmlText::new(s, style, FontSize::Pixels(FONT_SIZE)).to_string()
+    fn finalize_string(s: &str, style: &TextStyle, link: Option<&String>) -> String {
+        let text = HtmlText::new(s, style, FontSize::Pixels(FONT_SIZE));
+        match link {
+            Some(url) => HtmlLink { url, text }.to_string(),
+            None => text.to_string(),
+        }
+    }
+}
+
+/// A sequence of characters in a row of a grid that share the same style and link.
+pub(crate) struct CellSpan<'a> {
+    /// The column this span starts at.
+    pub(crate) column: usize,
+    pub(crate) text: String,
+    pub(crate) style: TextStyle,
+    pub(crate) link: Option<&'a String>,
+    /// The number of columns this span takes up.
+    pub(crate) width: usize,
+}
+
+impl<'a> CellSpan<'a> {
+    /// Split a row in a grid into spans.
+    ///
+    /// Characters with a size > 1 take up several cells but only the first one is used.
+    pub(crate) fn split(grid: &'a TerminalGrid, row: usize) -> Vec<Self> {
+        let cells: &[StyledChar] = grid.rows.get(row).map(Vec::as_slice).unwrap_or_default();
+        let mut spans: Vec<Self> = Vec::new();
+        let mut column = 0;
+        while let Some(cell) = cells.get(column) {
+            let size = cell.style.size.max(1) as usize;
+            let link = grid.links.get(&(row as u16, column as u16));
+            match spans.last_mut() {
+                Some(span) if span.style == cell.style && span.link == link => {
+                    span.text.push(cell.character);
+                    span.width += size;
+                }
+                _ => spans.push(Self { column, text: cell.character.into(), style: cell.style, link, width: size }),
+            };
+            column += size;
+        }
+        spans
+    }
+
+    /// Iterate the characters in this span along with the column each of them is at.
+    pub(crate) fn iter_columns(&self) -> impl Iterator<Item = (usize, char)> + '_ {
+        let size = self.style.size.max(1) as usize;
+        self.text.chars().enumerate().map(move |(index, character)| (self.column + index * size, character))
     }
 }
 
@@ -172,6 +210,11 @@ impl ExportRenderer {
             display: inline-block;
         }}
 
+        a {{
+            color: inherit;
+            text-decoration: none;
+        }}
+
         {font_face} 
 
         html {{
@@ -306,3 +349,52 @@ struct FontConfig {
     font_face: String,
     font_family: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_row(text: &str, style: TextStyle) -> Vec<StyledChar> {
+        text.chars().map(|character| StyledChar { character, style }).collect()
+    }
+
+    fn make_grid(row: Vec<StyledChar>) -> TerminalGrid {
+        TerminalGrid { rows: vec![row], background_color: None, images: Default::default(), links: Default::default() }
+    }
+
+    fn split(grid: &TerminalGrid) -> Vec<(usize, String, usize)> {
+        CellSpan::split(grid, 0).into_iter().map(|span| (span.column, span.text, span.width)).collect()
+    }
+
+    #[test]
+    fn split_spans() {
+        let mut row = make_row("ab", TextStyle::default().bold());
+        row.extend(make_row("cd", TextStyle::default()));
+        let grid = make_grid(row);
+        assert_eq!(split(&grid), &[(0, "ab".into(), 2), (2, "cd".into(), 2)]);
+    }
+
+    #[test]
+    fn split_sized_spans() {
+        let grid = make_grid(make_row("a b ", TextStyle::default().size(2)));
+        assert_eq!(split(&grid), &[(0, "ab".into(), 4)]);
+
+        let spans = CellSpan::split(&grid, 0);
+        let columns: Vec<_> = spans[0].iter_columns().collect();
+        assert_eq!(columns, &[(0, 'a'), (2, 'b')]);
+    }
+
+    #[test]
+    fn split_links() {
+        let mut grid = make_grid(make_row("abcd", TextStyle::default()));
+        grid.links.insert((0, 1), "https://example.com".into());
+        grid.links.insert((0, 2), "https://example.com".into());
+        assert_eq!(split(&grid), &[(0, "a".into(), 1), (1, "bc".into(), 2), (3, "d".into(), 1)]);
+    }
+
+    #[test]
+    fn split_missing_row() {
+        let grid = make_grid(vec![]);
+        assert!(CellSpan::split(&grid, 5).is_empty());
+    }
+}
@@ -0,0 +1,505 @@
+use super::{
+    fonts::{ExportFonts, FontVariant, has_cff_outlines},
+    html::color_to_rgb,
+    output::{FONT_SIZE, LINE_HEIGHT},
+};
+use crate::{
+    markdown::text_style::{Color, TextAttribute, TextStyle},
+    terminal::{
+        image::{Image, printer::TerminalImage},
+        virt::{StyledChar, TerminalGrid},
+    },
+};
+use ab_glyph::{Font, GlyphId};
+use flate2::{Compression, write::ZlibEncoder};
+use image::{GenericImageView, ImageError};
+use pdf_writer::{
+    Content, Filter, Finish, Name, Pdf, Rect, Ref, Str,
+    types::{CidFontType, FontFlags, SystemInfo, TextRenderingMode},
+    writers::Resources,
+};
+use std::{collections::BTreeMap, io::Write};
+
+const DEFAULT_FOREGROUND: Color = Color::White;
+const DEFAULT_BACKGROUND: Color = Color::Black;
+
+// The horizontal skew used to emulate italics when there's no italic font.
+const SYNTHETIC_ITALICS_SKEW: f32 = 0.2;
+
+const SYSTEM_INFO: SystemInfo = SystemInfo { registry: Str(b"Adobe"), ordering: Str(b"Identity"), supplement: 0 };
+
+/// A PDF document where every page is a rendered grid.
+///
+/// This writes text as text using the export fonts so it can be selected and searched in the output document.
+pub(crate) struct PdfDocument {
+    pdf: Pdf,
+    fonts: ExportFonts,
+    next_id: i32,
+    catalog_id: Ref,
+    page_tree_id: Ref,
+    page_ids: Vec<Ref>,
+    used_fonts: Vec<UsedFont>,
+    images: Vec<EmbeddedImage>,
+    cell_width: f32,
+    baseline: f32,
+}
+
+impl PdfDocument {
+    /// Construct a new document that uses the given fonts.
+    pub(crate) fn new(fonts: ExportFonts) -> Self {
+        let normal = fonts.normal();
+        let units_per_em = normal.units_per_em().unwrap_or(1000.0);
+        let scale = FONT_SIZE as f32 / units_per_em;
+        let cell_width = normal.h_advance_unscaled(normal.glyph_id('M')) * scale;
+        let (ascent, descent) = (normal.ascent_unscaled() * scale, normal.descent_unscaled() * scale);
+        // Center glyphs vertically within each line.
+        let baseline = (LINE_HEIGHT as f32 - (ascent - descent)) / 2.0 + ascent;
+        let mut document = Self {
+            pdf: Pdf::new(),
+            fonts,
+            next_id: 1,
+            catalog_id: Ref::new(1),
+            page_tree_id: Ref::new(1),
+            page_ids: Vec::new(),
+            used_fonts: Vec::new(),
+            images: Vec::new(),
+            cell_width,
+            baseline,
+        };
+        document.catalog_id = document.allocate_id();
+        document.page_tree_id = document.allocate_id();
+        document
+    }
+
+    /// Add a page that contains the given grid.
+    pub(crate) fn add_page(&mut self, grid: &TerminalGrid) -> Result<(), ImageError> {
+        let rows = grid.rows.len();
+        let columns = grid.rows.first().map(Vec::len).unwrap_or_default();
+        let page_width = columns as f32 * self.cell_width;
+        let page_height = rows as f32 * LINE_HEIGHT as f32;
+
+        let mut content = Content::new();
+        let background = grid.background_color.as_ref().unwrap_or(&DEFAULT_BACKGROUND);
+        Self::set_fill_color(&mut content, background);
+        content.rect(0.0, 0.0, page_width, page_height).fill_nonzero();
+
+        let mut used_fonts = Vec::new();
+        for (row, cells) in grid.rows.iter().enumerate() {
+            // PDF coordinates start at the bottom left of the page.
+            let top = page_height - row as f32 * LINE_HEIGHT as f32;
+            self.draw_backgrounds(&mut content, cells, top);
+            self.draw_text(&mut content, cells, top, &mut used_fonts);
+        }
+
+        let mut used_images = Vec::new();
+        for ((row, column), printed) in &grid.images {
+            let TerminalImage::Raw(raw_image) = printed.image.image() else {
+                continue;
+            };
+            // Images are usually repeated across slides so only embed them once.
+            let (image_id, (width, height)) = match self.images.iter().find(|image| image.image == printed.image) {
+                Some(image) => (image.id, image.dimensions),
+                None => {
+                    let decoded = raw_image.to_image()?;
+                    let id = self.write_image(&decoded);
+                    let dimensions = decoded.dimensions();
+                    self.images.push(EmbeddedImage { image: printed.image.clone(), id, dimensions });
+                    (id, dimensions)
+                }
+            };
+            if width == 0 {
+                continue;
+            }
+            let name = format!("Im{}", used_images.len());
+            let draw_width = printed.width_columns as f32 * self.cell_width;
+            let draw_height = draw_width * height as f32 / width as f32;
+            let x = *column as f32 * self.cell_width;
+            let y = page_height - *row as f32 * LINE_HEIGHT as f32 - draw_height;
+            content.save_state();
+            content.transform([draw_width, 0.0, 0.0, draw_height, x, y]);
+            content.x_object(Name(name.as_bytes()));
+            content.restore_state();
+            used_images.push((name, image_id));
+        }
+
+        let content_id = self.allocate_id();
+        let content = compress(&content.finish());
+        self.pdf.stream(content_id, &content).filter(Filter::FlateDecode);
+
+        let page_id = self.allocate_id();
+        let mut page = self.pdf.page(page_id);
+        page.parent(self.page_tree_id).media_box(Rect::new(0.0, 0.0, page_width, page_height)).contents(content_id);
+        let mut resources = page.resources();
+        Self::write_resources(&mut resources, &self.used_fonts, &used_fonts, &used_images);
+        resources.finish();
+        page.finish();
+        self.page_ids.push(page_id);
+        Ok(())
+    }
+
+    /// Finish this document and get its contents.
+    pub(crate) fn finish(mut self) -> Vec<u8> {
+        let used_fonts = std::mem::take(&mut self.used_fonts);
+        for used_font in &used_fonts {
+            self.write_font(used_font);
+        }
+        self.pdf.catalog(self.catalog_id).pages(self.page_tree_id);
+        self.pdf.pages(self.page_tree_id).kids(self.page_ids.iter().copied()).count(self.page_ids.len() as i32);
+        self.pdf.finish()
+    }
+
+    fn draw_backgrounds(&self, content: &mut Content, cells: &[StyledChar], top: f32) {
+        let mut column = 0;
+        while let Some(cell) = cells.get(column) {
+            let size = cell.style.size.max(1) as usize;
+            if let Some(color) = &cell.style.colors.background {
+                let height = LINE_HEIGHT as f32 * size as f32;
+                Self::set_fill_color(content, color);
+                content.rect(column as f32 * self.cell_width, top - height, self.cell_width * size as f32, height);
+                content.fill_nonzero();
+            }
+            column += size;
+        }
+    }
+
+    fn draw_text(&mut self, content: &mut Content, cells: &[StyledChar], top: f32, used_fonts: &mut Vec<usize>) {
+        // Shapes can't be drawn inside text objects so draw them first and then all of the line's glyphs at once.
+        let mut column = 0;
+        while let Some(cell) = cells.get(column) {
+            let StyledChar { character, style } = cell;
+            let size = style.size.max(1) as usize;
+            let x = column as f32 * self.cell_width;
+            let width = self.cell_width * size as f32;
+            let height = LINE_HEIGHT as f32 * size as f32;
+            let has_lines = style
+                .iter_attributes()
+                .any(|attribute| matches!(attribute, TextAttribute::Underlined | TextAttribute::Strikethrough));
+            if is_block(*character) || has_lines {
+                let foreground = style.colors.foreground.as_ref().unwrap_or(&DEFAULT_FOREGROUND);
+                Self::set_fill_color(content, foreground);
+            }
+            // Block characters are used to draw images as ascii so make sure they fill the cell exactly.
+            match character {
+                '█' => {
+                    content.rect(x, top - height, width, height).fill_nonzero();
+                }
+                '▀' => {
+                    content.rect(x, top - height / 2.0, width, height / 2.0).fill_nonzero();
+                }
+                '▄' => {
+                    content.rect(x, top - height, width, height / 2.0).fill_nonzero();
+                }
+                _ => (),
+            };
+            let line_width = (size as f32 * 0.5).max(0.5);
+            for attribute in style.iter_attributes() {
+                let y = match attribute {
+                    TextAttribute::Underlined => top - self.baseline * size as f32 - line_width * 2.0,
+                    TextAttribute::Strikethrough => top - height / 2.0,
+                    _ => continue,
+                };
+                content.rect(x, y, width, line_width).fill_nonzero();
+            }
+            column += size;
+        }
+
+        let mut state = None;
+        let mut column = 0;
+        while let Some(cell) = cells.get(column) {
+            let StyledChar { character, style } = cell;
+            let size = style.size.max(1) as usize;
+            if *character != ' ' && !is_block(*character) {
+                let state = state.get_or_insert_with(|| {
+                    content.begin_text();
+                    TextState::default()
+                });
+                let x = column as f32 * self.cell_width;
+                let y = top - self.baseline * size as f32;
+                let font_index = self.draw_glyph(content, state, *character, style, x, y);
+                if !used_fonts.contains(&font_index) {
+                    used_fonts.push(font_index);
+                }
+            }
+            column += size;
+        }
+        if state.is_some() {
+            content.end_text();
+        }
+    }
+
+    fn draw_glyph(
+        &mut self,
+        content: &mut Content,
+        state: &mut TextState,
+        character: char,
+        style: &TextStyle,
+        x: f32,
+        y: f32,
+    ) -> usize {
+        let selected = self.fonts.select(style, character);
+        let glyph_id = selected.font.glyph_id(character).0;
+        let units_per_em = selected.font.units_per_em().unwrap_or(1000.0);
+        let width = selected.font.h_advance_unscaled(GlyphId(glyph_id)) / units_per_em * 1000.0;
+        let (variant, synthetic_bold, synthetic_italics) =
+            (selected.variant, selected.synthetic_bold, selected.synthetic_italics);
+        let font_index = self.font_index(variant);
+        self.used_fonts[font_index].glyphs.insert(glyph_id, (character, width));
+
+        let foreground = style.colors.foreground.unwrap_or(DEFAULT_FOREGROUND);
+        if state.foreground != Some(foreground) {
+            // The stroke is only visible when emulating bold text.
+            let (r, g, b) = color_to_rgb(&foreground);
+            content.set_fill_rgb(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+            content.set_stroke_rgb(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+            state.foreground = Some(foreground);
+        }
+        let font = (font_index, style.size.max(1));
+        if state.font != Some(font) {
+            let font_name = UsedFont::name(font_index);
+            content.set_font(Name(font_name.as_bytes()), FONT_SIZE as f32 * font.1 as f32);
+            state.font = Some(font);
+        }
+        if state.synthetic_bold != synthetic_bold {
+            if synthetic_bold {
+                content.set_text_rendering_mode(TextRenderingMode::FillStroke);
+                content.set_line_width(FONT_SIZE as f32 * 0.04);
+            } else {
+                content.set_text_rendering_mode(TextRenderingMode::Fill);
+            }
+            state.synthetic_bold = synthetic_bold;
+        }
+        let skew = if synthetic_italics { SYNTHETIC_ITALICS_SKEW } else { 0.0 };
+        content.set_text_matrix([1.0, 0.0, skew, 1.0, x, y]);
+        content.show(Str(&glyph_id.to_be_bytes()));
+        font_index
+    }
+
+    fn font_index(&mut self, variant: FontVariant) -> usize {
+        if let Some(index) = self.used_fonts.iter().position(|font| font.variant == variant) {
+            return index;
+        }
+        let id = self.allocate_id();
+        self.used_fonts.push(UsedFont { variant, id, glyphs: Default::default() });
+        self.used_fonts.len() - 1
+    }
+
+    fn write_resources(
+        resources: &mut Resources,
+        fonts: &[UsedFont],
+        used_fonts: &[usize],
+        used_images: &[(String, Ref)],
+    ) {
+        let mut font_resources = resources.fonts();
+        for index in used_fonts {
+            font_resources.pair(Name(UsedFont::name(*index).as_bytes()), fonts[*index].id);
+        }
+        font_resources.finish();
+        let mut image_resources = resources.x_objects();
+        for (name, id) in used_images {
+            image_resources.pair(Name(name.as_bytes()), *id);
+        }
+        image_resources.finish();
+    }
+
+    fn write_image(&mut self, image: &image::DynamicImage) -> Ref {
+        let id = self.allocate_id();
+        let (width, height) = (image.width() as i32, image.height() as i32);
+        let mask_id = image.color().has_alpha().then(|| self.allocate_id());
+        let data = compress(image.to_rgb8().as_raw());
+        let mut xobject = self.pdf.image_xobject(id, &data);
+        xobject.width(width).height(height).bits_per_component(8).filter(Filter::FlateDecode);
+        xobject.color_space().device_rgb();
+        if let Some(mask_id) = mask_id {
+            xobject.s_mask(mask_id);
+        }
+        xobject.finish();
+
+        if let Some(mask_id) = mask_id {
+            let alpha: Vec<_> = image.to_rgba8().pixels().map(|pixel| pixel.0[3]).collect();
+            let data = compress(&alpha);
+            let mut mask = self.pdf.image_xobject(mask_id, &data);
+            mask.width(width).height(height).bits_per_component(8).filter(Filter::FlateDecode);
+            mask.color_space().device_gray();
+        }
+        id
+    }
+
+    fn write_font(&mut self, used_font: &UsedFont) {
+        let (cid_font_id, descriptor_id, cmap_id, file_id) =
+            (self.allocate_id(), self.allocate_id(), self.allocate_id(), self.allocate_id());
+        let font = self.fonts.variant(used_font.variant).expect("font for used variant not loaded");
+        let base_font = format!("presenterm-{:?}", used_font.variant);
+        let base_font = Name(base_font.as_bytes());
+
+        self.pdf
+            .type0_font(used_font.id)
+            .base_font(base_font)
+            .encoding_predefined(Name(b"Identity-H"))
+            .descendant_font(cid_font_id)
+            .to_unicode(cmap_id);
+
+        // Fonts with CFF outlines are embedded as OpenType, where CIDs are used as glyph ids as they are.
+        let cff = has_cff_outlines(font);
+        let mut cid_font = self.pdf.cid_font(cid_font_id);
+        cid_font.base_font(base_font).system_info(SYSTEM_INFO).font_descriptor(descriptor_id);
+        if cff {
+            cid_font.subtype(CidFontType::Type0);
+        } else {
+            cid_font.subtype(CidFontType::Type2).cid_to_gid_map_predefined(Name(b"Identity"));
+        }
+        let mut widths = cid_font.widths();
+        for (glyph_id, (_, width)) in &used_font.glyphs {
+            widths.consecutive(*glyph_id, [*width]);
+        }
+        widths.finish();
+        cid_font.finish();
+
+        let units_per_em = font.units_per_em().unwrap_or(1000.0);
+        let to_units = |value: f32| value / units_per_em * 1000.0;
+        let (ascent, descent) = (to_units(font.ascent_unscaled()), to_units(font.descent_unscaled()));
+        let advance = to_units(font.h_advance_unscaled(font.glyph_id('M')));
+        let mut flags = FontFlags::FIXED_PITCH | FontFlags::NON_SYMBOLIC;
+        if matches!(used_font.variant, FontVariant::Italic | FontVariant::BoldItalic) {
+            flags |= FontFlags::ITALIC;
+        }
+        let mut descriptor = self.pdf.font_descriptor(descriptor_id);
+        descriptor
+            .name(base_font)
+            .flags(flags)
+            .bbox(Rect::new(0.0, descent, advance, ascent))
+            .italic_angle(0.0)
+            .ascent(ascent)
+            .descent(descent)
+            .cap_height(ascent)
+            .stem_v(80.0);
+        if cff {
+            descriptor.font_file3(file_id);
+        } else {
+            descriptor.font_file2(file_id);
+        }
+        descriptor.finish();
+
+        let mut cmap = pdf_writer::types::UnicodeCmap::new(Name(b"Custom"), SYSTEM_INFO);
+        for (glyph_id, (character, _)) in &used_font.glyphs {
+            cmap.pair(*glyph_id, *character);
+        }
+        let cmap = compress(&cmap.finish());
+        self.pdf.cmap(cmap_id, &cmap).filter(Filter::FlateDecode);
+
+        let data = font.as_slice();
+        let compressed = compress(data);
+        let mut stream = self.pdf.stream(file_id, &compressed);
+        stream.filter(Filter::FlateDecode);
+        if cff {
+            stream.pair(Name(b"Subtype"), Name(b"OpenType"));
+        } else {
+            stream.pair(Name(b"Length1"), data.len() as i32);
+        }
+    }
+
+    fn set_fill_color(content: &mut Content, color: &Color) {
+        let (r, g, b) = color_to_rgb(color);
+        content.set_fill_rgb(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+    }
+
+    fn allocate_id(&mut self) -> Ref {
+        let id = Ref::new(self.next_id);
+        self.next_id += 1;
+        id
+    }
+}
+
+// The text state set while drawing a line's glyphs, to avoid repeating it for every glyph.
+#[derive(Default)]
+struct TextState {
+    font: Option<(usize, u8)>,
+    foreground: Option<Color>,
+    synthetic_bold: bool,
+}
+
+struct UsedFont {
+    variant: FontVariant,
+    id: Ref,
+    glyphs: BTreeMap<u16, (char, f32)>,
+}
+
+impl UsedFont {
+    fn name(index: usize) -> String {
+        format!("F{index}")
+    }
+}
+
+struct EmbeddedImage {
+    image: Image,
+    id: Ref,
+    dimensions: (u32, u32),
+}
+
+fn is_block(character: char) -> bool {
+    matches!(character, '█' | '▀' | '▄')
+}
+
+fn compress(data: &[u8]) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data).expect("writing to vec failed");
+    encoder.finish().expect("writing to vec failed")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn compress_roundtrip() {
+        let data = b"BT /F0 10 Tf ET".repeat(10);
+        let compressed = compress(&data);
+        let mut decoder = flate2::read::ZlibDecoder::new(compressed.as_slice());
+        let mut output = Vec::new();
+        std::io::Read::read_to_end(&mut decoder, &mut output).expect("decompress failed");
+        assert_eq!(output, data);
+    }
+
+    fn grid(text: &str, style: TextStyle) -> TerminalGrid {
+        TerminalGrid {
+            rows: vec![text.chars().map(|c| StyledChar { character: c, style }).collect()],
+            background_color: Some(Color::Black),
+            images: Default::default(),
+            links: Default::default(),
+        }
+    }
+
+    #[rstest]
+    #[case::truetype(ExportFonts::fixture(), &["/FontFile2", "/CIDFontType2"])]
+    #[case::cff(ExportFonts::cff_fixture(), &["/FontFile3", "/Subtype /OpenType", "/CIDFontType0"])]
+    fn single_page(#[case] fonts: ExportFonts, #[case] expected: &[&str]) {
+        let mut document = PdfDocument::new(fonts);
+        let style = TextStyle::default().fg_color(Color::Red).bold();
+        document.add_page(&grid("a", style)).expect("adding page failed");
+        let output = document.finish();
+        assert!(output.starts_with(b"%PDF"));
+
+        let contains = |needle: &[u8]| output.windows(needle.len()).any(|window| window == needle);
+        assert!(contains(b"/Type /Page\n"));
+        for needle in expected {
+            assert!(contains(needle.as_bytes()), "{needle} not found");
+        }
+    }
+
+    #[test]
+    fn line_in_single_text_object() {
+        let mut document = PdfDocument::new(ExportFonts::fixture());
+        let mut content = Content::new();
+        let grid = grid("hi █ there", TextStyle::default().underlined());
+        document.draw_text(&mut content, &grid.rows[0], 100.0, &mut Vec::new());
+        let content = String::from_utf8(content.finish()).expect("invalid content");
+        assert_eq!(content.matches("BT").count(), 1);
+        assert_eq!(content.matches("ET").count(), 1);
+        assert_eq!(content.matches("Tf").count(), 1);
+        assert_eq!(content.matches("Tj").count(), 7);
+
+        // Shapes are drawn before the text object starts.
+        let text_start = content.find("BT").unwrap();
+        assert!(!content[text_start..].contains(" re"));
+    }
+}
@@ -0,0 +1,204 @@
+use super::{fonts::ExportFonts, html::color_to_rgb};
+use crate::{
+    markdown::text_style::{Color, TextAttribute},
+    terminal::{
+        image::printer::TerminalImage,
+        virt::{PrintedImage, StyledChar, TerminalGrid},
+    },
+};
+use ab_glyph::{Font, FontVec, PxScale, ScaleFont, point};
+use image::{
+    ImageError, Rgba, RgbaImage,
+    imageops::{self, FilterType},
+};
+
+const DEFAULT_FOREGROUND: Color = Color::White;
+const DEFAULT_BACKGROUND: Color = Color::Black;
+
+/// Draws terminal grids into images.
+pub(crate) struct GridRasterizer {
+    fonts: ExportFonts,
+    scale: PxScale,
+    ascent: f32,
+    cell_width: u32,
+    cell_height: u32,
+}
+
+impl GridRasterizer {
+    /// Construct a new rasterizer that uses the given fonts.
+    pub(crate) fn new(fonts: ExportFonts, font_size: u16) -> Self {
+        let scale = PxScale::from(font_size.max(1) as f32);
+        let scaled = fonts.normal().as_scaled(scale);
+        let cell_width = scaled.h_advance(scaled.glyph_id('M')).ceil().max(1.0) as u32;
+        let cell_height = scaled.height().ceil().max(1.0) as u32;
+        let ascent = scaled.ascent();
+        Self { fonts, scale, ascent, cell_width, cell_height }
+    }
+
+    /// The size in pixels of every cell in the grid.
+    pub(crate) fn cell_size(&self) -> (u32, u32) {
+        (self.cell_width, self.cell_height)
+    }
+
+    /// Draw a grid into an image.
+    pub(crate) fn rasterize(&self, grid: &TerminalGrid) -> Result<RgbaImage, ImageError> {
+        let rows = grid.rows.len() as u32;
+        let columns = grid.rows.first().map(Vec::len).unwrap_or_default() as u32;
+        let background = to_rgba(grid.background_color.as_ref().unwrap_or(&DEFAULT_BACKGROUND));
+        let mut image = RgbaImage::from_pixel(columns * self.cell_width, rows * self.cell_height, background);
+        for (row, cells) in grid.rows.iter().enumerate() {
+            let mut column = 0;
+            while let Some(cell) = cells.get(column) {
+                self.draw_cell(&mut image, cell, column as u32 * self.cell_width, row as u32 * self.cell_height);
+                column += cell.style.size.max(1) as usize;
+            }
+        }
+        for ((row, column), printed) in &grid.images {
+            let x = *column as u32 * self.cell_width;
+            let y = *row as u32 * self.cell_height;
+            self.draw_image(&mut image, printed, x, y)?;
+        }
+        Ok(image)
+    }
+
+    fn draw_cell(&self, image: &mut RgbaImage, cell: &StyledChar, x: u32, y: u32) {
+        let StyledChar { character, style } = cell;
+        let size = style.size.max(1) as u32;
+        let (width, height) = (self.cell_width * size, self.cell_height * size);
+        if let Some(color) = &style.colors.background {
+            fill(image, x, y, width, height, to_rgba(color));
+        }
+        let foreground = to_rgba(style.colors.foreground.as_ref().unwrap_or(&DEFAULT_FOREGROUND));
+        // Block characters are used to draw images as ascii so make sure they fill the cell exactly.
+        match character {
+            ' ' => (),
+            '█' => fill(image, x, y, width, height, foreground),
+            '▀' => fill(image, x, y, width, height / 2, foreground),
+            '▄' => fill(image, x, y + height / 2, width, height - height / 2, foreground),
+            _ => {
+                let selected = self.fonts.select(style, *character);
+                self.draw_glyph(image, selected.font, *character, x, y, size, foreground);
+                if selected.synthetic_bold {
+                    self.draw_glyph(image, selected.font, *character, x + 1, y, size, foreground);
+                }
+            }
+        };
+        let line_height = size.max(self.cell_height / 16);
+        for attribute in style.iter_attributes() {
+            match attribute {
+                TextAttribute::Underlined => {
+                    let line_y = y + (self.ascent * size as f32) as u32 + line_height;
+                    fill(image, x, line_y, width, line_height, foreground);
+                }
+                TextAttribute::Strikethrough => fill(image, x, y + height / 2, width, line_height, foreground),
+                _ => (),
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn draw_glyph(
+        &self,
+        image: &mut RgbaImage,
+        font: &FontVec,
+        character: char,
+        x: u32,
+        y: u32,
+        size: u32,
+        color: Rgba<u8>,
+    ) {
+        let scale = PxScale { x: self.scale.x * size as f32, y: self.scale.y * size as f32 };
+        let position = point(x as f32, y as f32 + self.ascent * size as f32);
+        let glyph = font.glyph_id(character).with_scale_and_position(scale, position);
+        let Some(outline) = font.outline_glyph(glyph) else {
+            return;
+        };
+        let bounds = outline.px_bounds();
+        outline.draw(|glyph_x, glyph_y, coverage| {
+            let pixel_x = bounds.min.x as i64 + glyph_x as i64;
+            let pixel_y = bounds.min.y as i64 + glyph_y as i64;
+            if pixel_x < 0 || pixel_y < 0 || pixel_x >= image.width() as i64 || pixel_y >= image.height() as i64 {
+                return;
+            }
+            let pixel = image.get_pixel_mut(pixel_x as u32, pixel_y as u32);
+            blend(pixel, color, coverage);
+        });
+    }
+
+    fn draw_image(&self, image: &mut RgbaImage, printed: &PrintedImage, x: u32, y: u32) -> Result<(), ImageError> {
+        let TerminalImage::Raw(raw_image) = printed.image.image() else {
+            return Ok(());
+        };
+        let source = raw_image.to_image()?;
+        if source.width() == 0 {
+            return Ok(());
+        }
+        let width = printed.width_columns as u32 * self.cell_width;
+        let height = (width as f64 * source.height() as f64 / source.width() as f64).round() as u32;
+        let resized = imageops::resize(&source.to_rgba8(), width, height, FilterType::Triangle);
+        imageops::overlay(image, &resized, x as i64, y as i64);
+        Ok(())
+    }
+}
+
+fn to_rgba(color: &Color) -> Rgba<u8> {
+    let (r, g, b) = color_to_rgb(color);
+    Rgba([r, g, b, 255])
+}
+
+fn fill(image: &mut RgbaImage, x: u32, y: u32, width: u32, height: u32, color: Rgba<u8>) {
+    let max_x = (x + width).min(image.width());
+    let max_y = (y + height).min(image.height());
+    for pixel_y in y..max_y {
+        for pixel_x in x..max_x {
+            image.put_pixel(pixel_x, pixel_y, color);
+        }
+    }
+}
+
+fn blend(pixel: &mut Rgba<u8>, color: Rgba<u8>, coverage: f32) {
+    let coverage = coverage.clamp(0.0, 1.0);
+    for (current, new) in pixel.0.iter_mut().zip(color.0).take(3) {
+        *current = (*current as f32 * (1.0 - coverage) + new as f32 * coverage).round() as u8;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{markdown::text_style::TextStyle, terminal::virt::StyledChar};
+
+    #[test]
+    fn fill_clipped() {
+        let mut image = RgbaImage::new(4, 4);
+        let red = Rgba([255, 0, 0, 255]);
+        fill(&mut image, 2, 3, 10, 10, red);
+        let filled: Vec<_> = image.enumerate_pixels().filter(|(_, _, p)| **p == red).map(|(x, y, _)| (x, y)).collect();
+        assert_eq!(filled, &[(2, 3), (3, 3)]);
+    }
+
+    #[test]
+    fn blend_coverage() {
+        let mut pixel = Rgba([0, 0, 0, 255]);
+        blend(&mut pixel, Rgba([200, 100, 50, 255]), 0.5);
+        assert_eq!(pixel, Rgba([100, 50, 25, 255]));
+    }
+
+    #[test]
+    fn rasterize_blocks() {
+        let rasterizer = GridRasterizer::new(ExportFonts::fixture(), 10);
+        let (width, height) = rasterizer.cell_size();
+        let style = TextStyle::default().fg_color(Color::Red).bg_color(Color::Blue);
+        let grid = TerminalGrid {
+            rows: vec![vec![StyledChar { character: '▀', style }, StyledChar::from(' ')]],
+            background_color: Some(Color::Green),
+            images: Default::default(),
+            links: Default::default(),
+        };
+        let image = rasterizer.rasterize(&grid).expect("rasterize failed");
+        assert_eq!(image.dimensions(), (width * 2, height));
+        assert_eq!(image.get_pixel(0, 0), &Rgba([255, 0, 0, 255]));
+        assert_eq!(image.get_pixel(0, height - 1), &Rgba([0, 0, 255, 255]));
+        assert_eq!(image.get_pixel(width, 0), &Rgba([0, 255, 0, 255]));
+    }
+}
@@ -0,0 +1,240 @@
+use super::{
+    html::color_to_html,
+    output::{CellSpan, FONT_SIZE, FONT_SIZE_WIDTH, LINE_HEIGHT},
+};
+use crate::{
+    markdown::text_style::{Color, TextAttribute},
+    terminal::{
+        image::printer::{ImageProperties, TerminalImage},
+        virt::TerminalGrid,
+    },
+};
+use std::fmt::Write;
+
+const DEFAULT_FOREGROUND: Color = Color::White;
+const DEFAULT_BACKGROUND: Color = Color::Black;
+
+// How far down a line, relative to its height, the text baseline sits.
+const BASELINE_RATIO: f64 = 0.8;
+
+/// A terminal grid rendered as an SVG image.
+///
+/// Text is kept as text so it can still be selected and searched for.
+pub(crate) struct SvgSlide {
+    background: String,
+    rectangles: String,
+    text: String,
+    images: String,
+    font_families: String,
+    width: f64,
+    height: f64,
+}
+
+impl SvgSlide {
+    /// Construct a slide that prefers the given font families, in order, to display its text.
+    pub(crate) fn new(grid: &TerminalGrid, font_families: &[String]) -> Self {
+        let columns = grid.rows.first().map(Vec::len).unwrap_or_default();
+        let background = color_to_html(grid.background_color.as_ref().unwrap_or(&DEFAULT_BACKGROUND));
+        let mut slide = Self {
+            background,
+            rectangles: String::new(),
+            text: String::new(),
+            images: String::new(),
+            font_families: font_families.iter().map(|family| format!("'{}', ", escape(&css_escape(family)))).collect(),
+            width: columns as f64 * Self::cell_width(),
+            height: grid.rows.len() as f64 * LINE_HEIGHT as f64,
+        };
+        for row in 0..grid.rows.len() {
+            for span in CellSpan::split(grid, row) {
+                slide.push_span(row, &span);
+            }
+        }
+        for ((row, column), printed) in &grid.images {
+            let TerminalImage::Raw(raw_image) = printed.image.image() else {
+                continue;
+            };
+            let (image_width, image_height) = raw_image.dimensions();
+            if image_width == 0 {
+                continue;
+            }
+            let x = *column as f64 * Self::cell_width();
+            let y = *row as f64 * LINE_HEIGHT as f64;
+            let width = printed.width_columns as f64 * Self::cell_width();
+            let height = width * image_height as f64 / image_width as f64;
+            let href = raw_image.to_inline_html();
+            let _ = writeln!(
+                slide.images,
+                r#"<image x="{x:.2}" y="{y:.2}" width="{width:.2}" height="{height:.2}" href="{href}"/>"#
+            );
+        }
+        slide
+    }
+
+    /// The width of every cell in the grid.
+    pub(crate) fn cell_width() -> f64 {
+        FONT_SIZE as f64 * FONT_SIZE_WIDTH
+    }
+
+    fn push_span(&mut self, row: usize, span: &CellSpan) {
+        let CellSpan { column, text, style, width, .. } = span;
+        let size = style.size.max(1) as f64;
+        let x = *column as f64 * Self::cell_width();
+        let y = row as f64 * LINE_HEIGHT as f64;
+        let run_width = *width as f64 * Self::cell_width();
+        let run_height = LINE_HEIGHT as f64 * size;
+        // There's no point in drawing a background that's the same as the slide's.
+        if let Some(color) = style.colors.background.as_ref().filter(|c| color_to_html(c) != self.background) {
+            self.push_rectangle(x, y, run_width, run_height, color);
+        }
+        let foreground = style.colors.foreground.as_ref().unwrap_or(&DEFAULT_FOREGROUND);
+
+        // Block characters are used to draw images as ascii so they're drawn as rectangles to avoid any gaps.
+        let cell_width = Self::cell_width() * size;
+        let mut visible_text = String::new();
+        for (index, character) in text.chars().enumerate() {
+            let cell_x = x + index as f64 * cell_width;
+            match character {
+                '█' => self.push_rectangle(cell_x, y, cell_width, run_height, foreground),
+                '▀' => self.push_rectangle(cell_x, y, cell_width, run_height / 2.0, foreground),
+                '▄' => self.push_rectangle(cell_x, y + run_height / 2.0, cell_width, run_height / 2.0, foreground),
+                _ => {
+                    visible_text.push(character);
+                    continue;
+                }
+            };
+            visible_text.push(' ');
+        }
+        let mut attributes = String::new();
+        let mut decorations = Vec::new();
+        for attribute in style.iter_attributes() {
+            match attribute {
+                TextAttribute::Bold => attributes.push_str(r#" font-weight="bold""#),
+                TextAttribute::Italics => attributes.push_str(r#" font-style="italic""#),
+                TextAttribute::Underlined => decorations.push("underline"),
+                TextAttribute::Strikethrough => decorations.push("line-through"),
+                TextAttribute::Superscript => attributes.push_str(r#" baseline-shift="super""#),
+                TextAttribute::ForegroundColor(_) | TextAttribute::BackgroundColor(_) => (),
+            };
+        }
+        if visible_text.trim().is_empty() && decorations.is_empty() {
+            return;
+        }
+        if !decorations.is_empty() {
+            let _ = write!(attributes, r#" text-decoration="{}""#, decorations.join(" "));
+        }
+        if size > 1.0 {
+            let _ = write!(attributes, r#" font-size="{}""#, FONT_SIZE as f64 * size);
+        }
+
+        let baseline = y + run_height * BASELINE_RATIO;
+        let foreground = color_to_html(foreground);
+        let position = format!(r#"x="{x:.2}" y="{baseline:.2}" textLength="{run_width:.2}""#);
+        let text = escape(&visible_text);
+        let _ = writeln!(
+            self.text,
+            r#"<text {position} lengthAdjust="spacingAndGlyphs" fill="{foreground}"{attributes}>{text}</text>"#
+        );
+    }
+
+    fn push_rectangle(&mut self, x: f64, y: f64, width: f64, height: f64, color: &Color) {
+        let color = color_to_html(color);
+        let _ = writeln!(
+            self.rectangles,
+            r#"<rect x="{x:.2}" y="{y:.2}" width="{width:.2}" height="{height:.2}" fill="{color}"/>"#
+        );
+    }
+}
+
+impl std::fmt::Display for SvgSlide {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self { background, rectangles, text, images, font_families, width, height } = self;
+        writeln!(
+            f,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width:.2}" height="{height:.2}" viewBox="0 0 {width:.2} {height:.2}">"#
+        )?;
+        writeln!(f, r#"<rect width="100%" height="100%" fill="{background}"/>"#)?;
+        write!(f, "{rectangles}")?;
+        // Not every viewer picks a sensible font for "monospace" so list the preferred ones first.
+        writeln!(f, r#"<g font-family="{font_families}monospace" font-size="{FONT_SIZE}" xml:space="preserve">"#)?;
+        write!(f, "{text}")?;
+        writeln!(f, "</g>")?;
+        write!(f, "{images}")?;
+        writeln!(f, "</svg>")
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' => output.push_str("&quot;"),
+            '\'' => output.push_str("&apos;"),
+            other => output.push(other),
+        }
+    }
+    output
+}
+
+// Escapes a string so it can be used within a single quoted CSS string.
+fn css_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{markdown::text_style::TextStyle, terminal::virt::StyledChar};
+
+    fn make_row(text: &str, style: TextStyle) -> Vec<StyledChar> {
+        text.chars().map(|character| StyledChar { character, style }).collect()
+    }
+
+    #[test]
+    fn render_text() {
+        let style = TextStyle::default().fg_color(Color::Red).bold();
+        let mut row = make_row("<a>", style);
+        row.extend(make_row("  ", TextStyle::default()));
+        let grid = TerminalGrid {
+            rows: vec![row],
+            background_color: Some(Color::Blue),
+            images: Default::default(),
+            links: Default::default(),
+        };
+        let svg = SvgSlide::new(&grid, &[]).to_string();
+        assert!(svg.contains(r##"<rect width="100%" height="100%" fill="#0000ff"/>"##), "{svg}");
+        assert!(svg.contains(r##"fill="#ff0000" font-weight="bold">&lt;a&gt;</text>"##), "{svg}");
+        // Whitespace only runs are not rendered.
+        assert_eq!(svg.matches("<text").count(), 1);
+    }
+
+    #[test]
+    fn render_blocks() {
+        let style = TextStyle::default().fg_color(Color::Red);
+        let grid = TerminalGrid {
+            rows: vec![make_row("▀", style)],
+            background_color: None,
+            images: Default::default(),
+            links: Default::default(),
+        };
+        let svg = SvgSlide::new(&grid, &[]).to_string();
+        let height = LINE_HEIGHT as f64 / 2.0;
+        let expected = format!(r##"<rect x="0.00" y="0.00" width="6.05" height="{height:.2}" fill="#ff0000"/>"##);
+        assert!(svg.contains(&expected), "{svg}");
+        assert!(!svg.contains("<text"), "{svg}");
+    }
+
+    #[test]
+    fn font_families() {
+        let grid = TerminalGrid {
+            rows: vec![make_row("hi", TextStyle::default())],
+            background_color: None,
+            images: Default::default(),
+            links: Default::default(),
+        };
+        let svg = SvgSlide::new(&grid, &["Fira Code".into(), "A&B".into(), "O'Neil".into()]).to_string();
+        assert!(svg.contains(r#"<g font-family="'Fira Code', 'A&amp;B', 'O\&apos;Neil', monospace""#), "{svg}");
+    }
+}
@@ -0,0 +1,212 @@
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, Read, Write},
+};
+
+const MAX_HEADERS_SIZE: u64 = 8192;
+const MAX_BODY_SIZE: usize = 4096;
+
+/// An HTTP request.
+#[derive(Debug, Default)]
+pub(crate) struct Request {
+    pub(crate) method: String,
+    pub(crate) path: String,
+    pub(crate) query: String,
+    pub(crate) headers: HashMap<String, String>,
+    pub(crate) body: Vec<u8>,
+}
+
+impl Request {
+    /// Read a request from the given reader.
+    pub(crate) fn read<R: BufRead>(reader: &mut R) -> Result<Self, RequestError> {
+        // Don't let clients make us buffer an arbitrary amount of data.
+        let mut headers_reader = (&mut *reader).take(MAX_HEADERS_SIZE);
+        let mut line = String::new();
+        headers_reader.read_line(&mut line)?;
+        let mut parts = line.split_whitespace();
+        let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+            return Err(RequestError::MalformedRequestLine);
+        };
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let mut request =
+            Request { method: method.into(), path: path.into(), query: query.into(), ..Default::default() };
+        loop {
+            line.clear();
+            if headers_reader.read_line(&mut line)? == 0 {
+                return Err(RequestError::MalformedHeader);
+            }
+            let header = line.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            let (name, value) = header.split_once(':').ok_or(RequestError::MalformedHeader)?;
+            request.headers.insert(name.trim().to_lowercase(), value.trim().into());
+        }
+        let length = match request.headers.get("content-length") {
+            Some(length) => length.parse().map_err(|_| RequestError::MalformedHeader)?,
+            None => 0,
+        };
+        if length > MAX_BODY_SIZE {
+            return Err(RequestError::BodyTooLarge);
+        }
+        request.body = vec![0; length];
+        reader.read_exact(&mut request.body)?;
+        Ok(request)
+    }
+
+    /// Get the value of a query string parameter.
+    pub(crate) fn query_param(&self, name: &str) -> Option<&str> {
+        self.query.split('&').filter_map(|pair| pair.split_once('=')).find(|(key, _)| *key == name).map(|(_, v)| v)
+    }
+
+    /// Check whether this request provides the given token.
+    ///
+    /// The token can be provided either via an `Authorization: Bearer` header or a `token` query string parameter.
+    pub(crate) fn has_token(&self, token: &str) -> bool {
+        let header_token = self.headers.get("authorization").and_then(|value| value.strip_prefix("Bearer "));
+        let query_token = self.query_param("token");
+        match header_token.or(query_token) {
+            Some(provided) => constant_time_eq(provided.trim().as_bytes(), token.as_bytes()),
+            None => false,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum RequestError {
+    #[error("malformed request line")]
+    MalformedRequestLine,
+
+    #[error("malformed header")]
+    MalformedHeader,
+
+    #[error("body too large")]
+    BodyTooLarge,
+
+    #[error("io: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// An HTTP response.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Response {
+    pub(crate) status: u16,
+    pub(crate) content_type: &'static str,
+    pub(crate) body: String,
+}
+
+impl Response {
+    /// Construct a JSON response.
+    pub(crate) fn new(status: u16, body: String) -> Self {
+        Self { status, content_type: "application/json", body }
+    }
+
+    /// Construct a successful JSON response out of a serializable value.
+    pub(crate) fn json<T: Serialize>(value: &T) -> Self {
+        Self::new(200, serde_json::to_string(value).expect("serialization failed"))
+    }
+
+    /// Construct a successful HTML response.
+    pub(crate) fn html(body: String) -> Self {
+        Self { status: 200, content_type: "text/html; charset=utf-8", body }
+    }
+
+    /// Construct an error response.
+    pub(crate) fn error(status: u16, message: &str) -> Self {
+        Self::new(status, serde_json::json!({ "error": message }).to_string())
+    }
+
+    /// Write this response into the given writer.
+    pub(crate) fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write!(
+            writer,
+            "HTTP/1.1 {} {}\r\n\
+            Content-Type: {}\r\n\
+            Content-Length: {}\r\n\
+            Connection: close\r\n\r\n{}",
+            self.status,
+            reason(self.status),
+            self.content_type,
+            self.body.len(),
+            self.body
+        )?;
+        writer.flush()
+    }
+}
+
+/// Write the headers that start a server-sent events stream.
+pub(crate) fn write_event_stream_headers<W: Write>(mut writer: W) -> io::Result<()> {
+    write!(
+        writer,
+        "HTTP/1.1 200 OK\r\n\
+        Content-Type: text/event-stream\r\n\
+        Cache-Control: no-cache\r\n\
+        Connection: keep-alive\r\n\r\n"
+    )?;
+    writer.flush()
+}
+
+// Compare in constant time so a token can't be guessed by timing responses.
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    if left.len() != right.len() {
+        return false;
+    }
+    left.iter().zip(right).fold(0, |acc, (l, r)| acc | (l ^ r)) == 0
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        503 => "Service Unavailable",
+        _ => "Unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Request {
+        Request::read(&mut input.as_bytes()).expect("invalid request")
+    }
+
+    #[test]
+    fn parse_request() {
+        let request = parse("POST /command?token=abc HTTP/1.1\r\nContent-Length: 4\r\nX-Foo: bar\r\n\r\nbody");
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/command");
+        assert_eq!(request.query_param("token"), Some("abc"));
+        assert_eq!(request.headers.get("x-foo").map(String::as_str), Some("bar"));
+        assert_eq!(request.body, b"body");
+    }
+
+    #[test]
+    fn body_too_large() {
+        let input = format!("POST /command HTTP/1.1\r\nContent-Length: {}\r\n\r\n", MAX_BODY_SIZE + 1);
+        let result = Request::read(&mut input.as_bytes());
+        assert!(matches!(result, Err(RequestError::BodyTooLarge)));
+    }
+
+    #[test]
+    fn truncated_headers() {
+        let result = Request::read(&mut "GET / HTTP/1.1\r\nHost: foo\r\n".as_bytes());
+        assert!(matches!(result, Err(RequestError::MalformedHeader)));
+    }
+
+    #[test]
+    fn write_response() {
+        let mut output = Vec::new();
+        Response::html("hi".into()).write(&mut output).expect("write failed");
+        let output = String::from_utf8(output).expect("not utf8");
+        assert!(
+            output.starts_with("HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: 2\r\n")
+        );
+        assert!(output.ends_with("\r\n\r\nhi"));
+    }
+}
@@ -3,21 +3,34 @@ use crate::{
     commands::listener::CommandListener,
     config::{Config, ImageProtocol, ValidateOverflows},
     demo::ThemesDemo,
-    export::exporter::Exporter,
+    export::{
+        exporter::Exporter,
+        images::ImageExportFormat,
+        mirror::{AudienceMirror, AudienceMirrorServer},
+    },
     markdown::parse::MarkdownParser,
     presentation::builder::{CommentCommand, PresentationBuilderOptions, Themes},
-    presenter::{PresentMode, Presenter, PresenterOptions},
+    presenter::{DynamicTheme, PresentMode, Presenter, PresenterOptions},
     resource::Resources,
     terminal::{
         GraphicsMode,
+        appearance::{Appearance, AppearanceWatcher},
         image::printer::{ImagePrinter, ImageRegistry},
+        record::AsciicastPlayer,
     },
     theme::{raw::PresentationTheme, registry::PresentationThemeRegistry},
     third_party::{ThirdPartyConfigs, ThirdPartyRender},
+    utils::HumanDuration,
 };
 use anyhow::anyhow;
 use clap::{CommandFactory, Parser, error::ErrorKind};
-use commands::speaker_notes::{SpeakerNotesEventListener, SpeakerNotesEventPublisher};
+use commands::{
+    remote::{RemoteControlListener, RemoteControlPublisher, RemoteControlServer},
+    speaker_notes::{
+        SpeakerNotesControlListener, SpeakerNotesControlPublisher, SpeakerNotesEventListener,
+        SpeakerNotesEventPublisher,
+    },
+};
 use comrak::Arena;
 use config::ConfigLoadError;
 use crossterm::{
@@ -42,6 +55,7 @@ mod commands;
 mod config;
 mod demo;
 mod export;
+mod http;
 mod markdown;
 mod presentation;
 mod presenter;
@@ -77,11 +91,23 @@ struct Cli {
     #[clap(short = 'E', long, group = "export")]
     export_html: bool,
 
+    /// Export the presentation as an animated GIF rather than displaying it.
+    #[clap(long, group = "export")]
+    export_gif: bool,
+
+    /// Export every slide in the presentation as an SVG image rather than displaying it.
+    #[clap(long, group = "export")]
+    export_svg: bool,
+
+    /// Export every slide in the presentation as a PNG image rather than displaying it.
+    #[clap(long, group = "export")]
+    export_png: bool,
+
     /// The path in which to store temporary files used when exporting.
     #[clap(long, requires = "export")]
     export_temporary_path: Option<PathBuf>,
 
-    /// The output path for the exported PDF.
+    /// The output path for the exported file, or the output directory when exporting images.
     #[clap(short = 'o', long = "output", requires = "export")]
     export_output: Option<PathBuf>,
 
@@ -94,6 +120,10 @@ struct Cli {
     #[clap(short, long, default_value_t = false)]
     present: bool,
 
+    /// Automatically move through the presentation, waiting this long before every step (e.g. `10s`).
+    #[clap(long, conflicts_with = "export")]
+    auto_advance: Option<HumanDuration>,
+
     /// The theme to use.
     #[clap(short, long)]
     theme: Option<String>,
@@ -145,6 +175,20 @@ struct Cli {
     /// List all available comment commands.
     #[clap(long, group = "target")]
     list_comment_commands: bool,
+
+    /// Record the presentation as an asciicast file in the given path.
+    #[clap(long, conflicts_with = "export")]
+    record: Option<PathBuf>,
+
+    /// Replay a recording created via `--record`.
+    #[clap(long, group = "target")]
+    replay: Option<PathBuf>,
+}
+
+impl Cli {
+    fn exporting(&self) -> bool {
+        self.export_pdf || self.export_html || self.export_gif || self.export_svg || self.export_png
+    }
 }
 
 fn create_splash() -> String {
@@ -189,7 +233,10 @@ impl Customizations {
             Err(ConfigLoadError::NotFound) if !require_config_file => Default::default(),
             Err(e) => return Err(e.into()),
         };
-        let code_executor = SnippetExecutor::new(config.snippet.exec.custom.clone(), cwd.to_path_buf())?;
+        let exec_config = &config.snippet.exec;
+        let code_executor = SnippetExecutor::new(exec_config.custom.clone(), cwd.to_path_buf())?
+            .with_limits(exec_config.limits.clone(), exec_config.language_limits.clone())
+            .with_sandbox(exec_config.sandbox.clone());
         Ok(Customizations { config, themes, themes_path: Some(themes_path), code_executor })
     }
 
@@ -213,6 +260,7 @@ struct CoreComponents {
     builder_options: PresentationBuilderOptions,
     themes: Themes,
     default_theme: PresentationTheme,
+    dynamic_theme: Option<DynamicTheme>,
     config: Config,
     present_mode: PresentMode,
     graphics_mode: GraphicsMode,
@@ -229,9 +277,10 @@ impl CoreComponents {
         let Customizations { config, themes, code_executor, themes_path } =
             Customizations::load(cli.config_file.clone().map(PathBuf::from), &resources_path)?;
 
-        let default_theme = Self::load_default_theme(&config, &themes, cli);
+        let dynamic_theme = Self::dynamic_theme(&config, cli);
+        let default_theme = Self::load_default_theme(&config, &themes, cli, dynamic_theme.as_ref());
         let force_default_theme = cli.theme.is_some();
-        let present_mode = match (cli.present, cli.export_pdf) {
+        let present_mode = match (cli.present, cli.exporting() && !cli.export_html) {
             (true, _) | (_, true) => PresentMode::Presentation,
             (false, false) => PresentMode::Development,
         };
@@ -243,6 +292,9 @@ impl CoreComponents {
         if cli.enable_snippet_execution_replace {
             builder_options.enable_snippet_execution_replace = true;
         }
+        if let Some(delay) = &cli.auto_advance {
+            builder_options.auto_advance = Some(delay.0);
+        }
         let graphics_mode = Self::select_graphics_mode(cli, &config);
         let printer = Arc::new(ImagePrinter::new(graphics_mode.clone())?);
         let registry = ImageRegistry::new(printer.clone());
@@ -270,6 +322,7 @@ impl CoreComponents {
             builder_options,
             themes,
             default_theme,
+            dynamic_theme,
             config,
             present_mode,
             graphics_mode,
@@ -295,6 +348,7 @@ impl CoreComponents {
             strict_front_matter_parsing: options.strict_front_matter_parsing.unwrap_or(true),
             enable_snippet_execution: config.snippet.exec.enable,
             enable_snippet_execution_replace: config.snippet.exec_replace.enable,
+            enable_interactive_snippets: true,
             render_speaker_notes_only,
             auto_render_languages: options.auto_render_languages.clone(),
             theme_options: ThemeOptions { font_size_supported: TerminalEmulator::capabilities().font_size },
@@ -307,11 +361,12 @@ impl CoreComponents {
             validate_snippets: config.snippet.validate,
             layout_grid: false,
             h1_slide_titles: options.h1_slide_titles.unwrap_or_default(),
+            auto_advance: options.auto_advance.as_ref().map(|delay| delay.0),
         }
     }
 
     fn select_graphics_mode(cli: &Cli, config: &Config) -> GraphicsMode {
-        if cli.export_pdf | cli.export_html {
+        if cli.exporting() {
             GraphicsMode::Raw
         } else {
             cli.image_protocol.as_ref().unwrap_or(&config.defaults.image_protocol).into()
@@ -325,26 +380,49 @@ impl CoreComponents {
             match &config.defaults.theme {
                 config::ThemeConfig::None => DEFAULT_THEME.into(),
                 config::ThemeConfig::Some(theme_name) => theme_name.clone(),
-                config::ThemeConfig::Dynamic { dark, light, timeout } => {
-                    let default_timeout = timeout.unwrap_or(DEFAULT_THEME_DYNAMIC_DETECTION_TIMEOUT);
-                    let timeout_duration = Duration::from_millis(default_timeout);
-                    if let Ok(theme) = termbg::theme(timeout_duration) {
-                        if theme == termbg::Theme::Dark { dark.clone() } else { light.clone() }
-                    } else {
-                        Cli::command()
-                            .error(
-                                ErrorKind::Io,
-                                "terminal theme detection failed, unsupported terminal or timeout exceeded",
-                            )
-                            .exit();
-                    }
-                }
+                config::ThemeConfig::Dynamic { dark, light, timeout, .. } => match Self::detect_appearance(*timeout) {
+                    Appearance::Dark => dark.clone(),
+                    Appearance::Light => light.clone(),
+                },
             }
         }
     }
 
-    fn load_default_theme(config: &Config, themes: &Themes, cli: &Cli) -> PresentationTheme {
-        let default_theme_name = Self::theme_name(config, cli);
+    fn detect_appearance(timeout: Option<u64>) -> Appearance {
+        let timeout = Duration::from_millis(timeout.unwrap_or(DEFAULT_THEME_DYNAMIC_DETECTION_TIMEOUT));
+        match Appearance::detect(timeout) {
+            Some(appearance) => appearance,
+            None => Cli::command()
+                .error(ErrorKind::Io, "terminal theme detection failed, unsupported terminal or timeout exceeded")
+                .exit(),
+        }
+    }
+
+    // The themes to switch between while presenting, if the terminal's appearance needs to be followed.
+    fn dynamic_theme(config: &Config, cli: &Cli) -> Option<DynamicTheme> {
+        let config::ThemeConfig::Dynamic { dark, light, timeout, poll_interval: Some(interval) } =
+            &config.defaults.theme
+        else {
+            return None;
+        };
+        if cli.theme.is_some() || cli.exporting() {
+            return None;
+        }
+        let appearance = Self::detect_appearance(*timeout);
+        let watcher = AppearanceWatcher::new(appearance, Duration::from_millis(*interval));
+        Some(DynamicTheme { dark: dark.clone(), light: light.clone(), watcher })
+    }
+
+    fn load_default_theme(
+        config: &Config,
+        themes: &Themes,
+        cli: &Cli,
+        dynamic_theme: Option<&DynamicTheme>,
+    ) -> PresentationTheme {
+        let default_theme_name = match dynamic_theme {
+            Some(dynamic_theme) => dynamic_theme.theme_name(dynamic_theme.watcher.appearance()).to_string(),
+            None => Self::theme_name(config, cli),
+        };
         let Some(default_theme) = themes.presentation.load_by_name(default_theme_name.as_str()) else {
             let valid_themes = themes.presentation.theme_names().join(", ");
             let error_message = format!("invalid theme name, valid themes are: {valid_themes}");
@@ -357,6 +435,8 @@ impl CoreComponents {
 struct SpeakerNotesComponents {
     events_listener: Option<SpeakerNotesEventListener>,
     events_publisher: Option<SpeakerNotesEventPublisher>,
+    control_listener: Option<SpeakerNotesControlListener>,
+    control_publisher: Option<SpeakerNotesControlPublisher>,
 }
 
 impl SpeakerNotesComponents {
@@ -372,11 +452,57 @@ impl SpeakerNotesComponents {
             .map_err(|e| anyhow!("failed to create speaker notes publisher: {e}"))?;
         let events_listener = cli
             .listen_speaker_notes
-            .then(|| SpeakerNotesEventListener::new(config.speaker_notes.listen_address, full_presentation_path))
+            .then(|| {
+                SpeakerNotesEventListener::new(config.speaker_notes.listen_address, full_presentation_path.clone())
+            })
             .transpose()
             .map_err(|e| anyhow!("failed to create speaker notes listener: {e}"))?;
-        Ok(Self { events_listener, events_publisher })
+        let enable_control = config.speaker_notes.enable_control;
+        let control_address = config.speaker_notes.control_address;
+        let control_listener = (enable_control && events_publisher.is_some())
+            .then(|| SpeakerNotesControlListener::new(control_address, full_presentation_path.clone()))
+            .transpose()
+            .map_err(|e| anyhow!("failed to create speaker notes control listener: {e}"))?;
+        let control_publisher = (enable_control && events_listener.is_some())
+            .then(|| SpeakerNotesControlPublisher::new(control_address, full_presentation_path))
+            .transpose()
+            .map_err(|e| anyhow!("failed to create speaker notes control publisher: {e}"))?;
+        Ok(Self { events_listener, events_publisher, control_listener, control_publisher })
+    }
+}
+
+fn launch_remote_control(
+    cli: &Cli,
+    config: &Config,
+) -> anyhow::Result<Option<(RemoteControlListener, RemoteControlPublisher)>> {
+    // Speaker notes instances follow the main one so only that one can be controlled.
+    if !config.remote_control.enable || cli.listen_speaker_notes {
+        return Ok(None);
     }
+    let token = config
+        .remote_control
+        .token
+        .clone()
+        .filter(|token| !token.is_empty())
+        .ok_or_else(|| anyhow!("remote control requires 'remote_control.token' to be set"))?;
+    let components = RemoteControlServer::launch(config.remote_control.address, token)
+        .map_err(|e| anyhow!("failed to launch remote control server: {e}"))?;
+    Ok(Some(components))
+}
+
+fn launch_audience_mirror(cli: &Cli, config: &Config) -> anyhow::Result<Option<AudienceMirror>> {
+    if !config.audience_mirror.enable || cli.listen_speaker_notes {
+        return Ok(None);
+    }
+    let token = config
+        .audience_mirror
+        .token
+        .clone()
+        .filter(|token| !token.is_empty())
+        .ok_or_else(|| anyhow!("audience mirror requires 'audience_mirror.token' to be set"))?;
+    let mirror = AudienceMirrorServer::launch(config.audience_mirror.address, token)
+        .map_err(|e| anyhow!("failed to launch audience mirror server: {e}"))?;
+    Ok(Some(mirror))
 }
 
 fn overflow_validation_enabled(mode: &PresentMode, config: &ValidateOverflows) -> bool {
@@ -415,6 +541,9 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
         let theme_name = CoreComponents::theme_name(&config, &cli);
         println!("{theme_name}");
         return Ok(());
+    } else if let Some(path) = &cli.replay {
+        AsciicastPlayer::load(path)?.play()?;
+        return Ok(());
     } else if cli.list_comment_commands {
         let samples = CommentCommand::generate_samples();
         for sample in samples {
@@ -423,7 +552,7 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
         return Ok(());
     }
     // Disable this so we don't mess things up when exporting.
-    if cli.export_pdf || cli.export_html {
+    if cli.exporting() {
         TerminalEmulator::disable_capability_detection();
     }
 
@@ -438,6 +567,7 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
         mut builder_options,
         themes,
         default_theme,
+        dynamic_theme,
         config,
         present_mode,
         graphics_mode,
@@ -449,7 +579,7 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
     if cli.validate_snippets {
         builder_options.validate_snippets = cli.validate_snippets;
     }
-    if cli.export_pdf || cli.export_html {
+    if cli.exporting() {
         let dimensions = match config.export.dimensions {
             Some(dimensions) => WindowSize {
                 rows: dimensions.rows,
@@ -478,13 +608,21 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
         }?;
         if cli.export_pdf {
             exporter.export_pdf(&path, output_directory, cli.export_output.as_deref(), config.export.pdf)?;
+        } else if cli.export_gif {
+            exporter.export_gif(&path, cli.export_output.as_deref(), config.export.gif, config.transition)?;
+        } else if cli.export_svg || cli.export_png {
+            let format = if cli.export_svg { ImageExportFormat::Svg } else { ImageExportFormat::Png };
+            exporter.export_images(&path, cli.export_output.as_deref(), format, config.export.images)?;
         } else {
             exporter.export_html(&path, output_directory, cli.export_output.as_deref())?;
         }
     } else {
-        let SpeakerNotesComponents { events_listener, events_publisher } =
+        let SpeakerNotesComponents { events_listener, events_publisher, control_listener, control_publisher } =
             SpeakerNotesComponents::new(&cli, &config, &path)?;
-        let command_listener = CommandListener::new(config.bindings.clone(), events_listener)?;
+        let (remote_control_listener, remote_control_publisher) = launch_remote_control(&cli, &config)?.unzip();
+        let audience_mirror = launch_audience_mirror(&cli, &config)?;
+        let command_listener =
+            CommandListener::new(config.bindings.clone(), events_listener, control_listener, remote_control_listener)?;
 
         builder_options.print_modal_background = matches!(graphics_mode, GraphicsMode::Kitty { .. });
         let options = PresenterOptions {
@@ -500,6 +638,9 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
                 max_rows_alignment: config.defaults.max_rows_alignment,
             },
             transition: config.transition,
+            speaker_notes_layout: config.speaker_notes.layout,
+            record_path: cli.record.clone(),
+            dynamic_theme,
         };
         let presenter = Presenter::new(
             &default_theme,
@@ -512,6 +653,9 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
             printer,
             options,
             events_publisher,
+            control_publisher,
+            remote_control_publisher,
+            audience_mirror,
         );
         presenter.present(&path)?;
     }
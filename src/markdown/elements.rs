@@ -133,7 +133,7 @@ impl Line<RawColor> {
         let mut output = Vec::with_capacity(self.0.len());
         for text in self.0 {
             let style = text.style.resolve(palette)?;
-            output.push(Text::new(text.content, style));
+            output.push(Text { content: text.content, style, link: text.link });
         }
         Ok(Line(output))
     }
@@ -147,23 +147,30 @@ impl<C, T: Into<Text<C>>> From<T> for Line<C> {
 
 /// A styled piece of text.
 ///
-/// This is the most granular text representation: a `String` and a style.
+/// This is the most granular text representation: a `String`, a style, and the URL it links to, if any.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub(crate) struct Text<C = Color> {
     pub(crate) content: String,
     pub(crate) style: TextStyle<C>,
+    pub(crate) link: Option<String>,
 }
 
 impl<C> Default for Text<C> {
     fn default() -> Self {
-        Self { content: Default::default(), style: TextStyle::default() }
+        Self { content: Default::default(), style: TextStyle::default(), link: None }
     }
 }
 
 impl<C> Text<C> {
     /// Construct a new styled text.
     pub(crate) fn new<S: Into<String>>(content: S, style: TextStyle<C>) -> Self {
-        Self { content: content.into(), style }
+        Self { content: content.into(), style, link: None }
+    }
+
+    /// Make this text a hyperlink to the given URL.
+    pub(crate) fn with_link<S: Into<String>>(mut self, url: S) -> Self {
+        self.link = Some(url.into());
+        self
     }
 
     /// Get the width of this text.
@@ -174,13 +181,13 @@ impl<C> Text<C> {
 
 impl<C> From<String> for Text<C> {
     fn from(text: String) -> Self {
-        Self { content: text, style: TextStyle::default() }
+        Self { content: text, style: TextStyle::default(), link: None }
     }
 }
 
 impl<C> From<&str> for Text<C> {
     fn from(text: &str) -> Self {
-        Self { content: text.into(), style: TextStyle::default() }
+        Self { content: text.into(), style: TextStyle::default(), link: None }
     }
 }
 
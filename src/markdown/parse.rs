@@ -437,10 +437,15 @@ impl<'a> InlinesParser<'a> {
             NodeValue::Link(link) => {
                 let has_label = node.first_child().is_some();
                 if has_label {
+                    let label_start = self.pending_text.len();
                     self.process_children(node, TextStyle::default().link_label())?;
+                    for text in self.pending_text.iter_mut().skip(label_start) {
+                        text.link = Some(link.url.clone());
+                    }
                     self.pending_text.push(Text::from(" ("));
                 }
-                self.pending_text.push(Text::new(link.url.clone(), TextStyle::default().link_url()));
+                self.pending_text
+                    .push(Text::new(link.url.clone(), TextStyle::default().link_url()).with_link(&link.url));
                 if !link.title.is_empty() {
                     self.pending_text.push(Text::from(" \""));
                     self.pending_text.push(Text::new(link.title.clone(), TextStyle::default().link_title()));
@@ -451,7 +456,8 @@ impl<'a> InlinesParser<'a> {
                 }
             }
             NodeValue::WikiLink(link) => {
-                self.pending_text.push(Text::new(link.url.clone(), TextStyle::default().link_url()));
+                self.pending_text
+                    .push(Text::new(link.url.clone(), TextStyle::default().link_url()).with_link(&link.url));
             }
             NodeValue::LineBreak => {
                 self.store_pending_text();
@@ -799,8 +805,10 @@ boop
     fn link_wo_label_wo_title() {
         let parsed = parse_single("my [](https://example.com)");
         let MarkdownElement::Paragraph(elements) = parsed else { panic!("not a paragraph: {parsed:?}") };
-        let expected_chunks =
-            vec![Text::from("my "), Text::new("https://example.com", TextStyle::default().link_url())];
+        let expected_chunks = vec![
+            Text::from("my "),
+            Text::new("https://example.com", TextStyle::default().link_url()).with_link("https://example.com"),
+        ];
 
         let expected_elements = &[Line(expected_chunks)];
         assert_eq!(elements, expected_elements);
@@ -812,9 +820,9 @@ boop
         let MarkdownElement::Paragraph(elements) = parsed else { panic!("not a paragraph: {parsed:?}") };
         let expected_chunks = vec![
             Text::from("my "),
-            Text::new("website", TextStyle::default().link_label()),
+            Text::new("website", TextStyle::default().link_label()).with_link("https://example.com"),
             Text::from(" ("),
-            Text::new("https://example.com", TextStyle::default().link_url()),
+            Text::new("https://example.com", TextStyle::default().link_url()).with_link("https://example.com"),
             Text::from(")"),
         ];
 
@@ -828,7 +836,7 @@ boop
         let MarkdownElement::Paragraph(elements) = parsed else { panic!("not a paragraph: {parsed:?}") };
         let expected_chunks = vec![
             Text::from("my "),
-            Text::new("https://example.com", TextStyle::default().link_url()),
+            Text::new("https://example.com", TextStyle::default().link_url()).with_link("https://example.com"),
             Text::from(" \""),
             Text::new("Example", TextStyle::default().link_title()),
             Text::from("\""),
@@ -844,9 +852,9 @@ boop
         let MarkdownElement::Paragraph(elements) = parsed else { panic!("not a paragraph: {parsed:?}") };
         let expected_chunks = vec![
             Text::from("my "),
-            Text::new("website", TextStyle::default().link_label()),
+            Text::new("website", TextStyle::default().link_label()).with_link("https://example.com"),
             Text::from(" ("),
-            Text::new("https://example.com", TextStyle::default().link_url()),
+            Text::new("https://example.com", TextStyle::default().link_url()).with_link("https://example.com"),
             Text::from(" \""),
             Text::new("Example", TextStyle::default().link_title()),
             Text::from("\""),
@@ -861,7 +869,8 @@ boop
     fn wikilink_wo_title() {
         let parsed = parse_single("[[https://example.com]]");
         let MarkdownElement::Paragraph(elements) = parsed else { panic!("not a paragraph: {parsed:?}") };
-        let expected_chunks = vec![Text::new("https://example.com", TextStyle::default().link_url())];
+        let expected_chunks =
+            vec![Text::new("https://example.com", TextStyle::default().link_url()).with_link("https://example.com")];
 
         let expected_elements = &[Line(expected_chunks)];
         assert_eq!(elements, expected_elements);
@@ -2,7 +2,7 @@ use super::{
     elements::{Line, Text},
     text_style::TextStyle,
 };
-use std::{fmt, mem};
+use std::{fmt, mem, ops::Range};
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 /// A weighted line of text.
@@ -30,6 +30,61 @@ impl WeightedLine {
     pub(crate) fn font_size(&self) -> u8 {
         self.font_size
     }
+
+    /// Iterate the texts in this line.
+    pub(crate) fn iter_texts(&self) -> impl Iterator<Item = &WeightedText> {
+        self.text.iter()
+    }
+
+    /// Apply a style on top of every case insensitive occurrence of `query` in this line.
+    ///
+    /// Returns `None` if the query doesn't appear in this line.
+    pub(crate) fn highlight(&self, query: &str, style: &TextStyle) -> Option<Self> {
+        let content: String = self.text.iter().map(|text| text.text.content.as_str()).collect();
+        let ranges = find_ignore_case(&content, query);
+        if ranges.is_empty() {
+            return None;
+        }
+        let mut texts: Vec<Text> = Vec::new();
+        let mut ranges = ranges.into_iter().peekable();
+        let chars = self.text.iter().flat_map(|text| text.text.content.chars().map(move |c| (c, &text.text)));
+        for (index, (c, source)) in chars.enumerate() {
+            while ranges.next_if(|range| range.end <= index).is_some() {}
+            let mut char_style = source.style;
+            if ranges.peek().is_some_and(|range| range.contains(&index)) {
+                char_style = style.merged(&char_style);
+            }
+            match texts.last_mut() {
+                Some(text) if text.style == char_style && text.link == source.link => text.content.push(c),
+                _ => texts.push(Text { content: c.into(), style: char_style, link: source.link.clone() }),
+            };
+        }
+        Some(texts.into())
+    }
+}
+
+/// Find the ranges of characters in which `query` appears in `text`, ignoring case.
+pub(crate) fn find_ignore_case(text: &str, query: &str) -> Vec<Range<usize>> {
+    let text: Vec<char> = text.chars().map(lowercase).collect();
+    let query: Vec<char> = query.chars().map(lowercase).collect();
+    let mut ranges = Vec::new();
+    if query.is_empty() {
+        return ranges;
+    }
+    let mut index = 0;
+    while index + query.len() <= text.len() {
+        if text[index..index + query.len()] == query[..] {
+            ranges.push(index..index + query.len());
+            index += query.len();
+        } else {
+            index += 1;
+        }
+    }
+    ranges
+}
+
+fn lowercase(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
 }
 
 impl From<Line> for WeightedLine {
@@ -44,11 +99,11 @@ impl From<Vec<Text>> for WeightedLine {
         let mut index = 0;
         let mut width = 0;
         let mut font_size = 1;
-        // Compact chunks so any consecutive chunk with the same style is merged into the same block.
+        // Compact chunks so any consecutive chunk with the same style and link is merged into the same block.
         while index < texts.len() {
             let mut target = mem::replace(&mut texts[index], Text::from(""));
             let mut current = index + 1;
-            while current < texts.len() && texts[current].style == target.style {
+            while current < texts.len() && texts[current].style == target.style && texts[current].link == target.link {
                 let current_content = mem::take(&mut texts[current].content);
                 target.content.push_str(&current_content);
                 current += 1;
@@ -92,7 +147,12 @@ pub(crate) struct WeightedText {
 
 impl WeightedText {
     fn to_ref(&self) -> WeightedTextRef<'_> {
-        WeightedTextRef { text: &self.text.content, accumulators: &self.accumulators, style: self.text.style }
+        WeightedTextRef {
+            text: &self.text.content,
+            accumulators: &self.accumulators,
+            style: self.text.style,
+            link: self.text.link.as_deref(),
+        }
     }
 
     pub(crate) fn width(&self) -> usize {
@@ -184,6 +244,7 @@ pub(crate) struct WeightedTextRef<'a> {
     text: &'a str,
     accumulators: &'a [CharAccumulator],
     style: TextStyle,
+    link: Option<&'a str>,
 }
 
 impl<'a> WeightedTextRef<'a> {
@@ -192,6 +253,11 @@ impl<'a> WeightedTextRef<'a> {
         (self.text, self.style)
     }
 
+    /// The URL this text links to, if any.
+    pub(crate) fn link(&self) -> Option<&'a str> {
+        self.link
+    }
+
     // Attempts to split this at a word boundary.
     //
     // This will try to consume as many words as possible up to the given maximum length, and
@@ -220,14 +286,14 @@ impl<'a> WeightedTextRef<'a> {
         let leading_char_count = self.text[0..from].chars().count();
         let output_char_count = text.chars().count();
         let character_lengths = &self.accumulators[leading_char_count..leading_char_count + output_char_count + 1];
-        WeightedTextRef { text, accumulators: character_lengths, style: self.style }
+        WeightedTextRef { text, accumulators: character_lengths, style: self.style, link: self.link }
     }
 
     fn trim_start(self) -> Self {
         let text = self.text.trim_start();
         let trimmed = self.text.chars().count() - text.chars().count();
         let accumulators = &self.accumulators[trimmed..];
-        Self { text, accumulators, style: self.style }
+        Self { text, accumulators, style: self.style, link: self.link }
     }
 
     pub(crate) fn width(&self) -> usize {
@@ -253,6 +319,55 @@ mod test {
         lines.map(|l| l.iter().map(|weighted| weighted.text).collect::<Vec<_>>().join(" ")).collect()
     }
 
+    #[rstest]
+    #[case::none("hello", "bye", &[])]
+    #[case::ignores_case("Hello hELLO", "hello", &[0..5, 6..11])]
+    #[case::non_overlapping("aaaa", "aa", &[0..2, 2..4])]
+    #[case::utf8("██ab ab", "ab", &[2..4, 5..7])]
+    fn find_ranges(#[case] text: &str, #[case] query: &str, #[case] expected: &[Range<usize>]) {
+        assert_eq!(find_ignore_case(text, query), expected);
+    }
+
+    #[test]
+    fn highlight() {
+        let bold = TextStyle::default().bold();
+        let line = WeightedLine::from(vec![Text::new("hello ", bold), Text::from("world")]);
+        let style = TextStyle::default().underlined();
+        let highlighted = line.highlight("o w", &style).expect("no match");
+        let texts: Vec<_> = highlighted.iter_texts().map(|text| text.text().clone()).collect();
+        let expected =
+            &[Text::new("hell", bold), Text::new("o ", bold.underlined()), Text::new("w", style), Text::from("orld")];
+        assert_eq!(texts, expected);
+        assert_eq!(highlighted.width(), line.width());
+        assert!(line.highlight("potato", &style).is_none());
+    }
+
+    #[test]
+    fn links_not_merged() {
+        let line = WeightedLine::from(vec![
+            Text::from("see "),
+            Text::from("here").with_link("https://example.com"),
+            Text::from("there").with_link("https://example.com"),
+        ]);
+        let texts: Vec<_> = line.iter_texts().map(|text| text.text().clone()).collect();
+        let expected = &[Text::from("see "), Text::from("herethere").with_link("https://example.com")];
+        assert_eq!(texts, expected);
+
+        let style = TextStyle::default().bold();
+        let highlighted = line.highlight("e h", &style).expect("no match");
+        let texts: Vec<_> = highlighted.iter_texts().map(|text| text.text().clone()).collect();
+        let expected = &[
+            Text::from("se"),
+            Text::new("e ", style),
+            Text::new("h", style).with_link("https://example.com"),
+            Text::from("erethere").with_link("https://example.com"),
+        ];
+        assert_eq!(texts, expected);
+
+        let links: Vec<_> = line.split(5).flatten().map(|chunk| chunk.link()).collect();
+        assert_eq!(links, &[None, Some("https://example.com"), Some("https://example.com")]);
+    }
+
     #[test]
     fn text_creation() {
         let text = WeightedText::from("hello world");
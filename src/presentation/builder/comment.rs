@@ -3,6 +3,7 @@ use crate::{
     presentation::builder::{BuildResult, LayoutState, PresentationBuilder, error::InvalidPresentation},
     render::operation::{LayoutGrid, RenderOperation},
     theme::{Alignment, ElementType},
+    utils::HumanDuration,
 };
 use serde::Deserialize;
 use std::{fmt, num::NonZeroU8, path::PathBuf, str::FromStr};
@@ -93,7 +94,9 @@ impl PresentationBuilder<'_, '_> {
             CommentCommand::NoFooter => {
                 self.slide_state.ignore_footer = true;
             }
-            CommentCommand::SpeakerNote(_) => {}
+            CommentCommand::SpeakerNote(note) => {
+                self.slide_state.speaker_notes.push(note);
+            }
             CommentCommand::FontSize(size) => {
                 if size == 0 || size > 7 {
                     return Err(self.invalid_presentation(source_position, InvalidPresentation::InvalidFontSize));
@@ -113,6 +116,12 @@ impl PresentationBuilder<'_, '_> {
             CommentCommand::SkipSlide => {
                 self.slide_state.skip_slide = true;
             }
+            CommentCommand::TimeBudget(budget) => {
+                self.slide_state.time_budget = Some(budget.0);
+            }
+            CommentCommand::AutoAdvance(delay) => {
+                self.slide_state.auto_advance = Some(delay.0);
+            }
             CommentCommand::ListItemNewlines(count) => {
                 self.slide_state.list_item_newlines = Some(count.into());
             }
@@ -124,7 +133,11 @@ impl PresentationBuilder<'_, '_> {
                 // Ignore this if execution is disabled
                 if self.options.enable_snippet_execution {
                     let handle = self.executable_snippets.get(&id).cloned().ok_or_else(|| {
-                        self.invalid_presentation(source_position, InvalidPresentation::UndefinedSnippetId(id))
+                        let error = match self.identified_snippets.contains_key(&id) {
+                            true => InvalidPresentation::SnippetNotExecutable(id),
+                            false => InvalidPresentation::UndefinedSnippetId(id),
+                        };
+                        self.invalid_presentation(source_position, error)
                     })?;
                     self.push_detached_code_execution(handle)?;
                 }
@@ -148,6 +161,8 @@ impl PresentationBuilder<'_, '_> {
             CommentCommand::EndSlide => self.terminate_slide(),
             CommentCommand::Pause => self.push_pause(),
             CommentCommand::SkipSlide => self.slide_state.skip_slide = true,
+            CommentCommand::TimeBudget(budget) => self.slide_state.time_budget = Some(budget.0),
+            CommentCommand::AutoAdvance(delay) => self.slide_state.auto_advance = Some(delay.0),
             _ => {}
         }
     }
@@ -206,6 +221,7 @@ impl PresentationBuilder<'_, '_> {
 #[serde(rename_all = "snake_case")]
 pub(crate) enum CommentCommand {
     Alignment(CommentCommandAlignment),
+    AutoAdvance(HumanDuration),
     Column(usize),
     EndSlide,
     FontSize(u8),
@@ -226,6 +242,7 @@ pub(crate) enum CommentCommand {
     SkipSlide,
     SpeakerNote(String),
     SnippetOutput(String),
+    TimeBudget(HumanDuration),
     Comment(String),
 }
 
@@ -241,6 +258,7 @@ impl CommentCommand {
                     Alignment => {
                         vec!["<!-- alignment: left -->", "<!-- alignment: center -->", "<!-- alignment: right -->"]
                     }
+                    AutoAdvance => vec!["<!-- auto_advance: 10s -->"],
                     Column => vec!["<!-- column: 0 -->"],
                     EndSlide => vec!["<!-- end_slide -->"],
                     FontSize => vec!["<!-- font_size: 2 -->"],
@@ -262,6 +280,7 @@ impl CommentCommand {
                     SkipSlide => vec!["<!-- skip_slide -->"],
                     SpeakerNote => vec!["<!-- speaker_note: Your note here -->"],
                     SnippetOutput => vec!["<!-- snippet_output: identifier -->"],
+                    TimeBudget => vec!["<!-- time_budget: 2m -->"],
                     Comment => vec!["<!-- comment: hi mom -->"],
                 }
             })
@@ -308,7 +327,7 @@ mod tests {
     use crate::presentation::builder::{PresentationBuilderOptions, utils::Test};
     use image::{DynamicImage, ImageEncoder, codecs::png::PngEncoder};
     use rstest::rstest;
-    use std::{fs, io::BufWriter};
+    use std::{fs, io::BufWriter, time::Duration};
     use tempfile::tempdir;
 
     #[rstest]
@@ -324,6 +343,8 @@ mod tests {
     #[case::incremental_lists("new_line", CommentCommand::NewLine)]
     #[case::incremental_lists("newline", CommentCommand::NewLine)]
     #[case::comment("comment: This is a user comment", CommentCommand::Comment("This is a user comment".into()))]
+    #[case::time_budget("time_budget: 1m30s", CommentCommand::TimeBudget(HumanDuration(Duration::from_secs(90))))]
+    #[case::auto_advance("auto_advance: 5s", CommentCommand::AutoAdvance(HumanDuration(Duration::from_secs(5))))]
     fn command_formatting(#[case] input: &str, #[case] expected: CommentCommand) {
         let parsed: CommentCommand = input.parse().expect("deserialization failed");
         assert_eq!(parsed, expected);
@@ -420,6 +441,33 @@ mod tests {
         assert_eq!(presentation.iter_slides().count(), 2);
     }
 
+    #[test]
+    fn time_budget() {
+        let input = "
+<!-- time_budget: 2m -->
+<!-- end_slide -->
+hi
+";
+        let presentation = Test::new(input).build();
+        let budgets: Vec<_> = presentation.iter_slides().map(|slide| slide.time_budget()).collect();
+        assert_eq!(budgets, &[Some(Duration::from_secs(120)), None]);
+    }
+
+    #[rstest]
+    #[case::disabled(None, &[None, None])]
+    #[case::enabled(Some(Duration::from_secs(10)), &[Some(Duration::from_secs(3)), Some(Duration::from_secs(10))])]
+    fn auto_advance(#[case] default: Option<Duration>, #[case] expected: &[Option<Duration>]) {
+        let input = "
+<!-- auto_advance: 3s -->
+<!-- end_slide -->
+hi
+";
+        let options = PresentationBuilderOptions { auto_advance: default, ..Default::default() };
+        let presentation = Test::new(input).options(options).build();
+        let delays: Vec<_> = presentation.iter_slides().map(|slide| slide.auto_advance()).collect();
+        assert_eq!(delays, expected);
+    }
+
     #[test]
     fn columns() {
         let input = "---
@@ -700,6 +748,20 @@ hi
         assert_eq!(lines, expected);
     }
 
+    #[test]
+    fn speaker_notes_kept_in_slide() {
+        let input = "
+<!-- speaker_note: hi -->
+text
+<!-- speaker_note: bye -->
+<!-- end_slide -->
+other
+";
+        let presentation = Test::new(input).build();
+        let notes: Vec<_> = presentation.iter_slides().map(|slide| slide.speaker_notes().to_vec()).collect();
+        assert_eq!(notes, &[vec!["hi".to_string(), "bye".to_string()], vec![]]);
+    }
+
     #[test]
     fn speaker_notes_pause() {
         let input = "
@@ -117,12 +117,27 @@ pub(crate) enum InvalidPresentation {
     #[error("snippet id '{0}' not defined")]
     UndefinedSnippetId(String),
 
-    #[error("snippet identifiers can only be used in +exec blocks")]
-    SnippetIdNonExec,
+    #[error("snippet '{0}' is not executable, only the output of +exec snippets can be displayed")]
+    SnippetNotExecutable(String),
+
+    #[error("snippet identifiers can only be used in plain or +exec blocks")]
+    SnippetIdNotAllowed,
+
+    #[error("snippet diffs can only be used in plain or +exec blocks")]
+    SnippetDiffNotAllowed,
 
     #[error("snippet id '{0}' already exists")]
     SnippetAlreadyExists(String),
 
+    #[error("snippet input can only be used in +exec blocks that don't use +pty or +acquire_terminal")]
+    SnippetStdinNotAllowed,
+
+    #[error("+interactive can only be used in +exec blocks that display their output")]
+    SnippetInteractiveNotAllowed,
+
+    #[error("+timeout can only be used in +exec and +exec_replace blocks that don't use +acquire_terminal")]
+    SnippetTimeoutNotAllowed,
+
     #[error("invalid env file format: expected <key>=<value>")]
     InvalidEnvFile,
 }
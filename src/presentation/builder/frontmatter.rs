@@ -14,6 +14,7 @@ use crate::{
     },
     render::operation::RenderOperation,
     theme::{AuthorPositioning, ElementType, PresentationTheme},
+    utils::HumanDuration,
 };
 use comrak::Arena;
 
@@ -42,6 +43,7 @@ impl PresentationBuilder<'_, '_> {
             footer_context.event.clone_from(&metadata.event);
             footer_context.date.clone_from(&metadata.date);
             footer_context.author.clone_from(&metadata.author);
+            footer_context.duration = metadata.duration.map(|d| d.0);
         }
 
         self.set_theme(&metadata.theme)?;
@@ -236,13 +238,26 @@ struct StrictPresentationMetadata {
 
     #[serde(default)]
     options: Option<OptionsConfig>,
+
+    #[serde(default)]
+    duration: Option<HumanDuration>,
 }
 
 impl From<StrictPresentationMetadata> for PresentationMetadata {
     fn from(strict: StrictPresentationMetadata) -> Self {
-        let StrictPresentationMetadata { title, sub_title, event, location, date, author, authors, theme, options } =
-            strict;
-        Self { title, sub_title, event, location, date, author, authors, theme, options }
+        let StrictPresentationMetadata {
+            title,
+            sub_title,
+            event,
+            location,
+            date,
+            author,
+            authors,
+            theme,
+            options,
+            duration,
+        } = strict;
+        Self { title, sub_title, event, location, date, author, authors, theme, options, duration }
     }
 }
 
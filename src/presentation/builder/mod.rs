@@ -2,7 +2,7 @@ use crate::{
     code::{
         execute::SnippetExecutor,
         highlighting::{HighlightThemeSet, SnippetHighlighter},
-        snippet::SnippetLanguage,
+        snippet::{Snippet, SnippetLanguage},
     },
     config::{KeyBindingsConfig, OptionsConfig},
     markdown::{
@@ -33,7 +33,8 @@ use crate::{
     ui::{
         execution::output::WrappedSnippetHandle,
         footer::{FooterGenerator, FooterVariables},
-        modals::{IndexBuilder, KeyBindingsModalBuilder},
+        modals::{IndexBuilder, KeyBindingsModalBuilder, OverviewBuilder, ThemePickerBuilder},
+        search::SearchStyles,
         separator::RenderSeparator,
     },
 };
@@ -44,6 +45,7 @@ use std::{
     path::Path,
     rc::Rc,
     sync::Arc,
+    time::Duration,
 };
 
 pub(crate) mod error;
@@ -85,6 +87,7 @@ pub struct PresentationBuilderOptions {
     pub strict_front_matter_parsing: bool,
     pub enable_snippet_execution: bool,
     pub enable_snippet_execution_replace: bool,
+    pub enable_interactive_snippets: bool,
     pub render_speaker_notes_only: bool,
     pub auto_render_languages: Vec<SnippetLanguage>,
     pub theme_options: ThemeOptions,
@@ -97,6 +100,7 @@ pub struct PresentationBuilderOptions {
     pub validate_snippets: bool,
     pub layout_grid: bool,
     pub h1_slide_titles: bool,
+    pub auto_advance: Option<Duration>,
 }
 
 impl PresentationBuilderOptions {
@@ -108,6 +112,9 @@ impl PresentationBuilderOptions {
         self.strict_front_matter_parsing =
             options.strict_front_matter_parsing.unwrap_or(self.strict_front_matter_parsing);
         self.h1_slide_titles = options.h1_slide_titles.unwrap_or(self.h1_slide_titles);
+        if let Some(delay) = options.auto_advance {
+            self.auto_advance = Some(delay.0);
+        }
         if let Some(prefix) = options.command_prefix {
             self.command_prefix = prefix;
         }
@@ -138,6 +145,7 @@ impl Default for PresentationBuilderOptions {
             strict_front_matter_parsing: true,
             enable_snippet_execution: false,
             enable_snippet_execution_replace: false,
+            enable_interactive_snippets: true,
             render_speaker_notes_only: false,
             auto_render_languages: Default::default(),
             theme_options: ThemeOptions { font_size_supported: false },
@@ -150,6 +158,7 @@ impl Default for PresentationBuilderOptions {
             validate_snippets: false,
             layout_grid: false,
             h1_slide_titles: false,
+            auto_advance: None,
         }
     }
 }
@@ -174,11 +183,13 @@ pub(crate) struct PresentationBuilder<'a, 'b> {
     footer_vars: FooterVariables,
     themes: &'a Themes,
     index_builder: IndexBuilder,
+    overview_builder: OverviewBuilder,
     image_registry: ImageRegistry,
     bindings_config: KeyBindingsConfig,
     slides_without_footer: HashSet<usize>,
     markdown_parser: &'a MarkdownParser<'b>,
     executable_snippets: HashMap<String, WrappedSnippetHandle>,
+    identified_snippets: HashMap<String, Snippet>,
     sources: MarkdownSources,
     options: PresentationBuilderOptions,
 }
@@ -214,12 +225,14 @@ impl<'a, 'b> PresentationBuilder<'a, 'b> {
             footer_vars: Default::default(),
             themes,
             index_builder: Default::default(),
+            overview_builder: Default::default(),
             image_registry,
             bindings_config,
             slides_without_footer: HashSet::new(),
             markdown_parser,
             sources: Default::default(),
             executable_snippets: Default::default(),
+            identified_snippets: Default::default(),
             options,
         })
     }
@@ -267,10 +280,12 @@ impl<'a, 'b> PresentationBuilder<'a, 'b> {
         }
 
         let mut bindings_modal_builder = KeyBindingsModalBuilder::default();
+        let mut theme_picker_builder = ThemePickerBuilder::default();
         if self.options.print_modal_background {
             let background = self.build_modal_background()?;
             self.index_builder.set_background(background.clone());
-            bindings_modal_builder.set_background(background);
+            bindings_modal_builder.set_background(background.clone());
+            theme_picker_builder.set_background(background);
         };
 
         let mut slides = Vec::new();
@@ -285,8 +300,22 @@ impl<'a, 'b> PresentationBuilder<'a, 'b> {
         }
 
         let bindings = bindings_modal_builder.build(&self.theme, &self.bindings_config);
-        let slide_index = self.index_builder.build(&self.theme, self.presentation_state.clone());
-        let modals = Modals { slide_index, bindings };
+        let (slide_index, slide_index_search) =
+            self.index_builder.build(&self.theme, self.presentation_state.clone(), &slides);
+        let search_styles =
+            SearchStyles { prompt: self.theme.modals.style, highlight: self.theme.modals.selection_style };
+        let overview = self.overview_builder.build(&self.theme);
+        let theme_picker = theme_picker_builder.build(&self.theme, self.themes.presentation.theme_names());
+        let annotation_style = self.theme.modals.selection_style;
+        let modals = Modals {
+            slide_index,
+            slide_index_search,
+            bindings,
+            search_styles,
+            overview,
+            theme_picker,
+            annotation_style,
+        };
         let presentation = Presentation::new(slides, modals, self.presentation_state);
         Ok(presentation)
     }
@@ -525,9 +554,14 @@ impl<'a, 'b> PresentationBuilder<'a, 'b> {
         let chunks = mem::take(&mut self.slide_chunks);
 
         if !self.slide_state.skip_slide {
-            let builder = SlideBuilder::default().chunks(chunks);
-            self.index_builder
-                .add_title(self.slide_state.title.take().unwrap_or_else(|| Text::from("<no title>").into()));
+            let builder = SlideBuilder::default()
+                .chunks(chunks)
+                .time_budget(self.slide_state.time_budget)
+                .auto_advance(self.options.auto_advance.map(|delay| self.slide_state.auto_advance.unwrap_or(delay)))
+                .speaker_notes(mem::take(&mut self.slide_state.speaker_notes));
+            let title = self.slide_state.title.take().unwrap_or_else(|| Text::from("<no title>").into());
+            self.overview_builder.add_title(&title);
+            self.index_builder.add_title(title);
 
             if self.slide_state.ignore_footer {
                 self.slides_without_footer.insert(self.slide_builders.len());
@@ -564,7 +598,12 @@ impl<'a, 'b> PresentationBuilder<'a, 'b> {
     }
 
     fn generate_footer(&self) -> Result<Vec<RenderOperation>, BuildError> {
-        let generator = FooterGenerator::new(self.theme.footer.clone(), &self.footer_vars, &self.theme.palette)?;
+        let generator = FooterGenerator::new(
+            self.theme.footer.clone(),
+            &self.footer_vars,
+            &self.theme.palette,
+            self.presentation_state.clone(),
+        )?;
         Ok(vec![
             // Exit any layout we're in so this gets rendered on a default screen size.
             RenderOperation::ExitLayout,
@@ -608,6 +647,9 @@ struct SlideState {
     alignment: Option<Alignment>,
     skip_slide: bool,
     last_layout_comment: Option<FileSourcePosition>,
+    time_budget: Option<Duration>,
+    auto_advance: Option<Duration>,
+    speaker_notes: Vec<String>,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -2,13 +2,14 @@ use super::{BuildError, BuildResult};
 use crate::{
     code::{
         execute::LanguageSnippetExecutor,
+        region::{extract_region, extract_symbol},
         snippet::{
-            ExternalFile, Highlight, HighlightContext, HighlightGroup, HighlightMutator, HighlightedLine, PtyArgs,
-            Snippet, SnippetExecArgs, SnippetExecution, SnippetExecutorSpec, SnippetLanguage, SnippetLine,
-            SnippetParser, SnippetRepr, SnippetSplitter,
+            ExternalFile, Highlight, HighlightContext, HighlightGroup, HighlightMutator, HighlightedLine, LineChange,
+            PtyArgs, Snippet, SnippetExecArgs, SnippetExecution, SnippetExecutorSpec, SnippetLanguage, SnippetLine,
+            SnippetParser, SnippetRepr, SnippetSplitter, SnippetStdin,
         },
     },
-    markdown::elements::SourcePosition,
+    markdown::{elements::SourcePosition, text_style::TextStyle},
     presentation::builder::{PresentationBuilder, error::InvalidPresentation},
     render::{
         operation::{AsRenderOperations, RenderAsyncStartPolicy, RenderOperation},
@@ -40,14 +41,58 @@ impl PresentationBuilder<'_, '_> {
         if self.options.auto_render_languages.contains(&snippet.language) {
             snippet.attributes.execution = SnippetExecution::Render;
         }
-        // Ids can only be used in `+exec` snippets.
-        if snippet.attributes.id.is_some()
-            && !matches!(
-                snippet.attributes.execution,
-                SnippetExecution::Exec(SnippetExecArgs { repr: SnippetRepr::SnippetOutput, .. })
-            )
-        {
-            return Err(self.invalid_presentation(source_position, InvalidPresentation::SnippetIdNonExec));
+        // Ids and diffs can only be used in snippets that are displayed as they are.
+        let is_plain_or_exec = matches!(
+            snippet.attributes.execution,
+            SnippetExecution::None | SnippetExecution::Exec(SnippetExecArgs { repr: SnippetRepr::SnippetOutput, .. })
+        );
+        if !is_plain_or_exec {
+            if snippet.attributes.id.is_some() {
+                return Err(self.invalid_presentation(source_position, InvalidPresentation::SnippetIdNotAllowed));
+            }
+            if snippet.attributes.diff_from.is_some() {
+                return Err(self.invalid_presentation(source_position, InvalidPresentation::SnippetDiffNotAllowed));
+            }
+        }
+        // Input can only be fed to processes whose output we're consuming ourselves.
+        let has_stdin = snippet.attributes.stdin.is_some() || snippet.attributes.interactive;
+        let reads_stdin = matches!(
+            &snippet.attributes.execution,
+            SnippetExecution::Exec(args) if args.pty.is_none() && args.repr != SnippetRepr::AcquireTerminal
+        );
+        if has_stdin && !reads_stdin {
+            return Err(self.invalid_presentation(source_position, InvalidPresentation::SnippetStdinNotAllowed));
+        }
+        // Snippets that take over the terminal run until they exit so they can't be timed out.
+        let timed = matches!(
+            &snippet.attributes.execution,
+            SnippetExecution::Exec(args) if args.repr != SnippetRepr::AcquireTerminal
+        );
+        if snippet.attributes.timeout.is_some() && !timed {
+            return Err(self.invalid_presentation(source_position, InvalidPresentation::SnippetTimeoutNotAllowed));
+        }
+        // Input is typed in a prompt that's only available while the snippet's output is being displayed.
+        if snippet.attributes.interactive {
+            if !matches!(&snippet.attributes.execution, SnippetExecution::Exec(args) if args.repr == SnippetRepr::SnippetOutput)
+            {
+                return Err(
+                    self.invalid_presentation(source_position, InvalidPresentation::SnippetInteractiveNotAllowed)
+                );
+            }
+            if !self.options.enable_interactive_snippets {
+                snippet.attributes.interactive = false;
+            }
+        }
+        if let Some(id) = &snippet.attributes.diff_from {
+            if !self.identified_snippets.contains_key(id) {
+                let error = InvalidPresentation::UndefinedSnippetId(id.clone());
+                return Err(self.invalid_presentation(source_position, error));
+            }
+        }
+        if let Some(id) = snippet.attributes.id.clone() {
+            if self.identified_snippets.insert(id.clone(), snippet.clone()).is_some() {
+                return Err(self.invalid_presentation(source_position, InvalidPresentation::SnippetAlreadyExists(id)));
+            }
         }
 
         self.push_differ(snippet.contents.clone());
@@ -93,7 +138,13 @@ impl PresentationBuilder<'_, '_> {
                     Some(path) => self.load_env(path, source_position)?,
                     None => HashMap::new(),
                 };
-                let executor = self.snippet_executor.language_executor(&snippet.language, &args.spec, env)?;
+                let mut executor = self.snippet_executor.language_executor(&snippet.language, &args.spec, env)?;
+                if let Some(stdin) = &snippet.attributes.stdin {
+                    executor.set_stdin(self.load_stdin(stdin, source_position)?);
+                }
+                if let Some(timeout) = snippet.attributes.timeout {
+                    executor.set_timeout(timeout);
+                }
                 match args.repr {
                     SnippetRepr::Image => {
                         self.push_code_as_image(snippet, executor)?;
@@ -170,8 +221,13 @@ impl PresentationBuilder<'_, '_> {
     }
 
     fn push_code_lines(&mut self, snippet: &Snippet) -> u16 {
-        let lines = SnippetSplitter::new(&self.theme.code, self.snippet_executor.hidden_line_prefix(&snippet.language))
-            .split(snippet);
+        let splitter =
+            SnippetSplitter::new(&self.theme.code, self.snippet_executor.hidden_line_prefix(&snippet.language));
+        let source = snippet.attributes.diff_from.as_ref().and_then(|id| self.identified_snippets.get(id));
+        let lines = match source {
+            Some(source) => splitter.split_diff(source, snippet),
+            None => splitter.split(snippet),
+        };
         let block_length = lines.iter().map(|line| line.width()).max().unwrap_or(0) * self.slide_font_size() as usize;
         let block_length = block_length as u16;
         let (lines, context) = self.highlight_lines(snippet, lines, block_length);
@@ -232,8 +288,28 @@ impl PresentationBuilder<'_, '_> {
                 InvalidPresentation::Snippet(format!("failed to load snippet {path:?}: {e}")),
             )
         })?;
+        let has_lines = file.start_line.is_some() || file.end_line.is_some();
+        let contents = match (file.region, file.symbol) {
+            (None, None) => Ok(Self::filter_lines(contents, file.start_line, file.end_line)),
+            (Some(region), None) if !has_lines => extract_region(&contents, &region),
+            (None, Some(symbol)) if !has_lines => extract_symbol(&contents, &file.language, &symbol),
+            _ => {
+                return Err(self.invalid_presentation(
+                    source_position,
+                    InvalidPresentation::Snippet(
+                        "only one of 'start_line'/'end_line', 'region', and 'symbol' can be used".into(),
+                    ),
+                ));
+            }
+        }
+        .map_err(|e| {
+            self.invalid_presentation(
+                source_position,
+                InvalidPresentation::Snippet(format!("failed to load snippet {path:?}: {e}")),
+            )
+        })?;
         code.language = file.language;
-        code.contents = Self::filter_lines(contents, file.start_line, file.end_line);
+        code.contents = contents;
         Ok(code)
     }
 
@@ -290,8 +366,25 @@ impl PresentationBuilder<'_, '_> {
 
         let mut output = Vec::new();
         for line in lines.into_iter() {
+            let diff_colors = line.change.map(|change| match change {
+                LineChange::Added => style.diff.added,
+                LineChange::Removed => style.diff.removed,
+            });
+            let (highlighted, dim_style) = match diff_colors {
+                // Lines that changed use the diff colors instead of the snippet's background.
+                Some(colors) => {
+                    let block_style = CodeBlockStyle { background: false, ..style.clone() };
+                    let line_style = TextStyle::colored(colors).size(font_size);
+                    let mut dim_style = dim_style;
+                    dim_style.colors.background = colors.background;
+                    dim_style.colors.foreground = colors.foreground.or(dim_style.colors.foreground);
+                    (line.highlight(&mut code_highlighter, &block_style, &line_style), dim_style)
+                }
+                None => {
+                    (line.highlight(&mut code_highlighter, &style, &TextStyle::default().size(font_size)), dim_style)
+                }
+            };
             let prefix = line.dim_prefix(&dim_style);
-            let highlighted = line.highlight(&mut code_highlighter, &style, font_size);
             let not_highlighted = line.dim(&dim_style);
             let line_number = line.line_number;
             let context = context.clone();
@@ -413,6 +506,31 @@ impl PresentationBuilder<'_, '_> {
         self.chunk_operations.push(RenderOperation::RenderAsync(Rc::new(operation)));
     }
 
+    fn load_stdin(&self, stdin: &SnippetStdin, source_position: SourcePosition) -> Result<String, BuildError> {
+        match stdin {
+            SnippetStdin::File(path) => {
+                let base = self.resource_base_path();
+                self.resources.external_text_file(path, &base).map_err(|e| {
+                    self.invalid_presentation(
+                        source_position,
+                        InvalidPresentation::Snippet(format!("failed to load snippet input {path:?}: {e}")),
+                    )
+                })
+            }
+            SnippetStdin::Snippet(id) => {
+                let Some(snippet) = self.identified_snippets.get(id) else {
+                    let error = InvalidPresentation::UndefinedSnippetId(id.clone());
+                    return Err(self.invalid_presentation(source_position, error));
+                };
+                let mut contents = snippet.contents.clone();
+                if !contents.ends_with('\n') {
+                    contents.push('\n');
+                }
+                Ok(contents)
+            }
+        }
+    }
+
     fn load_env(&self, path: &Path, source_position: SourcePosition) -> Result<HashMap<String, String>, BuildError> {
         let base = self.resource_base_path();
         let contents = self.resources.external_text_file(path, &base).map_err(|e| {
@@ -457,11 +575,15 @@ mod tests {
     use super::*;
     use crate::{
         markdown::text_style::Color,
-        presentation::builder::utils::{RunAsyncRendersPolicy, Test},
+        presentation::builder::{
+            PresentationBuilderOptions,
+            utils::{RunAsyncRendersPolicy, Test},
+        },
         theme::raw,
     };
     use rstest::rstest;
     use std::fs;
+    use tempfile::tempdir;
 
     #[rstest]
     #[case::no_filters(None, None, &["a", "b", "c", "d", "e"])]
@@ -513,6 +635,49 @@ language: bash
         assert_eq!(lines, expected);
     }
 
+    #[test]
+    fn external_snippet_region() {
+        let temp = tempfile::NamedTempFile::new().expect("failed to create tempfile");
+        let path = temp.path();
+        fs::write(path, "echo bye\n# region: greet\necho hi\n# endregion").unwrap();
+
+        let path = path.to_string_lossy();
+        let input = format!(
+            "
+```file
+path: {path}
+language: bash
+region: greet
+```
+"
+        );
+        let lines = Test::new(input).render().rows(3).columns(7).into_lines();
+        let expected = &["       ", "echo hi", "       "];
+        assert_eq!(lines, expected);
+    }
+
+    #[rstest]
+    #[case::missing_region("region: greet")]
+    #[case::region_and_lines("region: greet\nstart_line: 1")]
+    #[case::region_and_symbol("region: greet\nsymbol: greet")]
+    fn invalid_external_snippet(#[case] selector: &str) {
+        let temp = tempfile::NamedTempFile::new().expect("failed to create tempfile");
+        let path = temp.path();
+        fs::write(path, "echo hi").unwrap();
+
+        let path = path.to_string_lossy();
+        let input = format!(
+            "
+```file
+path: {path}
+language: bash
+{selector}
+```
+"
+        );
+        Test::new(input).expect_invalid();
+    }
+
     #[test]
     fn line_numbers() {
         let input = "
@@ -850,6 +1015,52 @@ bar
         assert_eq!(lines, expected);
     }
 
+    #[rstest]
+    #[case::undefined("<!-- snippet_output: foo -->", "not defined")]
+    #[case::not_executable("```bash +id:foo\necho hi\n```\n<!-- snippet_output: foo -->", "not executable")]
+    fn invalid_detached_output(#[case] input: &str, #[case] expected: &str) {
+        // the error context is read from the presentation file so it needs to exist
+        let dir = tempdir().expect("failed to create tempdir");
+        fs::write(dir.path().join("presentation.md"), input).expect("failed to write presentation");
+
+        let error = Test::new(input).resources_path(dir.path()).expect_invalid();
+        assert!(error.to_string().contains(expected), "unexpected error: {error}");
+    }
+
+    #[test]
+    fn diff_from() {
+        let input = "
+```bash +id:foo
+echo hi
+echo bye
+```
+
+```bash +diff_from:foo
+echo hi
+echo hello
+```";
+        let lines = Test::new(input).render().rows(9).columns(14).into_lines();
+        let expected = &[
+            "              ",
+            "echo hi       ",
+            "echo bye      ",
+            "              ",
+            "  echo hi     ",
+            "- echo bye    ",
+            "+ echo hello  ",
+            "              ",
+            "              ",
+        ];
+        assert_eq!(lines, expected);
+    }
+
+    #[rstest]
+    #[case::undefined("```bash +diff_from:foo\necho hi\n```")]
+    #[case::render("```bash +id:foo\necho hi\n```\n```mermaid +render +diff_from:foo\nA\n```")]
+    fn invalid_diff_from(#[case] input: &str) {
+        Test::new(input).expect_invalid();
+    }
+
     #[test]
     fn exec_replace() {
         let input = "
@@ -943,4 +1154,95 @@ echo \"$TAR\"
         let expected = &["     ", "42   ", "1337 ", "hi   ", "     "];
         assert_eq!(lines, expected);
     }
+
+    #[test]
+    fn stdin_file() {
+        let temp = tempfile::NamedTempFile::new().expect("failed to create tempfile");
+        let path = temp.path();
+        fs::write(path, "bob\n").unwrap();
+
+        let path = path.to_string_lossy();
+        let input = format!(
+            "
+```bash +exec_replace +stdin:{path}
+read name
+echo \"hi $name\"
+```
+"
+        );
+        let lines = Test::new(input).render().rows(3).columns(6).into_lines();
+        let expected = &["      ", "hi bob", "      "];
+        assert_eq!(lines, expected);
+    }
+
+    #[test]
+    fn stdin_from() {
+        let input = "
+```text +id:input
+b
+a
+```
+```bash +exec_replace +stdin_from:input
+sort
+```
+";
+        let lines = Test::new(input).render().rows(7).columns(3).into_lines();
+        let expected = &["   ", "b  ", "a  ", "   ", "a  ", "b  ", "   "];
+        assert_eq!(lines, expected);
+    }
+
+    #[rstest]
+    #[case::not_executed("```bash +stdin:input.txt\necho hi\n```")]
+    #[case::pty("```bash +exec +pty +interactive\necho hi\n```")]
+    #[case::acquire_terminal("```bash +acquire_terminal +stdin_from:foo\necho hi\n```")]
+    #[case::undefined_id("```bash +exec +stdin_from:foo\necho hi\n```")]
+    fn invalid_stdin(#[case] input: &str) {
+        Test::new(input).expect_invalid();
+    }
+
+    #[rstest]
+    #[case::exec_replace("```bash +exec_replace +interactive\necho hi\n```")]
+    #[case::image("```bash +exec +image +interactive\necho hi\n```")]
+    fn invalid_interactive(#[case] input: &str) {
+        Test::new(input).expect_invalid();
+    }
+
+    #[rstest]
+    #[case::not_executed("```bash +timeout:1s\necho hi\n```")]
+    #[case::render("```latex +render +timeout:1s\nhi\n```")]
+    #[case::acquire_terminal("```bash +acquire_terminal +timeout:1s\necho hi\n```")]
+    fn invalid_timeout(#[case] input: &str) {
+        Test::new(input).expect_invalid();
+    }
+
+    #[test]
+    fn interactive_disabled() {
+        let input = "
+```bash +auto_exec +interactive
+read line
+echo done
+```";
+        let options = PresentationBuilderOptions {
+            enable_snippet_execution: true,
+            enable_interactive_snippets: false,
+            ..Default::default()
+        };
+        let lines = Test::new(input)
+            .options(options)
+            .render()
+            .rows(7)
+            .columns(19)
+            .run_async_renders(RunAsyncRendersPolicy::OnlyAutomatic)
+            .into_lines();
+        let expected = &[
+            "                   ",
+            "read line          ",
+            "echo done          ",
+            "                   ",
+            "——— [finished] ————",
+            "                   ",
+            "done               ",
+        ];
+        assert_eq!(lines, expected);
+    }
 }
@@ -0,0 +1,337 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fmt,
+    time::{Duration, Instant},
+};
+
+/// Keeps track of how long a presentation has been running for.
+#[derive(Debug)]
+pub(crate) struct PresentationClock {
+    started_at: Instant,
+    last_tick: u64,
+}
+
+impl PresentationClock {
+    /// Start the clock.
+    pub(crate) fn start() -> Self {
+        Self { started_at: Instant::now(), last_tick: 0 }
+    }
+
+    /// Get the time elapsed since the clock was started.
+    pub(crate) fn elapsed(&self) -> Duration {
+        self.started_at.elapsed()
+    }
+
+    /// Check whether a second boundary was crossed since the last time this was called.
+    pub(crate) fn tick(&mut self) -> bool {
+        let seconds = self.elapsed().as_secs();
+        if seconds == self.last_tick {
+            false
+        } else {
+            self.last_tick = seconds;
+            true
+        }
+    }
+}
+
+/// Keeps track of how much time is spent on each slide.
+#[derive(Debug, Default)]
+pub(crate) struct SlideTimeTracker {
+    spent: HashMap<usize, Duration>,
+    current: Option<(usize, Instant)>,
+}
+
+impl SlideTimeTracker {
+    /// Mark the given slide as the one being currently displayed.
+    pub(crate) fn visit(&mut self, slide: usize) {
+        match self.current {
+            Some((current, _)) if current == slide => (),
+            _ => {
+                self.flush();
+                self.current = Some((slide, Instant::now()));
+            }
+        }
+    }
+
+    /// Get the total time spent on a slide.
+    pub(crate) fn spent(&self, slide: usize) -> Duration {
+        let spent = self.spent.get(&slide).copied().unwrap_or_default();
+        match self.current {
+            Some((current, since)) if current == slide => spent + since.elapsed(),
+            _ => spent,
+        }
+    }
+
+    /// Compute the pacing for a presentation given the time budget of each slide.
+    ///
+    /// The time spent on all slides that have a budget is compared against the cumulative budget of all slides up to
+    /// the current one, plus any slide past it that was already visited so going back doesn't make the presentation
+    /// look like it's running behind. Slides that don't have a budget are not taken into account. This returns `None`
+    /// if no slide has a budget.
+    pub(crate) fn pacing<I>(&self, budgets: I, current_slide: usize) -> Option<PacingStatus>
+    where
+        I: IntoIterator<Item = Option<Duration>>,
+    {
+        let mut any_budget = false;
+        let mut status = PacingStatus {
+            slide_elapsed: self.spent(current_slide),
+            slide_budget: None,
+            elapsed: Duration::ZERO,
+            budget: Duration::ZERO,
+            behind: false,
+        };
+        for (index, budget) in budgets.into_iter().enumerate() {
+            let Some(budget) = budget else {
+                continue;
+            };
+            any_budget = true;
+            status.elapsed += self.spent(index);
+            if index <= current_slide || !self.spent(index).is_zero() {
+                status.budget += budget;
+            }
+            if index == current_slide {
+                status.slide_budget = Some(budget);
+            }
+        }
+        status.behind = status.elapsed > status.budget;
+        any_budget.then_some(status)
+    }
+
+    fn flush(&mut self) {
+        if let Some((slide, since)) = self.current.take() {
+            *self.spent.entry(slide).or_default() += since.elapsed();
+        }
+    }
+}
+
+/// Decides when a presentation should automatically move forward.
+///
+/// The delay starts over every time the current slide or chunk changes, regardless of what caused it. Interacting with
+/// the presentation pauses it until nothing has happened for a while.
+#[derive(Debug)]
+pub(crate) struct AutoAdvanceTimer {
+    resume_after: Duration,
+    position: (usize, usize),
+    since: Instant,
+    interrupted_at: Option<Instant>,
+}
+
+impl AutoAdvanceTimer {
+    /// Construct a timer that resumes after the given amount of time without interactions.
+    pub(crate) fn new(resume_after: Duration) -> Self {
+        Self { resume_after, position: (0, 0), since: Instant::now(), interrupted_at: None }
+    }
+
+    /// Mark the given slide and chunk as the ones being currently displayed.
+    pub(crate) fn visit(&mut self, slide: usize, chunk: usize) {
+        if self.position != (slide, chunk) {
+            self.position = (slide, chunk);
+            self.since = Instant::now();
+        }
+    }
+
+    /// Pause because someone interacted with the presentation.
+    pub(crate) fn interrupt(&mut self) {
+        self.interrupted_at = Some(Instant::now());
+    }
+
+    /// Check whether the current step has been displayed for at least the given delay.
+    ///
+    /// This restarts the delay every time it returns true.
+    pub(crate) fn is_due(&mut self, delay: Duration) -> bool {
+        if let Some(interrupted_at) = self.interrupted_at {
+            if interrupted_at.elapsed() < self.resume_after {
+                return false;
+            }
+            // Give the current step its full delay after resuming.
+            self.interrupted_at = None;
+            self.since = Instant::now();
+        }
+        if self.since.elapsed() < delay {
+            return false;
+        }
+        self.since = Instant::now();
+        true
+    }
+}
+
+/// The pacing of a presentation with respect to its slides' time budgets.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct PacingStatus {
+    /// The time spent on the current slide.
+    pub(crate) slide_elapsed: Duration,
+
+    /// The time budget for the current slide, if any.
+    pub(crate) slide_budget: Option<Duration>,
+
+    /// The time spent on slides that have a time budget.
+    pub(crate) elapsed: Duration,
+
+    /// The cumulative time budget of all slides up to the current one.
+    pub(crate) budget: Duration,
+
+    /// Whether the presentation is running behind its cumulative time budget.
+    pub(crate) behind: bool,
+}
+
+/// A duration formatted as a timer, like `05:12` or `1:05:12`.
+pub(crate) struct TimerDisplay(pub(crate) Duration);
+
+impl fmt::Display for TimerDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let seconds = self.0.as_secs();
+        let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+        if hours > 0 { write!(f, "{hours}:{minutes:02}:{seconds:02}") } else { write!(f, "{minutes:02}:{seconds:02}") }
+    }
+}
+
+/// The time left until a target duration is reached.
+///
+/// This is displayed with a leading `-` sign once the target duration is exceeded.
+pub(crate) struct RemainingTimeDisplay {
+    pub(crate) target: Duration,
+    pub(crate) elapsed: Duration,
+}
+
+impl fmt::Display for RemainingTimeDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.target.checked_sub(self.elapsed) {
+            Some(remaining) => write!(f, "{}", TimerDisplay(remaining)),
+            None => write!(f, "-{}", TimerDisplay(self.elapsed - self.target)),
+        }
+    }
+}
+
+/// A local time of day.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct TimeOfDay {
+    pub(crate) hours: u8,
+    pub(crate) minutes: u8,
+}
+
+impl TimeOfDay {
+    /// Get the current local time of day.
+    #[cfg(unix)]
+    pub(crate) fn now() -> Self {
+        let mut now: libc::time_t = 0;
+        // SAFETY: both pointers are valid for the duration of the calls.
+        unsafe {
+            libc::time(&mut now);
+            let mut local: libc::tm = std::mem::zeroed();
+            if libc::localtime_r(&now, &mut local).is_null() {
+                return Self::from_unix_time(now as u64);
+            }
+            Self { hours: local.tm_hour as u8, minutes: local.tm_min as u8 }
+        }
+    }
+
+    /// Get the current time of day in UTC.
+    #[cfg(not(unix))]
+    pub(crate) fn now() -> Self {
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default();
+        Self::from_unix_time(now.as_secs())
+    }
+
+    fn from_unix_time(seconds: u64) -> Self {
+        let seconds = seconds % 86400;
+        Self { hours: (seconds / 3600) as u8, minutes: (seconds / 60 % 60) as u8 }
+    }
+}
+
+impl fmt::Display for TimeOfDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.hours, self.minutes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::zero(0, "00:00")]
+    #[case::seconds(42, "00:42")]
+    #[case::minutes(125, "02:05")]
+    #[case::hours(3725, "1:02:05")]
+    fn timer_display(#[case] seconds: u64, #[case] expected: &str) {
+        assert_eq!(TimerDisplay(Duration::from_secs(seconds)).to_string(), expected);
+    }
+
+    #[rstest]
+    #[case::on_time(600, 125, "07:55")]
+    #[case::exact(600, 600, "00:00")]
+    #[case::overtime(600, 725, "-02:05")]
+    fn remaining_time_display(#[case] target: u64, #[case] elapsed: u64, #[case] expected: &str) {
+        let display =
+            RemainingTimeDisplay { target: Duration::from_secs(target), elapsed: Duration::from_secs(elapsed) };
+        assert_eq!(display.to_string(), expected);
+    }
+
+    #[test]
+    fn slide_time_tracking() {
+        let mut tracker = SlideTimeTracker::default();
+        tracker.spent.insert(0, Duration::from_secs(50));
+        tracker.spent.insert(1, Duration::from_secs(20));
+        tracker.visit(0);
+        tracker.visit(1);
+        assert!(tracker.spent(0) >= Duration::from_secs(50));
+        assert!(tracker.spent(1) >= Duration::from_secs(20));
+        assert_eq!(tracker.spent(2), Duration::ZERO);
+    }
+
+    #[rstest]
+    #[case::on_track(&[Some(60), Some(60)], &[50, 20], 1, false)]
+    #[case::behind(&[Some(60), Some(60)], &[100, 30], 1, true)]
+    #[case::behind_previous(&[Some(60), Some(60)], &[70, 0], 0, true)]
+    #[case::ignores_unbudgeted(&[Some(60), None, Some(60)], &[50, 500, 10], 2, false)]
+    #[case::navigated_back(&[Some(60), Some(60), Some(60)], &[50, 55, 58], 0, false)]
+    #[case::navigated_back_behind(&[Some(60), Some(60), Some(60)], &[50, 55, 90], 0, true)]
+    fn pacing(#[case] budgets: &[Option<u64>], #[case] spent: &[u64], #[case] current: usize, #[case] behind: bool) {
+        let spent = spent.iter().enumerate().map(|(index, spent)| (index, Duration::from_secs(*spent))).collect();
+        let tracker = SlideTimeTracker { spent, ..Default::default() };
+        let budgets = budgets.iter().map(|budget| budget.map(Duration::from_secs));
+        let status = tracker.pacing(budgets, current).expect("no pacing");
+        assert_eq!(status.behind, behind);
+    }
+
+    #[test]
+    fn pacing_after_navigating_back() {
+        let mut tracker = SlideTimeTracker::default();
+        tracker.spent.insert(0, Duration::from_secs(50));
+        tracker.spent.insert(1, Duration::from_secs(50));
+        tracker.visit(0);
+        let status = tracker.pacing([Some(Duration::from_secs(60)); 3], 0).expect("no pacing");
+        assert_eq!(status.budget, Duration::from_secs(120));
+        assert!(!status.behind);
+    }
+
+    #[test]
+    fn pacing_without_budgets() {
+        let tracker = SlideTimeTracker::default();
+        assert_eq!(tracker.pacing([None, None], 0), None);
+    }
+
+    #[test]
+    fn auto_advance_delay() {
+        let mut timer = AutoAdvanceTimer::new(Duration::from_secs(60));
+        assert!(timer.is_due(Duration::ZERO));
+        assert!(!timer.is_due(Duration::from_secs(60)));
+    }
+
+    #[rstest]
+    #[case::paused(Duration::from_secs(60), false)]
+    #[case::resumed(Duration::ZERO, true)]
+    fn auto_advance_interrupt(#[case] resume_after: Duration, #[case] due: bool) {
+        let mut timer = AutoAdvanceTimer::new(resume_after);
+        timer.interrupt();
+        assert_eq!(timer.is_due(Duration::ZERO), due);
+    }
+
+    #[test]
+    fn time_of_day_from_unix_time() {
+        let time = TimeOfDay::from_unix_time(86400 * 3 + 13 * 3600 + 7 * 60 + 59);
+        assert_eq!(time.to_string(), "13:07");
+    }
+}
@@ -1,4 +1,14 @@
-use crate::{config::OptionsConfig, render::operation::RenderOperation};
+use crate::{
+    config::OptionsConfig,
+    markdown::text_style::TextStyle,
+    render::operation::RenderOperation,
+    ui::{
+        modals::{SlideIndex, SlideOverview, ThemePicker},
+        search::{SearchStyles, SlideIndexSearch},
+    },
+    utils::HumanDuration,
+};
+use clock::PacingStatus;
 use serde::Deserialize;
 use std::{
     cell::RefCell,
@@ -6,16 +16,24 @@ use std::{
     ops::Deref,
     rc::Rc,
     sync::{Arc, Mutex},
+    time::Duration,
 };
 
 pub(crate) mod builder;
+pub(crate) mod clock;
 pub(crate) mod diff;
 pub(crate) mod poller;
+pub(crate) mod search;
 
 #[derive(Debug)]
 pub(crate) struct Modals {
-    pub(crate) slide_index: Vec<RenderOperation>,
+    pub(crate) slide_index: SlideIndex,
+    pub(crate) slide_index_search: SlideIndexSearch,
     pub(crate) bindings: Vec<RenderOperation>,
+    pub(crate) search_styles: SearchStyles,
+    pub(crate) overview: SlideOverview,
+    pub(crate) theme_picker: ThemePicker,
+    pub(crate) annotation_style: TextStyle,
 }
 
 /// A presentation.
@@ -44,7 +62,42 @@ impl Presentation {
 
     /// Iterate the operations that render the slide index.
     pub(crate) fn iter_slide_index_operations(&self) -> impl Iterator<Item = &RenderOperation> {
-        self.modals.slide_index.iter()
+        self.modals.slide_index.operations().iter()
+    }
+
+    /// The slide index modal.
+    pub(crate) fn slide_index(&self) -> &SlideIndex {
+        &self.modals.slide_index
+    }
+
+    /// The search over the slides in the slide index.
+    pub(crate) fn slide_index_search(&self) -> &SlideIndexSearch {
+        &self.modals.slide_index_search
+    }
+
+    /// The styles used when searching through this presentation.
+    pub(crate) fn search_styles(&self) -> &SearchStyles {
+        &self.modals.search_styles
+    }
+
+    /// The overview that shows every slide as a thumbnail.
+    pub(crate) fn overview(&self) -> &SlideOverview {
+        &self.modals.overview
+    }
+
+    /// The theme picker modal.
+    pub(crate) fn theme_picker(&self) -> &ThemePicker {
+        &self.modals.theme_picker
+    }
+
+    /// Iterate the operations that render the theme picker.
+    pub(crate) fn iter_theme_picker_operations(&self) -> impl Iterator<Item = &RenderOperation> {
+        self.modals.theme_picker.operations().iter()
+    }
+
+    /// The style used to draw annotations on top of slides.
+    pub(crate) fn annotation_style(&self) -> TextStyle {
+        self.modals.annotation_style
     }
 
     /// Iterate the operations that render the key bindings modal.
@@ -167,7 +220,15 @@ impl Presentation {
 
 impl From<Vec<Slide>> for Presentation {
     fn from(slides: Vec<Slide>) -> Self {
-        let modals = Modals { slide_index: vec![], bindings: vec![] };
+        let modals = Modals {
+            slide_index: Default::default(),
+            slide_index_search: Default::default(),
+            bindings: vec![],
+            search_styles: Default::default(),
+            overview: Default::default(),
+            theme_picker: Default::default(),
+            annotation_style: Default::default(),
+        };
         Self::new(slides, modals, Default::default())
     }
 }
@@ -184,6 +245,9 @@ pub(crate) type AsyncPresentationErrorHolder = Arc<Mutex<Option<AsyncPresentatio
 pub(crate) struct PresentationStateInner {
     current_slide_index: usize,
     async_error_holder: AsyncPresentationErrorHolder,
+    elapsed_time: Duration,
+    pacing: Option<PacingStatus>,
+    time_dependent: bool,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -203,6 +267,33 @@ impl PresentationState {
     fn set_current_slide_index(&self, value: usize) {
         self.inner.deref().borrow_mut().current_slide_index = value;
     }
+
+    /// The time elapsed since the presentation started.
+    pub(crate) fn elapsed_time(&self) -> Duration {
+        self.inner.deref().borrow().elapsed_time
+    }
+
+    pub(crate) fn set_elapsed_time(&self, value: Duration) {
+        self.inner.deref().borrow_mut().elapsed_time = value;
+    }
+
+    /// The pacing of this presentation, if its slides have time budgets.
+    pub(crate) fn pacing(&self) -> Option<PacingStatus> {
+        self.inner.deref().borrow().pacing
+    }
+
+    pub(crate) fn set_pacing(&self, value: Option<PacingStatus>) {
+        self.inner.deref().borrow_mut().pacing = value;
+    }
+
+    /// Whether anything in this presentation needs to be re-rendered as time goes by.
+    pub(crate) fn is_time_dependent(&self) -> bool {
+        self.inner.deref().borrow().time_dependent
+    }
+
+    pub(crate) fn mark_time_dependent(&self) {
+        self.inner.deref().borrow_mut().time_dependent = true;
+    }
 }
 
 /// A slide builder.
@@ -210,6 +301,9 @@ impl PresentationState {
 pub(crate) struct SlideBuilder {
     chunks: Vec<SlideChunk>,
     footer: Vec<RenderOperation>,
+    time_budget: Option<Duration>,
+    auto_advance: Option<Duration>,
+    speaker_notes: Vec<String>,
 }
 
 impl SlideBuilder {
@@ -223,8 +317,27 @@ impl SlideBuilder {
         self
     }
 
+    pub(crate) fn time_budget(mut self, time_budget: Option<Duration>) -> Self {
+        self.time_budget = time_budget;
+        self
+    }
+
+    pub(crate) fn auto_advance(mut self, auto_advance: Option<Duration>) -> Self {
+        self.auto_advance = auto_advance;
+        self
+    }
+
+    pub(crate) fn speaker_notes(mut self, speaker_notes: Vec<String>) -> Self {
+        self.speaker_notes = speaker_notes;
+        self
+    }
+
     pub(crate) fn build(self) -> Slide {
-        Slide::new(self.chunks, self.footer)
+        let mut slide = Slide::new(self.chunks, self.footer);
+        slide.time_budget = self.time_budget;
+        slide.auto_advance = self.auto_advance;
+        slide.speaker_notes = self.speaker_notes;
+        slide
     }
 }
 
@@ -237,11 +350,29 @@ pub(crate) struct Slide {
     chunks: Vec<SlideChunk>,
     footer: Vec<RenderOperation>,
     visible_chunks: usize,
+    time_budget: Option<Duration>,
+    auto_advance: Option<Duration>,
+    speaker_notes: Vec<String>,
 }
 
 impl Slide {
     pub(crate) fn new(chunks: Vec<SlideChunk>, footer: Vec<RenderOperation>) -> Self {
-        Self { chunks, footer, visible_chunks: 1 }
+        Self { chunks, footer, visible_chunks: 1, time_budget: None, auto_advance: None, speaker_notes: Vec::new() }
+    }
+
+    /// The amount of time that should be spent on this slide, if any.
+    pub(crate) fn time_budget(&self) -> Option<Duration> {
+        self.time_budget
+    }
+
+    /// How long to wait before automatically moving past each of this slide's chunks, if at all.
+    pub(crate) fn auto_advance(&self) -> Option<Duration> {
+        self.auto_advance
+    }
+
+    /// The speaker notes defined in this slide.
+    pub(crate) fn speaker_notes(&self) -> &[String] {
+        &self.speaker_notes
     }
 
     pub(crate) fn iter_operations(&self) -> impl Iterator<Item = &RenderOperation> + Clone {
@@ -425,6 +556,10 @@ pub(crate) struct PresentationMetadata {
     /// The presentation's options.
     #[serde(default)]
     pub(crate) options: Option<OptionsConfig>,
+
+    /// The target duration for this presentation.
+    #[serde(default)]
+    pub(crate) duration: Option<HumanDuration>,
 }
 
 impl PresentationMetadata {
@@ -0,0 +1,277 @@
+use super::{Presentation, SlideChunk};
+use crate::{
+    markdown::text::{WeightedLine, find_ignore_case},
+    render::{operation::RenderOperation, properties::WindowSize},
+};
+
+// Dynamic operations need to know the window size to generate their operations. The actual size doesn't matter here
+// as we only care about the text in them.
+const TEXT_DIMENSIONS: WindowSize = WindowSize { rows: 1000, columns: 1000, height: 0, width: 0 };
+
+/// Get the lines of text that a chunk renders.
+///
+/// This includes any text, code snippets, and tables in it.
+pub(crate) fn chunk_lines(chunk: &SlideChunk) -> Vec<String> {
+    let mut lines = Vec::new();
+    for operation in chunk.iter_operations() {
+        collect_lines(operation, &mut lines);
+    }
+    lines
+}
+
+fn collect_lines(operation: &RenderOperation, lines: &mut Vec<String>) {
+    let line = match operation {
+        RenderOperation::RenderText { line, .. } => line,
+        RenderOperation::RenderBlockLine(block) => &block.text,
+        RenderOperation::RenderDynamic(generator) => {
+            for operation in generator.as_render_operations(&TEXT_DIMENSIONS) {
+                collect_lines(&operation, lines);
+            }
+            return;
+        }
+        _ => return,
+    };
+    let line = line_text(line);
+    let line = line.trim();
+    if !line.is_empty() {
+        lines.push(line.to_string());
+    }
+}
+
+fn line_text(line: &WeightedLine) -> String {
+    line.iter_texts().map(|text| text.text().content.as_str()).collect()
+}
+
+/// A chunk in a presentation that contains the text being searched for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct SearchMatch {
+    pub(crate) slide: usize,
+    pub(crate) chunk: usize,
+}
+
+/// A full text search over a presentation.
+#[derive(Debug, Default)]
+pub(crate) struct PresentationSearch {
+    prompt: Option<SearchPrompt>,
+    query: Option<String>,
+    matches: Vec<SearchMatch>,
+}
+
+/// The query being typed.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SearchPrompt {
+    pub(crate) query: String,
+    pub(crate) no_matches: bool,
+}
+
+impl PresentationSearch {
+    /// Open the prompt to type a new query.
+    pub(crate) fn start(&mut self) {
+        self.prompt = Some(Default::default());
+    }
+
+    /// Whether a query is being typed.
+    pub(crate) fn is_typing(&self) -> bool {
+        self.prompt.is_some()
+    }
+
+    /// Whether there's a query being searched for.
+    pub(crate) fn is_active(&self) -> bool {
+        self.query.is_some()
+    }
+
+    /// The prompt where the query is being typed, if any.
+    pub(crate) fn prompt(&self) -> Option<&SearchPrompt> {
+        self.prompt.as_ref()
+    }
+
+    /// The query being searched for.
+    pub(crate) fn query(&self) -> Option<&str> {
+        self.query.as_deref()
+    }
+
+    pub(crate) fn push(&mut self, c: char) {
+        if let Some(prompt) = &mut self.prompt {
+            prompt.query.push(c);
+            prompt.no_matches = false;
+        }
+    }
+
+    /// Delete the last character typed, closing the prompt if there's nothing left to delete.
+    pub(crate) fn pop(&mut self) {
+        match &mut self.prompt {
+            Some(prompt) if prompt.query.is_empty() => self.prompt = None,
+            Some(prompt) => {
+                prompt.query.pop();
+                prompt.no_matches = false;
+            }
+            None => (),
+        };
+    }
+
+    /// Close the prompt without searching.
+    pub(crate) fn cancel(&mut self) {
+        self.prompt = None;
+    }
+
+    /// Stop searching.
+    pub(crate) fn clear(&mut self) {
+        self.query = None;
+        self.matches.clear();
+    }
+
+    /// Search for the query in the prompt and return the match to jump to.
+    ///
+    /// An empty query searches for the last query again. If the current slide already contains a match, this
+    /// returns `None` as there's no need to move. If nothing matches, the prompt stays open.
+    pub(crate) fn submit(&mut self, presentation: &Presentation) -> Option<SearchMatch> {
+        let prompt = self.prompt.as_mut()?;
+        let query = match (prompt.query.trim().is_empty(), &self.query) {
+            (false, _) => prompt.query.clone(),
+            (true, Some(query)) => query.clone(),
+            (true, None) => {
+                self.prompt = None;
+                return None;
+            }
+        };
+        let matches = Self::find(presentation, &query);
+        if matches.is_empty() {
+            prompt.no_matches = true;
+            return None;
+        }
+        self.prompt = None;
+        self.query = Some(query);
+        self.matches = matches;
+
+        let current = Self::current_position(presentation);
+        let is_visible = |m: &&SearchMatch| m.slide == current.slide && m.chunk <= current.chunk;
+        match self.matches.iter().find(is_visible) {
+            Some(_) => None,
+            None => self.next(presentation),
+        }
+    }
+
+    /// Search for the current query again, e.g. after the presentation is reloaded.
+    pub(crate) fn refresh(&mut self, presentation: &Presentation) {
+        if let Some(query) = &self.query {
+            self.matches = Self::find(presentation, query);
+        }
+    }
+
+    /// Find the first match after the current position in the presentation, wrapping around if needed.
+    pub(crate) fn next(&self, presentation: &Presentation) -> Option<SearchMatch> {
+        let current = Self::current_position(presentation);
+        self.matches.iter().find(|m| **m > current).or_else(|| self.matches.first()).copied()
+    }
+
+    /// Find the last match before the current position in the presentation, wrapping around if needed.
+    pub(crate) fn previous(&self, presentation: &Presentation) -> Option<SearchMatch> {
+        let current = Self::current_position(presentation);
+        self.matches.iter().rev().find(|m| **m < current).or_else(|| self.matches.last()).copied()
+    }
+
+    fn current_position(presentation: &Presentation) -> SearchMatch {
+        SearchMatch { slide: presentation.current_slide_index(), chunk: presentation.current_chunk() }
+    }
+
+    fn find(presentation: &Presentation, query: &str) -> Vec<SearchMatch> {
+        let mut matches = Vec::new();
+        for (slide_index, slide) in presentation.iter_slides().enumerate() {
+            for (chunk_index, chunk) in slide.iter_chunks().enumerate() {
+                if chunk_lines(chunk).iter().any(|line| !find_ignore_case(line, query).is_empty()) {
+                    matches.push(SearchMatch { slide: slide_index, chunk: chunk_index });
+                }
+            }
+        }
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::presentation::builder::utils::Test;
+
+    fn make_presentation() -> Presentation {
+        let input = "
+hello
+
+<!-- end_slide -->
+
+nothing here
+
+<!-- pause -->
+
+```rust
+let hello = 42;
+```
+
+<!-- end_slide -->
+
+| name  |
+| ----- |
+| HELLO |
+";
+        Test::new(input).build()
+    }
+
+    fn search(presentation: &Presentation, query: &str) -> PresentationSearch {
+        let mut search = PresentationSearch::default();
+        search.start();
+        for c in query.chars() {
+            search.push(c);
+        }
+        search.submit(presentation);
+        search
+    }
+
+    #[test]
+    fn find_matches() {
+        let presentation = make_presentation();
+        let search = search(&presentation, "hello");
+        let expected = &[
+            SearchMatch { slide: 0, chunk: 0 },
+            SearchMatch { slide: 1, chunk: 1 },
+            SearchMatch { slide: 2, chunk: 0 },
+        ];
+        assert_eq!(search.matches, expected);
+        assert_eq!(search.query(), Some("hello"));
+        assert!(!search.is_typing());
+    }
+
+    #[test]
+    fn navigate() {
+        let mut presentation = make_presentation();
+        let search = search(&presentation, "hello");
+        let next = search.next(&presentation).unwrap();
+        assert_eq!(next, SearchMatch { slide: 1, chunk: 1 });
+
+        presentation.go_to_slide(2);
+        // Wrap around.
+        assert_eq!(search.next(&presentation), Some(SearchMatch { slide: 0, chunk: 0 }));
+        assert_eq!(search.previous(&presentation), Some(SearchMatch { slide: 1, chunk: 1 }));
+    }
+
+    #[test]
+    fn submit_jumps_to_match() {
+        let mut presentation = make_presentation();
+        presentation.go_to_slide(1);
+        let mut search = PresentationSearch::default();
+        search.start();
+        search.push('4');
+        search.push('2');
+        assert_eq!(search.submit(&presentation), Some(SearchMatch { slide: 1, chunk: 1 }));
+    }
+
+    #[test]
+    fn no_matches() {
+        let presentation = make_presentation();
+        let mut search = search(&presentation, "potato");
+        assert!(search.is_typing());
+        assert!(search.prompt().unwrap().no_matches);
+        assert!(!search.is_active());
+
+        search.cancel();
+        assert!(!search.is_typing());
+    }
+}
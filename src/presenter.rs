@@ -1,27 +1,35 @@
 use crate::{
-    code::execute::SnippetExecutor,
+    code::execute::{ProcessInput, SnippetExecutor},
     commands::{
+        keyboard::{Direction, InputMode, PointerEvent, TextInputEvent},
         listener::{Command, CommandListener},
-        speaker_notes::{SpeakerNotesEvent, SpeakerNotesEventPublisher},
+        remote::{RemoteControlPublisher, RemoteState},
+        speaker_notes::{
+            SpeakerNotesControlEvent, SpeakerNotesControlPublisher, SpeakerNotesEvent, SpeakerNotesEventPublisher,
+        },
     },
-    config::{KeyBindingsConfig, SlideTransitionConfig, SlideTransitionStyleConfig},
+    config::{KeyBindingsConfig, SlideTransitionConfig, SlideTransitionStyleConfig, SpeakerNotesLayout},
+    export::mirror::AudienceMirror,
     markdown::parse::MarkdownParser,
     presentation::{
         Presentation, Slide,
         builder::{PresentationBuilder, PresentationBuilderOptions, Themes, error::BuildError},
+        clock::{AutoAdvanceTimer, PresentationClock, SlideTimeTracker},
         diff::PresentationDiffer,
         poller::{PollableEffect, Poller, PollerCommand},
+        search::{PresentationSearch, SearchMatch},
     },
     render::{
         ErrorSource, RenderError, RenderResult, TerminalDrawer, TerminalDrawerOptions,
         ascii_scaler::AsciiScaler,
-        engine::{MaxSize, RenderEngine, RenderEngineOptions},
-        operation::{Pollable, RenderAsyncStartPolicy, RenderOperation},
+        engine::{MaxSize, RenderEngine, RenderEngineOptions, TextHighlight},
+        operation::{Pollable, RenderAsync, RenderAsyncStartPolicy, RenderOperation},
         properties::WindowSize,
         validate::OverflowValidator,
     },
     resource::Resources,
     terminal::{
+        appearance::{Appearance, AppearanceWatcher},
         image::printer::{ImagePrinter, ImageRegistry},
         printer::{TerminalCommand, TerminalIo},
         virt::{ImageBehavior, TerminalGrid, VirtualTerminal},
@@ -30,20 +38,32 @@ use crate::{
     third_party::ThirdPartyRender,
     transitions::{
         AnimateTransition, AnimationFrame, LinesFrame, TransitionDirection,
-        collapse_horizontal::CollapseHorizontalAnimation, fade::FadeAnimation,
+        collapse_horizontal::CollapseHorizontalAnimation, fade::FadeAnimation, magic_move::MagicMoveAnimation,
         slide_horizontal::SlideHorizontalAnimation,
     },
+    ui::{
+        annotations::Annotations,
+        execution::input::{SnippetInputBar, SnippetInputPrompt},
+        modals::SlideOverview,
+        palette::{CommandPalette, PaletteBar, PaletteCommand, PaletteError},
+        search::SearchBar,
+        speaker_view::{SlidePreviews, SpeakerView},
+    },
 };
 use std::{
     fmt::Display,
     io::{self},
-    mem,
+    iter, mem,
     ops::Deref,
-    path::Path,
+    path::{Path, PathBuf},
+    rc::Rc,
     sync::Arc,
     time::{Duration, Instant},
 };
 
+// How long to wait without any interactions before auto advancing resumes.
+const AUTO_ADVANCE_RESUME_DELAY: Duration = Duration::from_secs(60);
+
 pub struct PresenterOptions {
     pub mode: PresentMode,
     pub builder_options: PresentationBuilderOptions,
@@ -52,6 +72,26 @@ pub struct PresenterOptions {
     pub validate_overflows: bool,
     pub max_size: MaxSize,
     pub transition: Option<SlideTransitionConfig>,
+    pub speaker_notes_layout: SpeakerNotesLayout,
+    pub record_path: Option<PathBuf>,
+    pub dynamic_theme: Option<DynamicTheme>,
+}
+
+/// The themes to switch between when the terminal goes from a dark to a light background and vice versa.
+pub struct DynamicTheme {
+    pub dark: String,
+    pub light: String,
+    pub watcher: AppearanceWatcher,
+}
+
+impl DynamicTheme {
+    /// The name of the theme to use for the given appearance.
+    pub(crate) fn theme_name(&self, appearance: Appearance) -> &str {
+        match appearance {
+            Appearance::Dark => &self.dark,
+            Appearance::Light => &self.light,
+        }
+    }
 }
 
 /// A slideshow presenter.
@@ -59,6 +99,7 @@ pub struct PresenterOptions {
 /// This type puts everything else together.
 pub struct Presenter<'a> {
     default_theme: &'a PresentationTheme,
+    theme: Option<PresentationTheme>,
     listener: CommandListener,
     parser: MarkdownParser<'a>,
     resources: Resources,
@@ -69,7 +110,21 @@ pub struct Presenter<'a> {
     themes: Themes,
     options: PresenterOptions,
     speaker_notes_event_publisher: Option<SpeakerNotesEventPublisher>,
+    speaker_notes_control_publisher: Option<SpeakerNotesControlPublisher>,
+    remote_control_publisher: Option<RemoteControlPublisher>,
+    audience_mirror: Option<AudienceMirror>,
     poller: Poller,
+    clock: PresentationClock,
+    slide_times: SlideTimeTracker,
+    auto_advance: AutoAdvanceTimer,
+    preview: Option<Presentation>,
+    previews: SlidePreviews,
+    // The elapsed time published by the main presentation when this is a speaker notes instance.
+    main_elapsed: Option<Duration>,
+    search: PresentationSearch,
+    annotations: Annotations,
+    palette: CommandPalette,
+    snippet_input: SnippetInputPrompt,
 }
 
 impl<'a> Presenter<'a> {
@@ -86,9 +141,13 @@ impl<'a> Presenter<'a> {
         image_printer: Arc<ImagePrinter>,
         options: PresenterOptions,
         speaker_notes_event_publisher: Option<SpeakerNotesEventPublisher>,
+        speaker_notes_control_publisher: Option<SpeakerNotesControlPublisher>,
+        remote_control_publisher: Option<RemoteControlPublisher>,
+        audience_mirror: Option<AudienceMirror>,
     ) -> Self {
         Self {
             default_theme,
+            theme: None,
             listener,
             parser,
             resources,
@@ -99,7 +158,20 @@ impl<'a> Presenter<'a> {
             themes,
             options,
             speaker_notes_event_publisher,
+            speaker_notes_control_publisher,
+            remote_control_publisher,
+            audience_mirror,
             poller: Poller::launch(),
+            clock: PresentationClock::start(),
+            slide_times: Default::default(),
+            auto_advance: AutoAdvanceTimer::new(AUTO_ADVANCE_RESUME_DELAY),
+            preview: None,
+            previews: Default::default(),
+            main_elapsed: None,
+            search: Default::default(),
+            annotations: Default::default(),
+            palette: Default::default(),
+            snippet_input: Default::default(),
         }
     }
 
@@ -109,34 +181,51 @@ impl<'a> Presenter<'a> {
             self.resources.watch_presentation_file(path.to_path_buf());
         }
         self.state = PresenterState::Presenting(Presentation::from(vec![]));
-        self.try_reload(path, true)?;
+        self.try_reload(path, ReloadMode::Forced)?;
 
         let drawer_options = TerminalDrawerOptions {
             font_size_fallback: self.options.font_size_fallback,
             max_size: self.options.max_size.clone(),
+            record_path: self.options.record_path.clone(),
         };
         let mut drawer = TerminalDrawer::new(self.image_printer.clone(), drawer_options)?;
+        self.clock = PresentationClock::start();
         loop {
+            self.publish_remote_state();
             // Poll async renders once before we draw just in case.
             self.render(&mut drawer)?;
 
             loop {
-                if self.process_poller_effects()? {
+                if self.process_poller_effects()? || self.process_clock_tick()? || self.mirror_needs_refresh() {
                     self.render(&mut drawer)?;
                 }
+                self.poll_appearance()?;
 
+                self.listener.set_input_mode(self.input_mode());
                 let command = match self.listener.try_next_command()? {
-                    Some(command) => command,
+                    Some(command) => {
+                        self.interrupt_auto_advance(&command);
+                        command
+                    }
                     _ => match self.resources.resources_modified() {
                         true => Command::Reload,
                         false => {
                             if self.check_async_error() {
                                 break;
                             }
-                            continue;
+                            match self.next_auto_advance_command() {
+                                Some(command) => command,
+                                None => continue,
+                            }
                         }
                     },
                 };
+                let Some(command) = self.resolve_click(command) else {
+                    continue;
+                };
+                if self.forward_command(&command)? {
+                    continue;
+                }
                 match self.apply_command(command) {
                     CommandSideEffect::Exit => {
                         self.publish_event(SpeakerNotesEvent::Exit)?;
@@ -146,8 +235,8 @@ impl<'a> Presenter<'a> {
                         self.suspend(&mut drawer);
                         break;
                     }
-                    CommandSideEffect::Reload => {
-                        self.try_reload(path, false)?;
+                    CommandSideEffect::Reload(mode) => {
+                        self.try_reload(path, mode)?;
                         break;
                     }
                     CommandSideEffect::Redraw => {
@@ -181,7 +270,9 @@ impl<'a> Presenter<'a> {
         let current_slide = match &self.state {
             PresenterState::Presenting(presentation)
             | PresenterState::SlideIndex(presentation)
+            | PresenterState::Overview(presentation)
             | PresenterState::KeyBindings(presentation)
+            | PresenterState::ThemePicker(presentation)
             | PresenterState::Failure { presentation, .. } => presentation.current_slide_index(),
             PresenterState::Empty => usize::MAX,
         };
@@ -192,6 +283,9 @@ impl<'a> Presenter<'a> {
                 PollableEffect::RefreshSlide(index) => {
                     needs_render = needs_render || index == current_slide;
                     refreshed = true;
+                    if !matches!(self.state, PresenterState::Empty) {
+                        self.state.presentation().overview().invalidate(index);
+                    }
                 }
                 PollableEffect::DisplayError { slide, error } => {
                     let presentation = mem::take(&mut self.state).into_presentation();
@@ -207,6 +301,89 @@ impl<'a> Presenter<'a> {
         Ok(needs_render)
     }
 
+    fn process_clock_tick(&mut self) -> Result<bool, PresentationError> {
+        let presentation = match &self.state {
+            PresenterState::Presenting(presentation)
+            | PresenterState::SlideIndex(presentation)
+            | PresenterState::Overview(presentation)
+            | PresenterState::KeyBindings(presentation)
+            | PresenterState::ThemePicker(presentation) => presentation,
+            PresenterState::Failure { .. } | PresenterState::Empty => return Ok(false),
+        };
+        let current_slide = presentation.current_slide_index();
+        self.slide_times.visit(current_slide);
+        if !self.clock.tick() {
+            return Ok(false);
+        }
+        // The speaker notes window gets its pacing from the main presentation.
+        if !self.options.builder_options.render_speaker_notes_only {
+            let budgets = presentation.iter_slides().map(Slide::time_budget);
+            let pacing = self.slide_times.pacing(budgets, current_slide);
+            presentation.state.set_pacing(pacing);
+            self.publish_event(SpeakerNotesEvent::Clock { elapsed: self.clock.elapsed(), pacing })?;
+        }
+        Ok(self.is_speaker_view() || self.state.presentation().state.is_time_dependent())
+    }
+
+    // Pause auto advancing if this command came from someone interacting with the presentation.
+    fn interrupt_auto_advance(&mut self, command: &Command) {
+        if !matches!(command, Command::Redraw | Command::UpdateAppearance(_)) {
+            self.auto_advance.interrupt();
+        }
+    }
+
+    // Get the command to move forward if the current slide auto advances and its delay has elapsed.
+    //
+    // Going past the last chunk of the last slide loops back to the first slide.
+    fn next_auto_advance_command(&mut self) -> Option<Command> {
+        // The speaker notes window follows the main presentation.
+        if self.options.builder_options.render_speaker_notes_only {
+            return None;
+        }
+        let PresenterState::Presenting(presentation) = &self.state else {
+            return None;
+        };
+        let slide = presentation.current_slide();
+        let delay = slide.auto_advance()?;
+        self.auto_advance.visit(presentation.current_slide_index(), slide.current_chunk_index());
+        if !self.auto_advance.is_due(delay) {
+            return None;
+        }
+        let is_last_slide = presentation.current_slide_index() + 1 == presentation.iter_slides().count();
+        let is_last_chunk = slide.current_chunk_index() + 1 == slide.iter_chunks().count();
+        match is_last_slide && is_last_chunk {
+            true => Some(Command::FirstSlide),
+            false => Some(Command::Next),
+        }
+    }
+
+    fn poll_appearance(&mut self) -> io::Result<()> {
+        let Some(dynamic_theme) = &mut self.options.dynamic_theme else {
+            return Ok(());
+        };
+        if dynamic_theme.watcher.poll()? {
+            self.listener.expect_terminal_reply();
+        }
+        Ok(())
+    }
+
+    // Switch to the theme for the terminal's appearance if it changed.
+    fn update_appearance(&mut self, appearance: Appearance) -> CommandSideEffect {
+        let Some(dynamic_theme) = &mut self.options.dynamic_theme else {
+            return CommandSideEffect::None;
+        };
+        let Some(appearance) = dynamic_theme.watcher.update(appearance) else {
+            return CommandSideEffect::None;
+        };
+        match self.themes.presentation.load_by_name(dynamic_theme.theme_name(appearance)) {
+            Some(theme) => {
+                self.theme = Some(theme);
+                CommandSideEffect::Reload(ReloadMode::Restyle)
+            }
+            None => CommandSideEffect::None,
+        }
+    }
+
     fn publish_event(&self, event: SpeakerNotesEvent) -> io::Result<()> {
         if let Some(publisher) = &self.speaker_notes_event_publisher {
             publisher.send(event)?;
@@ -214,6 +391,48 @@ impl<'a> Presenter<'a> {
         Ok(())
     }
 
+    fn publish_remote_state(&self) {
+        let Some(publisher) = &self.remote_control_publisher else {
+            return;
+        };
+        let presentation = match &self.state {
+            PresenterState::Presenting(presentation)
+            | PresenterState::SlideIndex(presentation)
+            | PresenterState::Overview(presentation)
+            | PresenterState::KeyBindings(presentation)
+            | PresenterState::ThemePicker(presentation)
+            | PresenterState::Failure { presentation, .. } => presentation,
+            PresenterState::Empty => return,
+        };
+        let slide = presentation.current_slide();
+        publisher.publish(RemoteState {
+            slide: presentation.current_slide_index() as u32 + 1,
+            total_slides: presentation.iter_slides().count() as u32,
+            chunk: slide.current_chunk_index() as u32,
+            total_chunks: slide.iter_chunks().count() as u32,
+            speaker_notes: slide.speaker_notes().to_vec(),
+        });
+    }
+
+    // Forward a command to the main presentation if this is a speaker notes instance that controls it.
+    //
+    // The main presentation will publish the resulting slide change back so this instance follows it.
+    fn forward_command(&self, command: &Command) -> io::Result<bool> {
+        let Some(publisher) = &self.speaker_notes_control_publisher else {
+            return Ok(false);
+        };
+        if !matches!(self.state, PresenterState::Presenting(_)) {
+            return Ok(false);
+        }
+        match SpeakerNotesControlEvent::from_command(command) {
+            Some(event) => {
+                publisher.send(event)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
     fn check_async_error(&mut self) -> bool {
         let error_holder = self.state.presentation().state.async_error_holder();
         let error_holder = error_holder.lock().unwrap();
@@ -233,42 +452,162 @@ impl<'a> Presenter<'a> {
     }
 
     fn render(&mut self, drawer: &mut TerminalDrawer) -> RenderResult {
+        if !matches!(self.state, PresenterState::Empty) {
+            let elapsed = self.main_elapsed.unwrap_or_else(|| self.clock.elapsed());
+            self.state.presentation().state.set_elapsed_time(elapsed);
+        }
+        if let PresenterState::Presenting(presentation) = &self.state {
+            self.annotations.follow_slide(presentation.current_slide_index());
+        }
+        let capture_mouse = self.options.bindings.mouse.enable || self.input_mode() == InputMode::Annotating;
+        drawer.terminal.set_mouse_capture(capture_mouse)?;
         let result = match &self.state {
-            PresenterState::Presenting(presentation) => {
-                drawer.render_operations(presentation.current_slide().iter_visible_operations())
-            }
+            PresenterState::Presenting(presentation) => match &self.preview {
+                Some(preview) => {
+                    let dimensions = WindowSize::current(self.options.font_size_fallback)?;
+                    Self::render_speaker_view(drawer, presentation, preview, &mut self.previews, dimensions)
+                }
+                None => self.render_slide(drawer, presentation),
+            },
             PresenterState::SlideIndex(presentation) => {
                 drawer.render_operations(presentation.current_slide().iter_visible_operations())?;
                 drawer.render_operations(presentation.iter_slide_index_operations())
             }
+            PresenterState::Overview(presentation) => self.render_overview(drawer, presentation),
             PresenterState::KeyBindings(presentation) => {
                 drawer.render_operations(presentation.current_slide().iter_visible_operations())?;
                 drawer.render_operations(presentation.iter_bindings_operations())
             }
+            PresenterState::ThemePicker(presentation) => {
+                drawer.render_operations(presentation.current_slide().iter_visible_operations())?;
+                drawer.render_operations(presentation.iter_theme_picker_operations())
+            }
             PresenterState::Failure { error, source, .. } => drawer.render_error(error, source),
             PresenterState::Empty => panic!("cannot render without state"),
         };
+        let result = result.and_then(|_| self.mirror_slide(drawer));
         // If the screen is too small, simply ignore this. Eventually the user will resize the
         // screen.
         if matches!(result, Err(RenderError::TerminalTooSmall)) { Ok(()) } else { result }
     }
 
+    fn render_slide(&self, drawer: &mut TerminalDrawer, presentation: &Presentation) -> RenderResult {
+        let operations = presentation.current_slide().iter_visible_operations();
+        match self.search.query() {
+            Some(query) => {
+                let highlight =
+                    TextHighlight { query: query.to_string(), style: presentation.search_styles().highlight };
+                drawer.render_highlighted_operations(operations, highlight)?;
+            }
+            None => drawer.render_operations(operations)?,
+        };
+        if let Some(prompt) = self.search.prompt() {
+            let bar = SearchBar { prompt: prompt.clone(), style: presentation.search_styles().prompt };
+            drawer.render_operations(iter::once(&RenderOperation::RenderDynamic(Rc::new(bar))))?;
+        }
+        if let Some(prompt) = self.palette.prompt() {
+            let styles = presentation.search_styles();
+            let bar = PaletteBar { prompt: prompt.clone(), style: styles.prompt, selection_style: styles.highlight };
+            drawer.render_operations(iter::once(&RenderOperation::RenderDynamic(Rc::new(bar))))?;
+        }
+        if let Some(input) = self.snippet_input.input() {
+            let bar = SnippetInputBar { input: input.to_string(), style: presentation.search_styles().prompt };
+            drawer.render_operations(iter::once(&RenderOperation::RenderDynamic(Rc::new(bar))))?;
+        }
+        if let Some(overlay) = self.annotations.overlay(presentation.annotation_style()) {
+            drawer.render_operations(iter::once(&RenderOperation::RenderDynamic(Rc::new(overlay))))?;
+        }
+        Ok(())
+    }
+
+    fn input_mode(&self) -> InputMode {
+        if self.state.is_typing()
+            || self.search.is_typing()
+            || self.palette.is_active()
+            || self.snippet_input.is_active()
+        {
+            InputMode::TextInput
+        } else if self.annotations.is_enabled() && matches!(self.state, PresenterState::Presenting(_)) {
+            InputMode::Annotating
+        } else if self.search.is_active() && matches!(self.state, PresenterState::Presenting(_)) {
+            InputMode::Searching
+        } else if matches!(self.state, PresenterState::Overview(_)) {
+            InputMode::Navigation
+        } else {
+            InputMode::Bindings
+        }
+    }
+
+    fn mirror_slide(&self, drawer: &TerminalDrawer) -> RenderResult {
+        let (Some(mirror), PresenterState::Presenting(presentation)) = (&self.audience_mirror, &self.state) else {
+            return Ok(());
+        };
+        // Don't bother rendering the slide if nobody is watching.
+        if !mirror.has_clients() {
+            return Ok(());
+        }
+        let dimensions = WindowSize::current(self.options.font_size_fallback)?;
+        let grid = Self::virtual_render(presentation.current_slide(), dimensions, &drawer.render_engine_options())?;
+        mirror.publish(grid, dimensions);
+        Ok(())
+    }
+
+    fn mirror_needs_refresh(&self) -> bool {
+        self.audience_mirror.as_ref().is_some_and(AudienceMirror::take_refresh_request)
+    }
+
+    fn render_speaker_view(
+        drawer: &mut TerminalDrawer,
+        presentation: &Presentation,
+        preview: &Presentation,
+        previews: &mut SlidePreviews,
+        dimensions: WindowSize,
+    ) -> RenderResult {
+        let current_slide = presentation.current_slide_index();
+        let options = drawer.render_engine_options();
+        let next_slide = match preview.iter_slides().nth(current_slide + 1) {
+            Some(slide) => Some(previews.get_or_render(current_slide + 1, slide, dimensions, &options)?),
+            None => None,
+        };
+        let view = SpeakerView {
+            notes: presentation.current_slide(),
+            next_slide,
+            current_slide: current_slide + 1,
+            total_slides: preview.iter_slides().count(),
+            elapsed: presentation.state.elapsed_time(),
+            pacing: presentation.state.pacing(),
+        };
+        let grid = view.render(dimensions, &options)?;
+        Self::render_frame(&LinesFrame::from(&grid).build_commands(), drawer)
+    }
+
+    fn render_overview(&self, drawer: &mut TerminalDrawer, presentation: &Presentation) -> RenderResult {
+        let dimensions = WindowSize::current(self.options.font_size_fallback)?;
+        let grid = presentation.overview().render(presentation, dimensions, &drawer.render_engine_options())?;
+        Self::render_frame(&LinesFrame::from(&grid).build_commands(), drawer)
+    }
+
+    fn is_speaker_view(&self) -> bool {
+        self.preview.is_some()
+    }
+
     fn apply_command(&mut self, command: Command) -> CommandSideEffect {
         // These ones always happens no matter our state.
         match command {
             Command::Reload => {
-                return CommandSideEffect::Reload;
+                return CommandSideEffect::Reload(ReloadMode::Development);
             }
             Command::HardReload => {
                 if matches!(self.options.mode, PresentMode::Development) {
                     self.resources.clear();
                 }
-                return CommandSideEffect::Reload;
+                return CommandSideEffect::Reload(ReloadMode::Development);
             }
             Command::ToggleLayoutGrid => {
                 self.options.builder_options.layout_grid = !self.options.builder_options.layout_grid;
-                return CommandSideEffect::Reload;
+                return CommandSideEffect::Reload(ReloadMode::Development);
             }
+            Command::UpdateAppearance(appearance) => return self.update_appearance(appearance),
             Command::Exit => return CommandSideEffect::Exit,
             Command::Suspend => return CommandSideEffect::Suspend,
             _ => (),
@@ -281,11 +620,26 @@ impl<'a> Presenter<'a> {
             return CommandSideEffect::Redraw;
         }
 
+        match command {
+            Command::Search => return self.start_search(),
+            Command::OpenCommandPalette => return self.open_command_palette(),
+            Command::SnippetInput => return self.start_snippet_input(),
+            Command::TextInput(event) => return self.apply_text_input(event),
+            Command::SearchNext | Command::SearchPrevious => return self.jump_search_match(&command),
+            Command::MoveSelection(direction) => return self.move_overview_selection(direction),
+            Command::ToggleAnnotations => return self.toggle_annotations(),
+            Command::Pointer(event) => return self.apply_pointer_event(event),
+            Command::Click { column, row } => return self.click(column, row),
+            _ => (),
+        };
+
         // Now apply the commands that require a presentation.
         let presentation = match &mut self.state {
             PresenterState::Presenting(presentation)
             | PresenterState::SlideIndex(presentation)
-            | PresenterState::KeyBindings(presentation) => presentation,
+            | PresenterState::Overview(presentation)
+            | PresenterState::KeyBindings(presentation)
+            | PresenterState::ThemePicker(presentation) => presentation,
             _ => {
                 return CommandSideEffect::None;
             }
@@ -332,15 +686,33 @@ impl<'a> Presenter<'a> {
                     return CommandSideEffect::None;
                 }
             }
+            Command::KillExecution => {
+                Self::kill_slide_executions(presentation);
+                // The status is updated and redrawn once the process is actually gone.
+                false
+            }
             Command::ToggleSlideIndex => {
                 self.toggle_slide_index();
                 true
             }
+            Command::ToggleOverview => {
+                self.toggle_overview();
+                true
+            }
             Command::ToggleKeyBindingsConfig => {
                 self.toggle_key_bindings();
                 true
             }
+            Command::ToggleThemePicker => {
+                self.toggle_theme_picker();
+                true
+            }
             Command::CloseModal => {
+                // Closing when there's no modal open stops searching and annotating.
+                if matches!(self.state, PresenterState::Presenting(_)) {
+                    self.search.clear();
+                    self.annotations.disable();
+                }
                 let presentation = mem::take(&mut self.state).into_presentation();
                 self.state = PresenterState::Presenting(presentation);
                 true
@@ -349,29 +721,283 @@ impl<'a> Presenter<'a> {
                 presentation.show_all_slide_chunks();
                 true
             }
+            Command::UpdateClock { elapsed, pacing } => {
+                self.main_elapsed = Some(elapsed);
+                presentation.state.set_pacing(pacing);
+                self.preview.is_some() || presentation.state.is_time_dependent()
+            }
             // These are handled above as they don't require the presentation
             Command::Reload
             | Command::HardReload
             | Command::Exit
             | Command::Suspend
             | Command::Redraw
-            | Command::ToggleLayoutGrid => {
+            | Command::ToggleLayoutGrid
+            | Command::UpdateAppearance(_)
+            | Command::Search
+            | Command::OpenCommandPalette
+            | Command::SnippetInput
+            | Command::SearchNext
+            | Command::SearchPrevious
+            | Command::MoveSelection(_)
+            | Command::ToggleAnnotations
+            | Command::Pointer(_)
+            | Command::Click { .. }
+            | Command::TextInput(_) => {
                 panic!("unreachable commands")
             }
         };
         if needs_redraw { CommandSideEffect::Redraw } else { CommandSideEffect::None }
     }
 
-    fn try_reload(&mut self, path: &Path, force: bool) -> RenderResult {
-        if matches!(self.options.mode, PresentMode::Presentation) && !force {
+    fn start_search(&mut self) -> CommandSideEffect {
+        match &self.state {
+            PresenterState::SlideIndex(presentation) => presentation.slide_index_search().start(),
+            PresenterState::Presenting(_) if !self.is_speaker_view() => self.search.start(),
+            _ => return CommandSideEffect::None,
+        };
+        CommandSideEffect::Redraw
+    }
+
+    fn open_command_palette(&mut self) -> CommandSideEffect {
+        if !matches!(self.state, PresenterState::Presenting(_)) || self.is_speaker_view() {
+            return CommandSideEffect::None;
+        }
+        self.palette.start();
+        CommandSideEffect::Redraw
+    }
+
+    fn start_snippet_input(&mut self) -> CommandSideEffect {
+        if self.is_speaker_view() || self.input_snippet().is_none() {
+            return CommandSideEffect::None;
+        }
+        self.snippet_input.start();
+        CommandSideEffect::Redraw
+    }
+
+    fn apply_snippet_input(&mut self, event: TextInputEvent) -> CommandSideEffect {
+        let input = match event {
+            TextInputEvent::Character(c) => {
+                self.snippet_input.push(c);
+                None
+            }
+            TextInputEvent::Backspace => {
+                self.snippet_input.pop();
+                None
+            }
+            TextInputEvent::Cancel => {
+                self.snippet_input.cancel();
+                None
+            }
+            TextInputEvent::Submit => self.snippet_input.take().map(ProcessInput::Line),
+            TextInputEvent::EndOfInput => {
+                self.snippet_input.cancel();
+                Some(ProcessInput::End)
+            }
+            TextInputEvent::Next | TextInputEvent::Previous | TextInputEvent::Complete => {
+                return CommandSideEffect::None;
+            }
+        };
+        match self.input_snippet() {
+            Some(operation) => {
+                if let Some(input) = input {
+                    operation.send_input(input);
+                }
+            }
+            // The process is gone so there's nothing to type into.
+            None => self.snippet_input.cancel(),
+        };
+        CommandSideEffect::Redraw
+    }
+
+    // The snippet in the current slide that's reading input typed by the presenter, if any.
+    fn input_snippet(&self) -> Option<&dyn RenderAsync> {
+        let PresenterState::Presenting(presentation) = &self.state else {
+            return None;
+        };
+        presentation.current_slide().iter_visible_operations().find_map(|operation| match operation {
+            RenderOperation::RenderAsync(operation) if operation.accepts_input() => Some(operation.as_ref()),
+            _ => None,
+        })
+    }
+
+    fn apply_palette_input(&mut self, event: TextInputEvent) -> CommandSideEffect {
+        match event {
+            TextInputEvent::Character(c) => self.palette.push(c),
+            TextInputEvent::Backspace => self.palette.pop(),
+            TextInputEvent::Complete => self.palette.complete(&self.themes.presentation.theme_names()),
+            TextInputEvent::Cancel => self.palette.cancel(),
+            TextInputEvent::Submit => {
+                return match self.palette.submit() {
+                    Some(command) => self.run_palette_command(command),
+                    None => CommandSideEffect::Redraw,
+                };
+            }
+            TextInputEvent::Next | TextInputEvent::Previous | TextInputEvent::EndOfInput => {
+                return CommandSideEffect::None;
+            }
+        };
+        CommandSideEffect::Redraw
+    }
+
+    fn run_palette_command(&mut self, command: PaletteCommand) -> CommandSideEffect {
+        match command {
+            PaletteCommand::Run(command) => {
+                // Make sure the screen is refreshed as the palette was just closed.
+                match self.apply_command(command) {
+                    CommandSideEffect::None => CommandSideEffect::Redraw,
+                    other => other,
+                }
+            }
+            PaletteCommand::Theme(name) => match self.switch_theme(&name) {
+                true => CommandSideEffect::Reload(ReloadMode::Restyle),
+                false => {
+                    self.palette.fail(PaletteError::UnknownTheme(name));
+                    CommandSideEffect::Redraw
+                }
+            },
+            PaletteCommand::Reload => {
+                self.resources.clear();
+                CommandSideEffect::Reload(ReloadMode::Forced)
+            }
+            PaletteCommand::ToggleNotes => {
+                let options = &mut self.options.builder_options;
+                options.render_speaker_notes_only = !options.render_speaker_notes_only;
+                self.preview = None;
+                CommandSideEffect::Reload(ReloadMode::Restyle)
+            }
+        }
+    }
+
+    // Use the theme with the given name from now on.
+    //
+    // Returns `false` if there's no such theme.
+    fn switch_theme(&mut self, name: &str) -> bool {
+        let Some(theme) = self.themes.presentation.load_by_name(name) else {
+            return false;
+        };
+        self.theme = Some(theme);
+        // The theme in the front matter would otherwise take precedence.
+        self.options.builder_options.force_default_theme = true;
+        // A theme that was explicitly picked shouldn't be replaced when the terminal's appearance changes.
+        self.options.dynamic_theme = None;
+        true
+    }
+
+    fn apply_text_input(&mut self, event: TextInputEvent) -> CommandSideEffect {
+        if self.palette.is_active() {
+            return self.apply_palette_input(event);
+        }
+        if self.snippet_input.is_active() {
+            return self.apply_snippet_input(event);
+        }
+        let presentation = match &self.state {
+            PresenterState::SlideIndex(_) => return self.apply_index_search_input(event),
+            PresenterState::ThemePicker(_) => return self.apply_theme_picker_input(event),
+            PresenterState::Presenting(presentation) if self.search.is_typing() => presentation,
+            _ => return CommandSideEffect::None,
+        };
+        match event {
+            TextInputEvent::Character(c) => self.search.push(c),
+            TextInputEvent::Backspace => self.search.pop(),
+            TextInputEvent::Cancel => self.search.cancel(),
+            TextInputEvent::Submit => {
+                let search_match = self.search.submit(presentation);
+                self.go_to_search_match(search_match);
+            }
+            TextInputEvent::Next | TextInputEvent::Previous | TextInputEvent::Complete | TextInputEvent::EndOfInput => {
+                return CommandSideEffect::None;
+            }
+        };
+        CommandSideEffect::Redraw
+    }
+
+    fn jump_search_match(&mut self, command: &Command) -> CommandSideEffect {
+        let PresenterState::Presenting(presentation) = &self.state else {
+            return CommandSideEffect::None;
+        };
+        let search_match = match command {
+            Command::SearchPrevious => self.search.previous(presentation),
+            _ => self.search.next(presentation),
+        };
+        if self.go_to_search_match(search_match) { CommandSideEffect::Redraw } else { CommandSideEffect::None }
+    }
+
+    fn go_to_search_match(&mut self, search_match: Option<SearchMatch>) -> bool {
+        let (Some(SearchMatch { slide, chunk }), PresenterState::Presenting(presentation)) =
+            (search_match, &mut self.state)
+        else {
+            return false;
+        };
+        presentation.go_to_slide(slide);
+        presentation.jump_chunk(chunk);
+        true
+    }
+
+    fn apply_index_search_input(&mut self, event: TextInputEvent) -> CommandSideEffect {
+        let PresenterState::SlideIndex(presentation) = &self.state else {
+            return CommandSideEffect::None;
+        };
+        let search = presentation.slide_index_search();
+        match event {
+            TextInputEvent::Character(c) => search.push(c),
+            TextInputEvent::Backspace => search.pop(),
+            TextInputEvent::Next => search.select_next(),
+            TextInputEvent::Previous => search.select_previous(),
+            TextInputEvent::Complete | TextInputEvent::EndOfInput => return CommandSideEffect::None,
+            TextInputEvent::Cancel => search.stop(),
+            TextInputEvent::Submit => {
+                let selected_slide = search.selected_slide();
+                search.stop();
+                if let Some(slide) = selected_slide {
+                    let presentation = mem::take(&mut self.state).into_presentation();
+                    self.state = PresenterState::Presenting(presentation);
+                    self.apply_command(Command::GoToSlide(slide as u32 + 1));
+                }
+            }
+        };
+        CommandSideEffect::Redraw
+    }
+
+    fn apply_theme_picker_input(&mut self, event: TextInputEvent) -> CommandSideEffect {
+        let PresenterState::ThemePicker(presentation) = &self.state else {
+            return CommandSideEffect::None;
+        };
+        let picker = presentation.theme_picker();
+        match event {
+            TextInputEvent::Character(c) => picker.push(c),
+            TextInputEvent::Backspace => picker.pop(),
+            TextInputEvent::Next => picker.select_next(),
+            TextInputEvent::Previous => picker.select_previous(),
+            TextInputEvent::Complete | TextInputEvent::EndOfInput => return CommandSideEffect::None,
+            TextInputEvent::Cancel => self.toggle_theme_picker(),
+            TextInputEvent::Submit => {
+                let selected_theme = picker.selected_theme();
+                self.toggle_theme_picker();
+                if let Some(name) = selected_theme {
+                    if self.switch_theme(&name) {
+                        return CommandSideEffect::Reload(ReloadMode::Restyle);
+                    }
+                }
+            }
+        };
+        CommandSideEffect::Redraw
+    }
+
+    fn try_reload(&mut self, path: &Path, mode: ReloadMode) -> RenderResult {
+        if matches!(self.options.mode, PresentMode::Presentation) && mode == ReloadMode::Development {
             return Ok(());
         }
         self.poller.send(PollerCommand::Reset);
         self.resources.clear_watches();
-        match self.load_presentation(path) {
+        match self.load_presentation(path, self.options.builder_options.clone()) {
             Ok(mut presentation) => {
                 let current = self.state.presentation();
-                if let Some(modification) = PresentationDiffer::find_first_modification(current, &presentation) {
+                let modification = match mode {
+                    ReloadMode::Restyle => None,
+                    _ => PresentationDiffer::find_first_modification(current, &presentation),
+                };
+                if let Some(modification) = modification {
                     presentation.go_to_slide(modification.slide_index);
                     presentation.jump_chunk(modification.chunk_index);
                 } else {
@@ -379,8 +1005,10 @@ impl<'a> Presenter<'a> {
                     presentation.jump_chunk(current.current_chunk());
                 }
                 self.start_automatic_async_renders(&mut presentation);
+                self.search.refresh(&presentation);
                 self.state = self.validate_overflows(presentation);
                 self.try_scale_transition_images()?;
+                self.try_load_preview(path);
             }
             Err(e) => {
                 let presentation = mem::take(&mut self.state).into_presentation();
@@ -414,6 +1042,14 @@ impl<'a> Presenter<'a> {
         pollables
     }
 
+    fn kill_slide_executions(presentation: &Presentation) {
+        for operation in presentation.current_slide().iter_visible_operations() {
+            if let RenderOperation::RenderAsync(operation) = operation {
+                operation.kill();
+            }
+        }
+    }
+
     fn is_displaying_other_error(&self) -> bool {
         matches!(self.state, PresenterState::Failure { mode: FailureMode::Other, .. })
     }
@@ -435,9 +1071,35 @@ impl<'a> Presenter<'a> {
         }
     }
 
-    fn load_presentation(&mut self, path: &Path) -> Result<Presentation, LoadPresentationError> {
+    fn try_load_preview(&mut self, path: &Path) {
+        let speaker_view = matches!(self.options.speaker_notes_layout, SpeakerNotesLayout::SpeakerView);
+        if !speaker_view || !self.options.builder_options.render_speaker_notes_only {
+            return;
+        }
+        let options =
+            PresentationBuilderOptions { render_speaker_notes_only: false, ..self.options.builder_options.clone() };
+        match self.load_presentation(path, options) {
+            Ok(mut preview) => {
+                for slide in preview.iter_slides_mut() {
+                    slide.show_all_chunks();
+                }
+                self.preview = Some(preview);
+                self.previews.clear();
+            }
+            Err(e) => {
+                let presentation = mem::take(&mut self.state).into_presentation();
+                self.state = PresenterState::failure(e, presentation, ErrorSource::Presentation, FailureMode::Other);
+            }
+        };
+    }
+
+    fn load_presentation(
+        &mut self,
+        path: &Path,
+        options: PresentationBuilderOptions,
+    ) -> Result<Presentation, LoadPresentationError> {
         let presentation = PresentationBuilder::new(
-            self.default_theme,
+            self.theme.as_ref().unwrap_or(self.default_theme),
             self.resources.clone(),
             &mut self.third_party,
             self.code_executor.clone(),
@@ -445,7 +1107,7 @@ impl<'a> Presenter<'a> {
             ImageRegistry::new(self.image_printer.clone()),
             self.options.bindings.clone(),
             &self.parser,
-            self.options.builder_options.clone(),
+            options,
         )?
         .build(path)?;
         Ok(presentation)
@@ -455,6 +1117,7 @@ impl<'a> Presenter<'a> {
         let state = mem::take(&mut self.state);
         match state {
             PresenterState::Presenting(presentation) | PresenterState::KeyBindings(presentation) => {
+                presentation.slide_index_search().stop();
                 self.state = PresenterState::SlideIndex(presentation)
             }
             PresenterState::SlideIndex(presentation) => self.state = PresenterState::Presenting(presentation),
@@ -462,17 +1125,146 @@ impl<'a> Presenter<'a> {
         }
     }
 
-    fn toggle_key_bindings(&mut self) {
+    fn toggle_overview(&mut self) {
         let state = mem::take(&mut self.state);
         match state {
-            PresenterState::Presenting(presentation) | PresenterState::SlideIndex(presentation) => {
-                self.state = PresenterState::KeyBindings(presentation)
+            PresenterState::Presenting(presentation)
+            | PresenterState::SlideIndex(presentation)
+            | PresenterState::KeyBindings(presentation) => self.state = PresenterState::Overview(presentation),
+            PresenterState::Overview(presentation) => self.state = PresenterState::Presenting(presentation),
+            other => self.state = other,
+        }
+    }
+
+    fn move_overview_selection(&mut self, direction: Direction) -> CommandSideEffect {
+        let PresenterState::Overview(presentation) = &mut self.state else {
+            return CommandSideEffect::None;
+        };
+        let Ok(dimensions) = WindowSize::current(self.options.font_size_fallback) else {
+            return CommandSideEffect::None;
+        };
+        let tiles_per_row = SlideOverview::tiles_per_row(dimensions.columns);
+        let current = presentation.current_slide_index();
+        let last = presentation.iter_slides().count().saturating_sub(1);
+        let next = match direction {
+            Direction::Left => current.checked_sub(1),
+            Direction::Right => Some(current + 1).filter(|next| *next <= last),
+            Direction::Up => current.checked_sub(tiles_per_row),
+            // Moving down into the last row lands on the last slide if there's nothing right below.
+            Direction::Down if current / tiles_per_row < last / tiles_per_row => {
+                Some((current + tiles_per_row).min(last))
+            }
+            Direction::Down => None,
+        };
+        match next {
+            Some(next) if presentation.go_to_slide(next) => CommandSideEffect::Redraw,
+            _ => CommandSideEffect::None,
+        }
+    }
+
+    fn toggle_annotations(&mut self) -> CommandSideEffect {
+        if self.annotations.is_enabled() {
+            self.annotations.disable();
+            return CommandSideEffect::Redraw;
+        }
+        let PresenterState::Presenting(presentation) = &self.state else {
+            return CommandSideEffect::None;
+        };
+        let Ok(dimensions) = WindowSize::current(self.options.font_size_fallback) else {
+            return CommandSideEffect::None;
+        };
+        if self.is_speaker_view() {
+            return CommandSideEffect::None;
+        }
+        self.annotations.enable(presentation.current_slide_index(), &dimensions);
+        CommandSideEffect::Redraw
+    }
+
+    fn apply_pointer_event(&mut self, event: PointerEvent) -> CommandSideEffect {
+        let Ok(dimensions) = WindowSize::current(self.options.font_size_fallback) else {
+            return CommandSideEffect::None;
+        };
+        match self.annotations.apply(event, &dimensions) {
+            true => CommandSideEffect::Redraw,
+            false => CommandSideEffect::None,
+        }
+    }
+
+    // Clicking on the slide being presented moves the presentation forwards or backwards depending on where the
+    // click happened.
+    fn resolve_click(&self, command: Command) -> Option<Command> {
+        let Command::Click { column, .. } = command else {
+            return Some(command);
+        };
+        if !matches!(self.state, PresenterState::Presenting(_)) {
+            return Some(command);
+        }
+        // Don't move around the presentation while someone is typing into a prompt.
+        if self.input_mode() == InputMode::TextInput {
+            return None;
+        }
+        let Ok(dimensions) = WindowSize::current(self.options.font_size_fallback) else {
+            return Some(command);
+        };
+        let previous_area = dimensions.columns as u32 * self.options.bindings.mouse.previous_area as u32 / 100;
+        if (column as u32) < previous_area { Some(Command::Previous) } else { Some(Command::Next) }
+    }
+
+    fn click(&mut self, column: u16, row: u16) -> CommandSideEffect {
+        let Ok(dimensions) = WindowSize::current(self.options.font_size_fallback) else {
+            return CommandSideEffect::None;
+        };
+        let slide = match &self.state {
+            PresenterState::SlideIndex(presentation) => {
+                let slide = presentation.slide_index().slide_at(&dimensions, column, row);
+                if slide.is_some() {
+                    presentation.slide_index_search().stop();
+                }
+                slide
             }
+            PresenterState::Overview(presentation) => {
+                presentation.overview().slide_at(presentation, &dimensions, column, row)
+            }
+            _ => None,
+        };
+        let Some(slide) = slide else {
+            return CommandSideEffect::None;
+        };
+        let presentation = mem::take(&mut self.state).into_presentation();
+        self.state = PresenterState::Presenting(presentation);
+        self.apply_command(Command::GoToSlide(slide as u32 + 1));
+        CommandSideEffect::Redraw
+    }
+
+    fn toggle_key_bindings(&mut self) {
+        let state = mem::take(&mut self.state);
+        match state {
+            PresenterState::Presenting(presentation)
+            | PresenterState::SlideIndex(presentation)
+            | PresenterState::Overview(presentation) => self.state = PresenterState::KeyBindings(presentation),
             PresenterState::KeyBindings(presentation) => self.state = PresenterState::Presenting(presentation),
             other => self.state = other,
         }
     }
 
+    fn toggle_theme_picker(&mut self) {
+        if self.is_speaker_view() {
+            return;
+        }
+        let state = mem::take(&mut self.state);
+        match state {
+            PresenterState::Presenting(presentation)
+            | PresenterState::SlideIndex(presentation)
+            | PresenterState::Overview(presentation)
+            | PresenterState::KeyBindings(presentation) => {
+                presentation.theme_picker().reset();
+                self.state = PresenterState::ThemePicker(presentation)
+            }
+            PresenterState::ThemePicker(presentation) => self.state = PresenterState::Presenting(presentation),
+            other => self.state = other,
+        }
+    }
+
     fn suspend(&self, drawer: &mut TerminalDrawer) {
         #[cfg(unix)]
         unsafe {
@@ -544,6 +1336,9 @@ impl<'a> Presenter<'a> {
             SlideTransitionStyleConfig::CollapseHorizontal => {
                 self.run_animation(drawer, first, CollapseHorizontalAnimation::new(left, right, direction), config)
             }
+            SlideTransitionStyleConfig::MagicMove => {
+                self.run_animation(drawer, first, MagicMoveAnimation::new(left, right, direction), config)
+            }
         }
     }
 
@@ -621,19 +1416,37 @@ enum CommandSideEffect {
     Exit,
     Suspend,
     Redraw,
-    Reload,
+    Reload(ReloadMode),
     AnimateNextSlide,
     AnimatePreviousSlide,
     None,
 }
 
+/// How to reload a presentation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ReloadMode {
+    /// Reload only when in development mode, jumping to the first slide that changed.
+    Development,
+
+    /// Always reload, jumping to the first slide that changed.
+    Forced,
+
+    /// Always reload, staying in the current slide.
+    ///
+    /// This is meant for changes that affect how the whole presentation looks, like switching themes, in which case
+    /// every slide would be considered modified.
+    Restyle,
+}
+
 #[derive(Default)]
 enum PresenterState {
     #[default]
     Empty,
     Presenting(Presentation),
     SlideIndex(Presentation),
+    Overview(Presentation),
     KeyBindings(Presentation),
+    ThemePicker(Presentation),
     Failure {
         error: String,
         presentation: Presentation,
@@ -656,7 +1469,9 @@ impl PresenterState {
         match self {
             Self::Presenting(presentation)
             | Self::SlideIndex(presentation)
+            | Self::Overview(presentation)
             | Self::KeyBindings(presentation)
+            | Self::ThemePicker(presentation)
             | Self::Failure { presentation, .. } => presentation,
             Self::Empty => panic!("state is empty"),
         }
@@ -666,17 +1481,30 @@ impl PresenterState {
         match self {
             Self::Presenting(presentation)
             | Self::SlideIndex(presentation)
+            | Self::Overview(presentation)
             | Self::KeyBindings(presentation)
+            | Self::ThemePicker(presentation)
             | Self::Failure { presentation, .. } => presentation,
             Self::Empty => panic!("state is empty"),
         }
     }
 
+    // Whether key presses are being used to type text in a modal.
+    fn is_typing(&self) -> bool {
+        match self {
+            Self::SlideIndex(presentation) => presentation.slide_index_search().is_active(),
+            Self::ThemePicker(_) => true,
+            _ => false,
+        }
+    }
+
     fn into_presentation(self) -> Presentation {
         match self {
             Self::Presenting(presentation)
             | Self::SlideIndex(presentation)
+            | Self::Overview(presentation)
             | Self::KeyBindings(presentation)
+            | Self::ThemePicker(presentation)
             | Self::Failure { presentation, .. } => presentation,
             Self::Empty => panic!("state is empty"),
         }
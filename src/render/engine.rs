@@ -3,7 +3,10 @@ use super::{
 };
 use crate::{
     config::{MaxColumnsAlignment, MaxRowsAlignment},
-    markdown::{text::WeightedLine, text_style::Colors},
+    markdown::{
+        text::WeightedLine,
+        text_style::{Colors, TextStyle},
+    },
     render::{
         operation::{
             AsRenderOperations, BlockLine, ImageRenderProperties, ImageSize, LayoutGrid, MarginProperties, RenderAsync,
@@ -44,10 +47,18 @@ impl Default for MaxSize {
     }
 }
 
+/// Text to be highlighted wherever it appears.
+#[derive(Clone, Debug)]
+pub(crate) struct TextHighlight {
+    pub(crate) query: String,
+    pub(crate) style: TextStyle,
+}
+
 #[derive(Clone, Default, Debug)]
 pub(crate) struct RenderEngineOptions {
     pub(crate) validate_overflows: bool,
     pub(crate) max_size: MaxSize,
+    pub(crate) highlight: Option<TextHighlight>,
 }
 
 pub(crate) struct RenderEngine<'a, T>
@@ -240,6 +251,8 @@ where
     }
 
     fn render_text(&mut self, text: &WeightedLine, alignment: Alignment) -> RenderResult {
+        let highlighted = self.highlight(text);
+        let text = highlighted.as_ref().unwrap_or(text);
         let layout = self.build_layout(alignment);
         let dimensions = self.current_dimensions();
         let positioning = layout.compute(dimensions, text.width() as u16);
@@ -252,6 +265,11 @@ where
         self.apply_colors()
     }
 
+    fn highlight(&self, text: &WeightedLine) -> Option<WeightedLine> {
+        let TextHighlight { query, style } = self.options.highlight.as_ref()?;
+        text.highlight(query, style)
+    }
+
     fn render_line_break(&mut self) -> RenderResult {
         self.terminal.execute(&TerminalCommand::MoveToNextLine)?;
         Ok(())
@@ -324,6 +342,8 @@ where
             right_padding_length,
             repeat_prefix_on_wrap,
         } = operation;
+        let highlighted = self.highlight(text);
+        let text = highlighted.as_ref().unwrap_or(text);
         let layout = self.build_layout(*alignment).with_font_size(text.font_size());
 
         let dimensions = self.current_dimensions();
@@ -535,7 +555,7 @@ impl WindowRect {
 mod tests {
     use super::*;
     use crate::{
-        markdown::text_style::{Color, TextStyle},
+        markdown::text_style::Color,
         terminal::{
             image::{
                 ImageSource,
@@ -543,12 +563,13 @@ mod tests {
                 scale::TerminalRect,
             },
             printer::TerminalError,
+            virt::VirtualTerminal,
         },
         theme::Margin,
     };
     use ::image::{ColorType, DynamicImage};
     use rstest::rstest;
-    use std::io;
+    use std::{io, iter};
     use unicode_width::UnicodeWidthStr;
 
     #[derive(Debug, PartialEq)]
@@ -654,6 +675,7 @@ mod tests {
                 MoveLeft(amount) => self.move_left(*amount)?,
                 MoveToNextLine => self.move_to_next_line()?,
                 PrintText { content, style } => self.print_text(content, style)?,
+                BeginHyperlink(_) | EndHyperlink => (),
                 ClearScreen => self.clear_screen()?,
                 SetColors(colors) => self.set_colors(*colors)?,
                 SetBackgroundColor(color) => self.set_background_color(*color)?,
@@ -697,7 +719,7 @@ mod tests {
     fn do_render(max_size: MaxSize, operations: &[RenderOperation]) -> Vec<Instruction> {
         let mut buf = TerminalBuf::default();
         let dimensions = WindowSize { rows: 100, columns: 100, height: 200, width: 200 };
-        let options = RenderEngineOptions { validate_overflows: false, max_size };
+        let options = RenderEngineOptions { validate_overflows: false, max_size, ..Default::default() };
         let mut engine = RenderEngine::new(&mut buf, dimensions, options);
         engine.image_scaler = Box::new(DummyImageScaler);
         engine.render(operations.iter()).expect("render failed");
@@ -751,6 +773,23 @@ mod tests {
         assert_eq!(ops, expected);
     }
 
+    #[test]
+    fn highlight() {
+        let dimensions = WindowSize { rows: 1, columns: 10, height: 0, width: 0 };
+        let mut term = VirtualTerminal::new(dimensions, Default::default());
+        let style = TextStyle::default().bold();
+        let highlight = TextHighlight { query: "LL".into(), style };
+        let options = RenderEngineOptions { highlight: Some(highlight), ..Default::default() };
+        let operation = RenderOperation::RenderText {
+            line: "hello".into(),
+            alignment: Alignment::Left { margin: Margin::Fixed(0) },
+        };
+        RenderEngine::new(&mut term, dimensions, options).render(iter::once(&operation)).expect("render failed");
+        let styles: Vec<_> = term.into_contents().rows[0].iter().take(5).map(|c| c.style).collect();
+        let plain = TextStyle::default();
+        assert_eq!(styles, &[plain, plain, style, style, plain]);
+    }
+
     #[test]
     fn bottom_margin() {
         let ops = render(&[
@@ -18,14 +18,16 @@ use crate::{
         ansi::AnsiParser,
         image::printer::{ImagePrinter, PrintImageError},
         printer::TerminalError,
+        record::{AsciicastRecorder, RecordingWriter},
     },
     theme::Margin,
 };
-use engine::{MaxSize, RenderEngine, RenderEngineOptions};
+use engine::{MaxSize, RenderEngine, RenderEngineOptions, TextHighlight};
 use operation::{AsRenderOperations, MarginProperties};
 use std::{
     io::{self, Stdout},
     iter,
+    path::PathBuf,
     rc::Rc,
     sync::Arc,
 };
@@ -36,23 +38,32 @@ pub(crate) type RenderResult = Result<(), RenderError>;
 pub(crate) struct TerminalDrawerOptions {
     pub(crate) font_size_fallback: u8,
     pub(crate) max_size: MaxSize,
+    pub(crate) record_path: Option<PathBuf>,
 }
 
 impl Default for TerminalDrawerOptions {
     fn default() -> Self {
-        Self { font_size_fallback: 1, max_size: Default::default() }
+        Self { font_size_fallback: 1, max_size: Default::default(), record_path: None }
     }
 }
 
 /// Allows drawing on the terminal.
 pub(crate) struct TerminalDrawer {
-    pub(crate) terminal: Terminal<Stdout>,
+    pub(crate) terminal: Terminal<RecordingWriter<Stdout>>,
     options: TerminalDrawerOptions,
 }
 
 impl TerminalDrawer {
     pub(crate) fn new(image_printer: Arc<ImagePrinter>, options: TerminalDrawerOptions) -> io::Result<Self> {
-        let terminal = Terminal::new(io::stdout(), image_printer)?;
+        let recorder = match &options.record_path {
+            Some(path) => {
+                let dimensions = WindowSize::current(options.font_size_fallback)?;
+                Some(AsciicastRecorder::create(path, dimensions.columns, dimensions.rows)?)
+            }
+            None => None,
+        };
+        let writer = RecordingWriter::new(io::stdout(), recorder);
+        let terminal = Terminal::new(writer, image_printer)?;
         Ok(Self { terminal, options })
     }
 
@@ -66,6 +77,19 @@ impl TerminalDrawer {
         Ok(())
     }
 
+    /// Render operations, highlighting every occurrence of some text in them.
+    pub(crate) fn render_highlighted_operations<'a>(
+        &mut self,
+        operations: impl Iterator<Item = &'a RenderOperation>,
+        highlight: TextHighlight,
+    ) -> RenderResult {
+        let dimensions = WindowSize::current(self.options.font_size_fallback)?;
+        let options = RenderEngineOptions { highlight: Some(highlight), ..self.render_engine_options() };
+        let engine = RenderEngine::new(&mut self.terminal, dimensions, options);
+        engine.render(operations)?;
+        Ok(())
+    }
+
     pub(crate) fn render_error(&mut self, message: &str, source: &ErrorSource) -> RenderResult {
         let (lines, _) = AnsiParser::new(Default::default()).parse_lines(message.lines());
         let lines = lines.into_iter().map(Into::into).collect();
@@ -81,7 +105,7 @@ impl TerminalDrawer {
         RenderEngineOptions { max_size: self.options.max_size.clone(), ..Default::default() }
     }
 
-    fn create_engine(&mut self, dimensions: WindowSize) -> RenderEngine<'_, Terminal<Stdout>> {
+    fn create_engine(&mut self, dimensions: WindowSize) -> RenderEngine<'_, Terminal<RecordingWriter<Stdout>>> {
         let options = self.render_engine_options();
         RenderEngine::new(&mut self.terminal, dimensions, options)
     }
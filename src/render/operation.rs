@@ -1,5 +1,6 @@
 use super::properties::WindowSize;
 use crate::{
+    code::execute::ProcessInput,
     markdown::{
         text::{WeightedLine, WeightedText},
         text_style::{Color, Colors, TextStyle},
@@ -185,6 +186,17 @@ pub(crate) trait RenderAsync: AsRenderOperations {
     fn start_policy(&self) -> RenderAsyncStartPolicy {
         RenderAsyncStartPolicy::OnDemand
     }
+
+    /// Whether this render is running a process that reads input typed by the presenter.
+    fn accepts_input(&self) -> bool {
+        false
+    }
+
+    /// Send input typed by the presenter to the process this render is running.
+    fn send_input(&self, _input: ProcessInput) {}
+
+    /// Kill the process this render is running, if any.
+    fn kill(&self) {}
 }
 
 /// The start policy for an async render.
@@ -89,7 +89,7 @@ impl<'a> TextDrawer<'a> {
 
         // Print the prefix at the beginning of the line.
         if self.prefix_width > 0 {
-            let Text { content, style } = self.prefix.text();
+            let Text { content, style, .. } = self.prefix.text();
             terminal.execute(&TerminalCommand::PrintText { content, style: *style })?;
         }
         for (line_index, line) in self.line.split(self.positioning.max_line_length as usize).enumerate() {
@@ -111,7 +111,7 @@ impl<'a> TextDrawer<'a> {
                 // Complete the new line in this block to the left where the prefix would be.
                 if self.prefix_width > 0 {
                     if self.repeat_prefix {
-                        let Text { content, style } = self.prefix.text();
+                        let Text { content, style, .. } = self.prefix.text();
                         terminal.execute(&TerminalCommand::PrintText { content, style: *style })?;
                     } else {
                         if let Some(color) = self.block_color {
@@ -126,8 +126,15 @@ impl<'a> TextDrawer<'a> {
             for chunk in line {
                 line_length = line_length.saturating_add(chunk.width() as u16);
 
+                let link = chunk.link();
                 let (text, style) = chunk.into_parts();
+                if let Some(url) = link {
+                    terminal.execute(&TerminalCommand::BeginHyperlink(url))?;
+                }
                 terminal.execute(&TerminalCommand::PrintText { content: text, style })?;
+                if link.is_some() {
+                    terminal.execute(&TerminalCommand::EndHyperlink)?;
+                }
 
                 // Crossterm resets colors if any attributes are set so let's just re-apply colors
                 // if the format has anything on it at all.
@@ -240,6 +247,7 @@ mod tests {
                 MoveToColumn(column) => self.move_to_column(*column)?,
                 MoveDown(amount) => self.move_down(*amount)?,
                 PrintText { content, style } => self.print_text(content, style)?,
+                BeginHyperlink(_) | EndHyperlink => (),
                 ClearScreen => self.clear_screen()?,
                 SetColors(colors) => self.set_colors(*colors)?,
                 SetBackgroundColor(color) => self.set_background_color(*color)?,
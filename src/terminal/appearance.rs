@@ -0,0 +1,124 @@
+use super::capabilities::TerminalCapabilities;
+use std::{
+    io::{self, Write},
+    time::{Duration, Instant},
+};
+
+/// Whether the terminal is using a dark or a light background.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Appearance {
+    Dark,
+    Light,
+}
+
+impl Appearance {
+    /// Query the terminal for its background color and classify it.
+    ///
+    /// This blocks until the terminal replies so it must only be used before any input is being read. Returns `None`
+    /// if the terminal doesn't reply within the given timeout.
+    pub(crate) fn detect(timeout: Duration) -> Option<Self> {
+        match termbg::theme(timeout).ok()? {
+            termbg::Theme::Dark => Some(Self::Dark),
+            termbg::Theme::Light => Some(Self::Light),
+        }
+    }
+
+    /// Classify the terminal's reply to a background color query, e.g. `11;rgb:ffff/ffff/ffff`.
+    pub(crate) fn from_background_reply(reply: &str) -> Option<Self> {
+        let color = reply.strip_prefix("11;rgb:")?;
+        let mut components = color.split('/').map(Self::parse_component);
+        let (red, green, blue) = (components.next()??, components.next()??, components.next()??);
+        if components.next().is_some() {
+            return None;
+        }
+        // Perceived brightness, same as what's used when detecting it on launch.
+        let luminance = 0.299 * red + 0.587 * green + 0.114 * blue;
+        if luminance > 0.5 { Some(Self::Light) } else { Some(Self::Dark) }
+    }
+
+    // Parse a color component, which uses 1 to 4 hex digits, into the [0, 1] range.
+    fn parse_component(component: &str) -> Option<f64> {
+        if component.is_empty() || component.len() > 4 {
+            return None;
+        }
+        let value = u16::from_str_radix(component, 16).ok()?;
+        let max = (1_u32 << (component.len() * 4)) - 1;
+        Some(value as f64 / max as f64)
+    }
+}
+
+/// Periodically asks the terminal for its background color to notice when it switches between dark and light.
+///
+/// Terminals that follow the system's appearance can switch at any time. Replies to the queries sent by this type show
+/// up as input and are turned into [crate::commands::listener::Command::UpdateAppearance] by the keyboard listener,
+/// which means input is never blocked while waiting for them.
+#[derive(Debug)]
+pub struct AppearanceWatcher {
+    appearance: Appearance,
+    interval: Duration,
+    last_query: Instant,
+}
+
+impl AppearanceWatcher {
+    pub(crate) fn new(appearance: Appearance, interval: Duration) -> Self {
+        Self { appearance, interval, last_query: Instant::now() }
+    }
+
+    /// The last appearance the terminal was found to be using.
+    pub(crate) fn appearance(&self) -> Appearance {
+        self.appearance
+    }
+
+    /// Query the terminal for its background color if the interval since the last query has elapsed.
+    ///
+    /// Returns whether a query was sent, in which case a reply should be expected as input.
+    pub(crate) fn poll(&mut self) -> io::Result<bool> {
+        if self.last_query.elapsed() < self.interval {
+            return Ok(false);
+        }
+        self.last_query = Instant::now();
+        let mut stdout = io::stdout();
+        match TerminalCapabilities::is_inside_tmux() {
+            true => write!(stdout, "\x1bPtmux;\x1b\x1b]11;?\x07\x1b\\")?,
+            false => write!(stdout, "\x1b]11;?\x1b\\")?,
+        };
+        stdout.flush()?;
+        Ok(true)
+    }
+
+    /// Update the terminal's appearance, returning it if it's different from the last one.
+    pub(crate) fn update(&mut self, appearance: Appearance) -> Option<Appearance> {
+        if appearance == self.appearance {
+            return None;
+        }
+        self.appearance = appearance;
+        Some(appearance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::black("11;rgb:0000/0000/0000", Some(Appearance::Dark))]
+    #[case::white("11;rgb:ffff/ffff/ffff", Some(Appearance::Light))]
+    #[case::short_components("11;rgb:ee/ee/dd", Some(Appearance::Light))]
+    #[case::dark_blue("11;rgb:1e1e/1e1e/2e2e", Some(Appearance::Dark))]
+    #[case::foreground("10;rgb:ffff/ffff/ffff", None)]
+    #[case::missing_component("11;rgb:ffff/ffff", None)]
+    #[case::extra_component("11;rgb:ffff/ffff/ffff/ffff", None)]
+    #[case::invalid("11;rgb:zzzz/ffff/ffff", None)]
+    fn background_reply(#[case] reply: &str, #[case] expected: Option<Appearance>) {
+        assert_eq!(Appearance::from_background_reply(reply), expected);
+    }
+
+    #[test]
+    fn update() {
+        let mut watcher = AppearanceWatcher::new(Appearance::Dark, Duration::ZERO);
+        assert_eq!(watcher.update(Appearance::Dark), None);
+        assert_eq!(watcher.update(Appearance::Light), Some(Appearance::Light));
+        assert_eq!(watcher.appearance(), Appearance::Light);
+    }
+}
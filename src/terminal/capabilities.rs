@@ -1,4 +1,7 @@
-use super::image::protocols::kitty::{Action, ControlCommand, ControlOption, ImageFormat, TransmissionMedium};
+use super::{
+    emulator::TerminalEmulator,
+    image::protocols::kitty::{Action, ControlCommand, ControlOption, ImageFormat, TransmissionMedium},
+};
 use base64::{Engine, engine::general_purpose::STANDARD};
 use crossterm::{
     QueueableCommand,
@@ -27,6 +30,7 @@ pub(crate) struct TerminalCapabilities {
     pub(crate) tmux: bool,
     pub(crate) font_size: bool,
     pub(crate) fractional_font_size: bool,
+    pub(crate) hyperlinks: bool,
 }
 
 impl TerminalCapabilities {
@@ -68,6 +72,7 @@ impl TerminalCapabilities {
 
         let mut response = response?;
         response.tmux = tmux;
+        response.hyperlinks = !tmux && TerminalEmulator::detect().supports_hyperlinks();
         Ok(response)
     }
 
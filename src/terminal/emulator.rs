@@ -66,6 +66,21 @@ impl TerminalEmulator {
         unreachable!("ascii blocks is always supported")
     }
 
+    /// Whether this terminal supports OSC 8 hyperlinks.
+    pub(crate) fn supports_hyperlinks(&self) -> bool {
+        match self {
+            Self::Iterm2 | Self::WezTerm | Self::Ghostty | Self::Mintty | Self::Kitty | Self::Konsole | Self::Foot => {
+                true
+            }
+            // VTE based terminals (e.g. gnome terminal) support them since 0.50, as do windows terminal and vscode.
+            Self::Yaft | Self::Mlterm | Self::St | Self::Xterm | Self::Unknown => {
+                env::var("VTE_VERSION").ok().and_then(|v| v.parse::<u32>().ok()).is_some_and(|v| v >= 5000)
+                    || env::var("WT_SESSION").is_ok()
+                    || env::var("TERM_PROGRAM").is_ok_and(|p| p == "vscode")
+            }
+        }
+    }
+
     fn is_detected(&self, term: &str, term_program: &str) -> bool {
         match self {
             TerminalEmulator::Iterm2 => {
@@ -3,7 +3,7 @@ use crate::terminal::{
     printer::TerminalIo,
 };
 use base64::{Engine, engine::general_purpose::STANDARD};
-use image::{GenericImageView, ImageEncoder, ImageFormat, codecs::png::PngEncoder};
+use image::{DynamicImage, GenericImageView, ImageEncoder, ImageFormat, ImageResult, codecs::png::PngEncoder};
 use std::fs;
 
 pub(crate) struct RawImage {
@@ -19,6 +19,10 @@ impl RawImage {
         let data = STANDARD.encode(&self.contents);
         format!("data:{mime_type};base64,{data}")
     }
+
+    pub(crate) fn to_image(&self) -> ImageResult<DynamicImage> {
+        image::load_from_memory_with_format(&self.contents, self.format)
+    }
 }
 
 impl ImageProperties for RawImage {
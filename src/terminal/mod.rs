@@ -1,8 +1,10 @@
 pub(crate) mod ansi;
+pub(crate) mod appearance;
 pub(crate) mod capabilities;
 pub(crate) mod emulator;
 pub(crate) mod image;
 pub(crate) mod printer;
+pub(crate) mod record;
 pub(crate) mod virt;
 
 pub(crate) use printer::{Terminal, TerminalWrite, should_hide_cursor};
@@ -7,10 +7,11 @@ use crate::{
     },
 };
 use crossterm::{
-    QueueableCommand, cursor, style,
+    QueueableCommand, cursor, event, style,
     terminal::{self},
 };
 use std::{
+    borrow::Cow,
     io::{self, Write},
     sync::Arc,
 };
@@ -27,6 +28,8 @@ pub(crate) enum TerminalCommand<'a> {
     MoveLeft(u16),
     MoveToNextLine,
     PrintText { content: &'a str, style: TextStyle },
+    BeginHyperlink(&'a str),
+    EndHyperlink,
     ClearScreen,
     SetColors(Colors),
     SetBackgroundColor(Color),
@@ -59,6 +62,8 @@ pub(crate) struct Terminal<I: TerminalWrite> {
     last_cleared_background_color: Option<Color>,
     background_color: Option<Color>,
     osc11_background: bool,
+    hyperlinks: bool,
+    mouse_capture: bool,
 }
 
 impl<I: TerminalWrite> Terminal<I> {
@@ -74,6 +79,8 @@ impl<I: TerminalWrite> Terminal<I> {
             background_color: None,
             // Only use OSC11 when outside of tmux temporarily since it somehow breaks under kitty
             osc11_background: !TerminalEmulator::capabilities().tmux,
+            hyperlinks: TerminalEmulator::capabilities().hyperlinks,
+            mouse_capture: false,
         })
     }
 
@@ -140,6 +147,21 @@ impl<I: TerminalWrite> Terminal<I> {
         Ok(())
     }
 
+    fn begin_hyperlink(&mut self, url: &str) -> io::Result<()> {
+        if self.hyperlinks {
+            let url = escape_hyperlink_url(url);
+            write!(self.writer, "\x1b]8;;{url}\x1b\\")?;
+        }
+        Ok(())
+    }
+
+    fn end_hyperlink(&mut self) -> io::Result<()> {
+        if self.hyperlinks {
+            write!(self.writer, "\x1b]8;;\x1b\\")?;
+        }
+        Ok(())
+    }
+
     fn clear_screen(&mut self) -> io::Result<()> {
         if self.osc11_background {
             match (self.last_cleared_background_color, self.background_color) {
@@ -193,12 +215,36 @@ impl<I: TerminalWrite> Terminal<I> {
         Ok(())
     }
 
+    /// Enable or disable capturing mouse events.
+    pub(crate) fn set_mouse_capture(&mut self, enabled: bool) -> io::Result<()> {
+        if self.mouse_capture != enabled {
+            self.queue_mouse_capture(enabled)?;
+            self.writer.flush()?;
+            self.mouse_capture = enabled;
+        }
+        Ok(())
+    }
+
+    fn queue_mouse_capture(&mut self, enabled: bool) -> io::Result<()> {
+        match enabled {
+            true => self.writer.queue(event::EnableMouseCapture)?,
+            false => self.writer.queue(event::DisableMouseCapture)?,
+        };
+        Ok(())
+    }
+
     pub(crate) fn suspend(&mut self) {
+        if self.mouse_capture {
+            let _ = self.queue_mouse_capture(false);
+        }
         self.writer.deinit();
     }
 
     pub(crate) fn resume(&mut self) {
         let _ = self.writer.init();
+        if self.mouse_capture {
+            let _ = self.queue_mouse_capture(true);
+        }
     }
 }
 
@@ -216,6 +262,8 @@ impl<I: TerminalWrite> TerminalIo for Terminal<I> {
             MoveLeft(amount) => self.move_left(*amount)?,
             MoveToNextLine => self.move_to_next_line()?,
             PrintText { content, style } => self.print_text(content, style)?,
+            BeginHyperlink(url) => self.begin_hyperlink(url)?,
+            EndHyperlink => self.end_hyperlink()?,
             ClearScreen => self.clear_screen()?,
             SetColors(colors) => self.set_colors(*colors)?,
             SetBackgroundColor(color) => self.set_background_color(*color)?,
@@ -238,6 +286,9 @@ impl<I: TerminalWrite> Drop for Terminal<I> {
                 let _ = write!(self.writer, "\x1b]111\x1b\\");
             }
         }
+        if self.mouse_capture {
+            let _ = self.queue_mouse_capture(false);
+        }
         self.writer.deinit();
     }
 }
@@ -250,6 +301,26 @@ pub(crate) fn should_hide_cursor() -> bool {
     !(is_windows_based_os() && is_wezterm)
 }
 
+// Control characters in a URL would otherwise terminate the OSC 8 sequence early and let the rest of it be
+// interpreted as arbitrary escape sequences, so percent-encode them.
+fn escape_hyperlink_url(url: &str) -> Cow<'_, str> {
+    if !url.chars().any(char::is_control) {
+        return Cow::Borrowed(url);
+    }
+    let mut escaped = String::with_capacity(url.len());
+    for c in url.chars() {
+        if c.is_control() {
+            let mut buffer = [0; 4];
+            for byte in c.encode_utf8(&mut buffer).bytes() {
+                escaped.push_str(&format!("%{byte:02X}"));
+            }
+        } else {
+            escaped.push(c);
+        }
+    }
+    Cow::Owned(escaped)
+}
+
 fn is_windows_based_os() -> bool {
     let is_windows = std::env::consts::OS == "windows";
     let is_wsl = std::env::var("WSL_DISTRO_NAME").is_ok();
@@ -280,3 +351,45 @@ impl TerminalWrite for io::Stdout {
         let _ = terminal::disable_raw_mode();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    impl TerminalWrite for Vec<u8> {
+        fn init(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn deinit(&mut self) {}
+    }
+
+    #[rstest]
+    #[case::plain("https://example.com/a?b=c", "https://example.com/a?b=c")]
+    #[case::escape("https://example.com/\x1b]0;pwned\x07", "https://example.com/%1B]0;pwned%07")]
+    #[case::c1("https://example.com/\u{9c}", "https://example.com/%C2%9C")]
+    #[case::unicode("https://example.com/ñ", "https://example.com/ñ")]
+    fn escape_url(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(escape_hyperlink_url(input), expected);
+    }
+
+    #[test]
+    fn hyperlink_with_escape() {
+        let mut terminal = Terminal {
+            writer: Vec::new(),
+            image_printer: Default::default(),
+            cursor_row: 0,
+            current_row_height: 1,
+            rows: u16::MAX,
+            last_cleared_background_color: None,
+            background_color: None,
+            osc11_background: false,
+            hyperlinks: true,
+            mouse_capture: false,
+        };
+        terminal.execute(&TerminalCommand::BeginHyperlink("https://example.com/\x1b\\\x1b[2J")).unwrap();
+        let output = String::from_utf8(terminal.writer.clone()).unwrap();
+        assert_eq!(output, "\x1b]8;;https://example.com/%1B\\%1B[2J\x1b\\");
+    }
+}
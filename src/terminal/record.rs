@@ -0,0 +1,373 @@
+use super::printer::TerminalWrite;
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
+    terminal,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::Path,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+const ASCIICAST_VERSION: u8 = 2;
+
+/// Records everything written to the terminal as an [asciicast v2](https://docs.asciinema.org/manual/asciicast/v2/)
+/// file.
+pub(crate) struct AsciicastRecorder {
+    writer: Box<dyn Write>,
+    started_at: Instant,
+    pending: Vec<u8>,
+    dimensions: (u16, u16),
+}
+
+impl AsciicastRecorder {
+    /// Create a recording in the given path.
+    pub(crate) fn create(path: &Path, columns: u16, rows: u16) -> io::Result<Self> {
+        let writer = BufWriter::new(File::create(path)?);
+        Self::new(Box::new(writer), columns, rows)
+    }
+
+    fn new(mut writer: Box<dyn Write>, columns: u16, rows: u16) -> io::Result<Self> {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|t| t.as_secs()).unwrap_or_default();
+        let header =
+            AsciicastHeader { version: ASCIICAST_VERSION, width: columns, height: rows, timestamp: Some(timestamp) };
+        writeln!(writer, "{}", serde_json::to_string(&header).expect("serialization failed"))?;
+        Ok(Self { writer, started_at: Instant::now(), pending: Vec::new(), dimensions: (columns, rows) })
+    }
+
+    fn record_output(&mut self, data: &[u8]) {
+        self.pending.extend_from_slice(data);
+    }
+
+    fn resize(&mut self, columns: u16, rows: u16) -> io::Result<()> {
+        if self.dimensions == (columns, rows) {
+            return Ok(());
+        }
+        self.dimensions = (columns, rows);
+        self.write_event(EventKind::Resize, format!("{columns}x{rows}"))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let data = Self::take_complete_utf8(&mut self.pending);
+        if !data.is_empty() {
+            self.write_event(EventKind::Output, data)?;
+        }
+        self.writer.flush()
+    }
+
+    fn write_event(&mut self, kind: EventKind, data: String) -> io::Result<()> {
+        let event = AsciicastEvent { time: self.started_at.elapsed().as_secs_f64(), kind, data };
+        writeln!(self.writer, "{}", serde_json::to_string(&event).expect("serialization failed"))
+    }
+
+    // Takes all complete UTF-8 sequences in the buffer, leaving any partial one behind for the next event.
+    fn take_complete_utf8(buffer: &mut Vec<u8>) -> String {
+        let split = match std::str::from_utf8(buffer) {
+            Ok(_) => buffer.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(_) => buffer.len(),
+        };
+        let rest = buffer.split_off(split);
+        let data = String::from_utf8_lossy(buffer).into_owned();
+        *buffer = rest;
+        data
+    }
+}
+
+/// A terminal writer that optionally records everything written into it.
+///
+/// This records the bytes that [TerminalCommand](super::printer::TerminalCommand)s are turned into rather than the
+/// commands themselves. asciicast files contain raw terminal output so commands would need to be encoded into escape
+/// sequences exactly like the terminal does, and images are only turned into bytes by the image printer.
+pub(crate) struct RecordingWriter<W> {
+    inner: W,
+    recorder: Option<AsciicastRecorder>,
+}
+
+impl<W> RecordingWriter<W> {
+    pub(crate) fn new(inner: W, recorder: Option<AsciicastRecorder>) -> Self {
+        Self { inner, recorder }
+    }
+}
+
+impl<W: Write> Write for RecordingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        if let Some(recorder) = &mut self.recorder {
+            recorder.record_output(&buf[0..written]);
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()?;
+        if let Some(recorder) = &mut self.recorder {
+            if let Ok((columns, rows)) = terminal::size() {
+                recorder.resize(columns, rows)?;
+            }
+            recorder.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: TerminalWrite> TerminalWrite for RecordingWriter<W> {
+    fn init(&mut self) -> io::Result<()> {
+        self.inner.init()
+    }
+
+    fn deinit(&mut self) {
+        self.inner.deinit()
+    }
+}
+
+/// Replays an asciicast file.
+pub struct AsciicastPlayer {
+    events: Vec<AsciicastEvent>,
+}
+
+impl AsciicastPlayer {
+    /// Load the recording in the given path.
+    pub fn load(path: &Path) -> Result<Self, ReplayError> {
+        let reader = BufReader::new(File::open(path)?);
+        Self::parse(reader)
+    }
+
+    fn parse<R: BufRead>(reader: R) -> Result<Self, ReplayError> {
+        let mut lines = reader.lines();
+        let header = lines.next().ok_or(ReplayError::Empty)??;
+        let header: AsciicastHeader = serde_json::from_str(&header).map_err(ReplayError::InvalidHeader)?;
+        if header.version != ASCIICAST_VERSION {
+            return Err(ReplayError::UnsupportedVersion(header.version));
+        }
+        let mut events = Vec::new();
+        for (index, line) in lines.enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            // Index 0 is the header and lines start at 1.
+            let line_number = index + 2;
+            let event = serde_json::from_str(&line).map_err(|e| ReplayError::InvalidEvent(line_number, e))?;
+            events.push(event);
+        }
+        Ok(Self { events })
+    }
+
+    /// Play this recording in the terminal.
+    ///
+    /// Playback can be stopped at any time by pressing `q`, `<esc>`, or `<c-c>`.
+    pub fn play(&self) -> Result<(), ReplayError> {
+        let mut stdout = io::stdout();
+        stdout.init()?;
+        let result = self.play_events(&mut stdout);
+        stdout.deinit();
+        result
+    }
+
+    fn play_events<W: Write>(&self, writer: &mut W) -> Result<(), ReplayError> {
+        let started_at = Instant::now();
+        for event in &self.events {
+            let target = Duration::from_secs_f64(event.time.max(0.0));
+            if let Some(remaining) = target.checked_sub(started_at.elapsed()) {
+                if Self::wait_for_exit(Some(remaining))? {
+                    return Ok(());
+                }
+            }
+            if let EventKind::Output = event.kind {
+                writer.write_all(event.data.as_bytes())?;
+                writer.flush()?;
+            }
+        }
+        // Keep the last frame around until the user wants to leave.
+        Self::wait_for_exit(None)?;
+        Ok(())
+    }
+
+    // Waits for up to the given timeout and returns whether the user wants to exit.
+    fn wait_for_exit(timeout: Option<Duration>) -> io::Result<bool> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        loop {
+            let poll_timeout = match deadline {
+                Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                    Some(timeout) => timeout,
+                    None => return Ok(false),
+                },
+                None => Duration::from_secs(1),
+            };
+            if !event::poll(poll_timeout)? {
+                continue;
+            }
+            if let Event::Key(KeyEvent { code, modifiers, .. }) = event::read()? {
+                let exit = matches!(code, KeyCode::Char('q') | KeyCode::Esc)
+                    || (code == KeyCode::Char('c') && modifiers.contains(KeyModifiers::CONTROL));
+                if exit {
+                    return Ok(true);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReplayError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    #[error("recording is empty")]
+    Empty,
+
+    #[error("invalid recording header: {0}")]
+    InvalidHeader(serde_json::Error),
+
+    #[error("unsupported asciicast version {0}, only version 2 is supported")]
+    UnsupportedVersion(u8),
+
+    #[error("invalid event in line {0}: {1}")]
+    InvalidEvent(usize, serde_json::Error),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AsciicastHeader {
+    version: u8,
+    width: u16,
+    height: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timestamp: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum EventKind {
+    Output,
+    Input,
+    Resize,
+    Marker,
+}
+
+impl EventKind {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::Output => "o",
+            Self::Input => "i",
+            Self::Resize => "r",
+            Self::Marker => "m",
+        }
+    }
+
+    fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "o" => Some(Self::Output),
+            "i" => Some(Self::Input),
+            "r" => Some(Self::Resize),
+            "m" => Some(Self::Marker),
+            _ => None,
+        }
+    }
+}
+
+// Events are encoded as `[time, code, data]` arrays.
+#[derive(Clone, Debug, PartialEq)]
+struct AsciicastEvent {
+    time: f64,
+    kind: EventKind,
+    data: String,
+}
+
+impl Serialize for AsciicastEvent {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.time, self.kind.code(), &self.data).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for AsciicastEvent {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (time, code, data) = <(f64, String, String)>::deserialize(deserializer)?;
+        let kind = EventKind::from_code(&code)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown event type '{code}'")))?;
+        Ok(Self { time, kind, data })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn record_and_parse() {
+        let buffer = SharedBuffer::default();
+        let mut recorder = AsciicastRecorder::new(Box::new(buffer.clone()), 80, 24).expect("create failed");
+        recorder.record_output(b"hello ");
+        recorder.record_output("wörld".as_bytes());
+        recorder.flush().expect("flush failed");
+        recorder.resize(80, 24).expect("resize failed");
+        recorder.resize(100, 30).expect("resize failed");
+        // Nothing to flush.
+        recorder.flush().expect("flush failed");
+
+        let contents = buffer.0.borrow().clone();
+        let header = contents.split(|c| *c == b'\n').next().expect("no header");
+        let header: AsciicastHeader = serde_json::from_slice(header).expect("invalid header");
+        assert_eq!((header.version, header.width, header.height), (2, 80, 24));
+        let player = AsciicastPlayer::parse(contents.as_slice()).expect("parse failed");
+        let events: Vec<_> = player.events.iter().map(|e| (e.kind.clone(), e.data.as_str())).collect();
+        assert_eq!(events, &[(EventKind::Output, "hello wörld"), (EventKind::Resize, "100x30")]);
+    }
+
+    #[test]
+    fn partial_utf8_kept() {
+        let data = "ö".as_bytes();
+        let mut buffer = vec![b'a', data[0]];
+        assert_eq!(AsciicastRecorder::take_complete_utf8(&mut buffer), "a");
+        assert_eq!(buffer, &[data[0]]);
+
+        buffer.push(data[1]);
+        assert_eq!(AsciicastRecorder::take_complete_utf8(&mut buffer), "ö");
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn writer_records() {
+        let buffer = SharedBuffer::default();
+        let recorder = AsciicastRecorder::new(Box::new(buffer.clone()), 80, 24).expect("create failed");
+        let mut writer = RecordingWriter::new(Vec::new(), Some(recorder));
+        writer.write_all(b"hi").expect("write failed");
+        writer.flush().expect("flush failed");
+
+        assert_eq!(writer.inner, b"hi");
+        let contents = buffer.0.borrow().clone();
+        let player = AsciicastPlayer::parse(contents.as_slice()).expect("parse failed");
+        let output: Vec<_> =
+            player.events.iter().filter(|e| e.kind == EventKind::Output).map(|e| e.data.as_str()).collect();
+        assert_eq!(output, &["hi"]);
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let input = r#"{"version": 1, "width": 80, "height": 24}"#;
+        let result = AsciicastPlayer::parse(input.as_bytes());
+        assert!(matches!(result, Err(ReplayError::UnsupportedVersion(1))));
+    }
+
+    #[test]
+    fn invalid_event() {
+        let input = "{\"version\": 2, \"width\": 80, \"height\": 24}\n[0.5, \"x\", \"hi\"]";
+        let result = AsciicastPlayer::parse(input.as_bytes());
+        assert!(matches!(result, Err(ReplayError::InvalidEvent(2, _))));
+    }
+}
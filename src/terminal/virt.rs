@@ -55,6 +55,7 @@ pub(crate) struct TerminalGrid {
     pub(crate) rows: Vec<Vec<StyledChar>>,
     pub(crate) background_color: Option<Color>,
     pub(crate) images: HashMap<(u16, u16), PrintedImage>,
+    pub(crate) links: HashMap<(u16, u16), String>,
 }
 
 pub(crate) struct VirtualTerminal {
@@ -64,6 +65,8 @@ pub(crate) struct VirtualTerminal {
     rows: Vec<Vec<StyledChar>>,
     background_color: Option<Color>,
     images: HashMap<(u16, u16), PrintedImage>,
+    links: HashMap<(u16, u16), String>,
+    link: Option<String>,
     row_heights: Vec<u16>,
     image_behavior: ImageBehavior,
 }
@@ -79,13 +82,29 @@ impl VirtualTerminal {
             rows,
             background_color: None,
             images: Default::default(),
+            links: Default::default(),
+            link: None,
             row_heights,
             image_behavior,
         }
     }
 
     pub(crate) fn into_contents(self) -> TerminalGrid {
-        TerminalGrid { rows: self.rows, background_color: self.background_color, images: self.images }
+        TerminalGrid {
+            rows: self.rows,
+            background_color: self.background_color,
+            images: self.images,
+            links: self.links,
+        }
+    }
+
+    pub(crate) fn contents(&self) -> TerminalGrid {
+        TerminalGrid {
+            rows: self.rows.clone(),
+            background_color: self.background_color,
+            images: self.images.clone(),
+            links: self.links.clone(),
+        }
     }
 
     fn current_cell_mut(&mut self) -> Option<&mut StyledChar> {
@@ -150,6 +169,11 @@ impl VirtualTerminal {
             };
             cell.character = c;
             cell.style = style;
+            let key = (self.row, self.column);
+            match &self.link {
+                Some(link) => self.links.insert(key, link.clone()),
+                None => self.links.remove(&key),
+            };
             self.column += style.size as u16;
         }
         let height = self.current_row_height().max(style.size as u16);
@@ -163,10 +187,19 @@ impl VirtualTerminal {
                 cell.character = ' ';
             }
         }
+        self.links.clear();
         self.background_color = self.colors.background;
         Ok(())
     }
 
+    fn begin_hyperlink(&mut self, url: &str) {
+        self.link = Some(url.to_string());
+    }
+
+    fn end_hyperlink(&mut self) {
+        self.link = None;
+    }
+
     fn set_colors(&mut self, colors: crate::markdown::text_style::Colors) -> io::Result<()> {
         self.colors = colors;
         Ok(())
@@ -225,6 +258,8 @@ impl TerminalIo for VirtualTerminal {
             MoveLeft(amount) => self.move_left(*amount)?,
             MoveToNextLine => self.move_to_next_line()?,
             PrintText { content, style } => self.print_text(content, style)?,
+            BeginHyperlink(url) => self.begin_hyperlink(url),
+            EndHyperlink => self.end_hyperlink(),
             ClearScreen => self.clear_screen()?,
             SetColors(colors) => self.set_colors(*colors)?,
             SetBackgroundColor(color) => self.set_background_color(*color)?,
@@ -320,6 +355,21 @@ mod tests {
         grid.assert_contents(&["A C", " BD"]);
     }
 
+    #[test]
+    fn hyperlinks() {
+        let dimensions = WindowSize { rows: 1, columns: 4, height: 0, width: 0 };
+        let mut term = VirtualTerminal::new(dimensions, Default::default());
+        term.print_text("A", &Default::default()).unwrap();
+        term.begin_hyperlink("https://example.com");
+        term.print_text("BC", &Default::default()).unwrap();
+        term.end_hyperlink();
+        term.print_text("D", &Default::default()).unwrap();
+
+        let grid = term.into_contents();
+        let link = String::from("https://example.com");
+        assert_eq!(grid.links, HashMap::from([((0, 1), link.clone()), ((0, 2), link)]));
+    }
+
     #[test]
     fn iterator() {
         let row = &[
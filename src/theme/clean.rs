@@ -91,7 +91,7 @@ impl PresentationTheme {
         let default_style = DefaultStyle::new(default_style, &palette)?;
         Ok(Self {
             slide_title: SlideTitleStyle::new(slide_title, &palette, options)?,
-            code: CodeBlockStyle::new(code),
+            code: CodeBlockStyle::new(code, &palette)?,
             execution_output: ExecutionOutputBlockStyle::new(execution_output, &palette)?,
             pty_output: PtyOutputBlockStyle::new(pty_output, &palette)?,
             inline_code: ModifierStyle::new(inline_code, &palette)?,
@@ -525,11 +525,13 @@ pub(crate) enum FooterStyle {
         center: Option<FooterContent>,
         right: Option<FooterContent>,
         style: TextStyle,
+        behind_style: Option<TextStyle>,
         height: u16,
     },
     ProgressBar {
         character: char,
         style: TextStyle,
+        behind_style: Option<TextStyle>,
     },
     #[default]
     Empty,
@@ -542,18 +544,20 @@ impl FooterStyle {
         resources: &Resources,
     ) -> Result<Self, ProcessingThemeError> {
         match raw {
-            raw::FooterStyle::Template { left, center, right, colors, height } => {
+            raw::FooterStyle::Template { left, center, right, colors, behind_colors, height } => {
                 let left = left.as_ref().map(|t| FooterContent::new(t, resources)).transpose()?;
                 let center = center.as_ref().map(|t| FooterContent::new(t, resources)).transpose()?;
                 let right = right.as_ref().map(|t| FooterContent::new(t, resources)).transpose()?;
                 let style = TextStyle::colored(colors.resolve(palette)?);
+                let behind_style = Self::resolve_behind_style(behind_colors, palette)?;
                 let height = height.unwrap_or(DEFAULT_FOOTER_HEIGHT);
-                Ok(Self::Template { left, center, right, style, height })
+                Ok(Self::Template { left, center, right, style, behind_style, height })
             }
-            raw::FooterStyle::ProgressBar { character, colors } => {
+            raw::FooterStyle::ProgressBar { character, colors, behind_colors } => {
                 let character = character.unwrap_or(DEFAULT_PROGRESS_BAR_CHAR);
                 let style = TextStyle::colored(colors.resolve(palette)?);
-                Ok(Self::ProgressBar { character, style })
+                let behind_style = Self::resolve_behind_style(behind_colors, palette)?;
+                Ok(Self::ProgressBar { character, style, behind_style })
             }
             raw::FooterStyle::Empty => Ok(Self::Empty),
         }
@@ -565,6 +569,31 @@ impl FooterStyle {
             _ => DEFAULT_FOOTER_HEIGHT,
         }
     }
+
+    /// Get the style to use when the presentation is running behind its time budget, if any.
+    pub(crate) fn behind_schedule(&self) -> Option<Self> {
+        match self {
+            Self::Template { left, center, right, behind_style, height, .. } => Some(Self::Template {
+                left: left.clone(),
+                center: center.clone(),
+                right: right.clone(),
+                style: (*behind_style)?,
+                behind_style: None,
+                height: *height,
+            }),
+            Self::ProgressBar { character, behind_style, .. } => {
+                Some(Self::ProgressBar { character: *character, style: (*behind_style)?, behind_style: None })
+            }
+            Self::Empty => None,
+        }
+    }
+
+    fn resolve_behind_style(
+        colors: &Option<raw::RawColors>,
+        palette: &ColorPalette,
+    ) -> Result<Option<TextStyle>, ProcessingThemeError> {
+        Ok(colors.as_ref().map(|colors| colors.resolve(palette)).transpose()?.map(TextStyle::colored))
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -592,22 +621,42 @@ pub(crate) struct CodeBlockStyle {
     pub(crate) theme_name: String,
     pub(crate) background: bool,
     pub(crate) line_numbers: bool,
+    pub(crate) diff: CodeDiffStyle,
 }
 
 impl CodeBlockStyle {
-    fn new(raw: &raw::CodeBlockStyle) -> Self {
-        let raw::CodeBlockStyle { alignment, padding, theme_name, background, line_numbers } = raw;
+    fn new(raw: &raw::CodeBlockStyle, palette: &ColorPalette) -> Result<Self, ProcessingThemeError> {
+        let raw::CodeBlockStyle { alignment, padding, theme_name, background, line_numbers, diff } = raw;
         let padding = PaddingRect {
             horizontal: padding.horizontal.unwrap_or_default(),
             vertical: padding.vertical.unwrap_or_default(),
         };
-        Self {
+        Ok(Self {
             alignment: alignment.clone().unwrap_or_default().into(),
             padding,
             theme_name: theme_name.as_deref().unwrap_or(DEFAULT_CODE_HIGHLIGHT_THEME).to_string(),
             background: background.unwrap_or(true),
             line_numbers: line_numbers.unwrap_or_default(),
-        }
+            diff: CodeDiffStyle::new(diff, palette)?,
+        })
+    }
+}
+
+/// The colors for the lines in a snippet displayed as a diff.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct CodeDiffStyle {
+    pub(crate) added: Colors,
+    pub(crate) removed: Colors,
+}
+
+impl CodeDiffStyle {
+    fn new(raw: &raw::CodeDiffStyle, palette: &ColorPalette) -> Result<Self, ProcessingThemeError> {
+        let raw::CodeDiffStyle { added, removed } = raw;
+        let mut added = added.resolve(palette)?;
+        let mut removed = removed.resolve(palette)?;
+        added.background = added.background.or(Some(Color::DarkGreen));
+        removed.background = removed.background.or(Some(Color::DarkRed));
+        Ok(Self { added, removed })
     }
 }
 
@@ -647,16 +696,20 @@ pub(crate) struct ExecutionStatusBlockStyle {
     pub(crate) success_style: TextStyle,
     pub(crate) failure_style: TextStyle,
     pub(crate) not_started_style: TextStyle,
+    pub(crate) timed_out_style: TextStyle,
+    pub(crate) killed_style: TextStyle,
 }
 
 impl ExecutionStatusBlockStyle {
     fn new(raw: &raw::ExecutionStatusBlockStyle, palette: &ColorPalette) -> Result<Self, ProcessingThemeError> {
-        let raw::ExecutionStatusBlockStyle { running, success, failure, not_started } = raw;
+        let raw::ExecutionStatusBlockStyle { running, success, failure, not_started, timed_out, killed } = raw;
         let running_style = TextStyle::colored(running.resolve(palette)?);
         let success_style = TextStyle::colored(success.resolve(palette)?);
         let failure_style = TextStyle::colored(failure.resolve(palette)?);
         let not_started_style = TextStyle::colored(not_started.resolve(palette)?);
-        Ok(Self { running_style, success_style, failure_style, not_started_style })
+        let timed_out_style = TextStyle::colored(timed_out.as_ref().unwrap_or(failure).resolve(palette)?);
+        let killed_style = TextStyle::colored(killed.as_ref().unwrap_or(failure).resolve(palette)?);
+        Ok(Self { running_style, success_style, failure_style, not_started_style, timed_out_style, killed_style })
     }
 }
 
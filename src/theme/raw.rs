@@ -469,6 +469,10 @@ pub(crate) enum FooterStyle {
         #[serde(default)]
         colors: RawColors,
 
+        /// The colors to be used when running behind the presentation's time budget.
+        #[serde(default)]
+        behind_colors: Option<RawColors>,
+
         /// The height of the footer area.
         height: Option<u16>,
     },
@@ -481,6 +485,12 @@ pub(crate) enum FooterStyle {
         /// The colors to be used.
         #[serde(default)]
         colors: RawColors,
+
+        /// The colors to be used when running behind the presentation's time budget.
+        ///
+        /// Setting this enables pacing mode.
+        #[serde(default)]
+        behind_colors: Option<RawColors>,
     },
 
     /// No footer.
@@ -489,7 +499,14 @@ pub(crate) enum FooterStyle {
 
 impl Default for FooterStyle {
     fn default() -> Self {
-        Self::Template { left: None, center: None, right: None, colors: RawColors::default(), height: None }
+        Self::Template {
+            left: None,
+            center: None,
+            right: None,
+            colors: RawColors::default(),
+            behind_colors: None,
+            height: None,
+        }
     }
 }
 
@@ -506,6 +523,16 @@ pub(crate) enum FooterTemplateChunk {
     Event,
     Location,
     Date,
+    Elapsed,
+    Remaining,
+    Clock,
+}
+
+impl FooterTemplateChunk {
+    /// Whether this chunk's value changes as time goes by.
+    pub(crate) fn is_time_dependent(&self) -> bool {
+        matches!(self, Self::Elapsed | Self::Remaining | Self::Clock)
+    }
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -616,6 +643,9 @@ impl FromStr for FooterTemplate {
                     "event" => FooterTemplateChunk::Event,
                     "location" => FooterTemplateChunk::Location,
                     "date" => FooterTemplateChunk::Date,
+                    "elapsed" => FooterTemplateChunk::Elapsed,
+                    "remaining" => FooterTemplateChunk::Remaining,
+                    "clock" => FooterTemplateChunk::Clock,
                     _ => return Err(ParseFooterTemplateError::UnsupportedVariable(variable.to_string())),
                 };
                 chunks.push(chunk);
@@ -648,6 +678,9 @@ impl fmt::Display for FooterTemplate {
                 Event => write!(f, "{{event}}"),
                 Location => write!(f, "{{location}}"),
                 Date => write!(f, "{{date}}"),
+                Elapsed => write!(f, "{{elapsed}}"),
+                Remaining => write!(f, "{{remaining}}"),
+                Clock => write!(f, "{{clock}}"),
             }?;
         }
         Ok(())
@@ -690,6 +723,22 @@ pub(crate) struct CodeBlockStyle {
     /// Whether to show line numbers in all code blocks.
     #[serde(default)]
     pub(crate) line_numbers: Option<bool>,
+
+    /// The colors for lines added and removed in snippets displayed as a diff.
+    #[serde(default)]
+    pub(crate) diff: CodeDiffStyle,
+}
+
+/// The style for the lines in a snippet displayed as a diff.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub(crate) struct CodeDiffStyle {
+    /// The colors for lines that were added.
+    #[serde(default)]
+    pub(crate) added: RawColors,
+
+    /// The colors for lines that were removed.
+    #[serde(default)]
+    pub(crate) removed: RawColors,
 }
 
 /// The style for the output of a code execution block.
@@ -760,6 +809,14 @@ pub(crate) struct ExecutionStatusBlockStyle {
     /// The colors for the "not started" status.
     #[serde(default)]
     pub(crate) not_started: RawColors,
+
+    /// The colors for the "timed out" status. Defaults to the "finished with error" colors.
+    #[serde(default)]
+    pub(crate) timed_out: Option<RawColors>,
+
+    /// The colors for the "killed" status. Defaults to the "finished with error" colors.
+    #[serde(default)]
+    pub(crate) killed: Option<RawColors>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
@@ -1015,7 +1072,8 @@ mod test {
     #[test]
     fn parse_all_footer_template_variables() {
         use FooterTemplateChunk::*;
-        let raw = "hi {current_slide} {total_slides} {author} {title} {sub_title} {event} {location} {event}";
+        let raw = "hi {current_slide} {total_slides} {author} {title} {sub_title} {event} {location} {event} {elapsed} \
+                   {remaining} {clock}";
         let t: FooterTemplate = raw.parse().expect("invalid input");
         let expected = vec![
             Literal("hi ".into()),
@@ -1034,6 +1092,12 @@ mod test {
             Location,
             Literal(" ".into()),
             Event,
+            Literal(" ".into()),
+            Elapsed,
+            Literal(" ".into()),
+            Remaining,
+            Literal(" ".into()),
+            Clock,
         ];
         assert_eq!(t.0, expected);
         assert_eq!(t.to_string(), raw);
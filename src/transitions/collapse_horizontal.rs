@@ -31,7 +31,12 @@ impl AnimateTransition for CollapseHorizontalAnimation {
             let row = to_prefix.chain(from).chain(to_suffix).copied().collect();
             rows.push(row)
         }
-        let grid = TerminalGrid { rows, background_color: self.from.background_color, images: Default::default() };
+        let grid = TerminalGrid {
+            rows,
+            background_color: self.from.background_color,
+            images: Default::default(),
+            links: Default::default(),
+        };
         LinesFrame::from(&grid)
     }
 
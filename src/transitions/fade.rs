@@ -109,6 +109,7 @@ mod tests {
             ],
             background_color: None,
             images: Default::default(),
+            links: Default::default(),
         };
         let right = TerminalGrid {
             rows: vec![
@@ -117,6 +118,7 @@ mod tests {
             ],
             background_color: None,
             images: Default::default(),
+            links: Default::default(),
         };
         let expected = match direction {
             TransitionDirection::Next => right.clone(),
@@ -0,0 +1,96 @@
+use super::{AnimateTransition, LinesFrame, TransitionDirection};
+use crate::{
+    code::diff::{DiffOp, diff},
+    terminal::virt::{StyledChar, TerminalGrid},
+};
+
+pub(crate) struct MagicMoveAnimation {
+    from: TerminalGrid,
+    to: TerminalGrid,
+    steps: Vec<DiffOp>,
+    total_changes: usize,
+}
+
+impl MagicMoveAnimation {
+    pub(crate) fn new(left: TerminalGrid, right: TerminalGrid, direction: TransitionDirection) -> Self {
+        let (from, to) = match direction {
+            TransitionDirection::Next => (left, right),
+            TransitionDirection::Previous => (right, left),
+        };
+        // Rows are matched by their text so lines that only moved horizontally are kept in place.
+        let from_text: Vec<_> = from.rows.iter().map(|row| Self::row_text(row)).collect();
+        let to_text: Vec<_> = to.rows.iter().map(|row| Self::row_text(row)).collect();
+        let steps = diff(&from_text, &to_text);
+        let total_changes = steps.iter().filter(|step| !matches!(step, DiffOp::Unchanged { .. })).count();
+        Self { from, to, steps, total_changes }
+    }
+
+    fn row_text(row: &[StyledChar]) -> String {
+        let text: String = row.iter().map(|c| c.character).collect();
+        text.trim().to_string()
+    }
+}
+
+impl AnimateTransition for MagicMoveAnimation {
+    type Frame = LinesFrame;
+
+    fn build_frame(&self, frame: usize, _previous_frame: usize) -> Self::Frame {
+        // Every frame removes or adds one more row, keeping the rows both slides share.
+        let mut rows = Vec::new();
+        let mut changes = 0;
+        for step in &self.steps {
+            match step {
+                DiffOp::Unchanged { after, .. } => rows.push(self.to.rows[*after].clone()),
+                DiffOp::Removed(index) => {
+                    if changes >= frame {
+                        rows.push(self.from.rows[*index].clone());
+                    }
+                    changes += 1;
+                }
+                DiffOp::Added(index) => {
+                    if changes < frame {
+                        rows.push(self.to.rows[*index].clone());
+                    }
+                    changes += 1;
+                }
+            }
+        }
+        let height = self.to.rows.len();
+        let width = self.to.rows.first().map(Vec::len).unwrap_or_default();
+        rows.resize(height, vec![StyledChar::default(); width]);
+        let grid = TerminalGrid {
+            rows,
+            background_color: self.to.background_color,
+            images: Default::default(),
+            links: Default::default(),
+        };
+        LinesFrame::from(&grid)
+    }
+
+    fn total_frames(&self) -> usize {
+        self.total_changes + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{markdown::elements::Line, transitions::utils::build_grid};
+    use rstest::rstest;
+
+    fn as_text(line: Line) -> String {
+        line.0.into_iter().map(|l| l.content).collect()
+    }
+
+    #[rstest]
+    #[case(0, &["A", "B", "C"])]
+    #[case(1, &["A", "C", " "])]
+    #[case(2, &["A", "X", "C"])]
+    fn transition(#[case] frame: usize, #[case] expected: &[&str]) {
+        let left = build_grid(&["A", "B", "C"]);
+        let right = build_grid(&["A", "X", "C"]);
+        let transition = MagicMoveAnimation::new(left, right, TransitionDirection::Next);
+        let lines: Vec<_> = transition.build_frame(frame, 0).lines.into_iter().map(as_text).collect();
+        assert_eq!(lines, expected);
+    }
+}
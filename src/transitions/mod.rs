@@ -10,6 +10,7 @@ use unicode_width::UnicodeWidthStr;
 
 pub(crate) mod collapse_horizontal;
 pub(crate) mod fade;
+pub(crate) mod magic_move;
 pub(crate) mod slide_horizontal;
 
 #[derive(Clone, Debug)]
@@ -119,7 +120,7 @@ mod utils {
             .iter()
             .map(|r| r.chars().map(|c| StyledChar { character: c, style: Default::default() }).collect())
             .collect();
-        TerminalGrid { rows, background_color: None, images: Default::default() }
+        TerminalGrid { rows, background_color: None, images: Default::default(), links: Default::default() }
     }
 }
 
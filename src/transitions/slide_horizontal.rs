@@ -23,7 +23,12 @@ impl SlideHorizontalAnimation {
             row.extend(right);
             rows.push(row);
         }
-        let grid = TerminalGrid { rows, background_color: left.background_color, images: Default::default() };
+        let grid = TerminalGrid {
+            rows,
+            background_color: left.background_color,
+            images: Default::default(),
+            links: Default::default(),
+        };
         Self { grid, dimensions, direction }
     }
 }
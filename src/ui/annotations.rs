@@ -0,0 +1,299 @@
+use crate::{
+    commands::keyboard::{Direction, PointerEvent},
+    markdown::{elements::Text, text::WeightedLine, text_style::TextStyle},
+    render::{
+        operation::{AsRenderOperations, RenderOperation},
+        properties::WindowSize,
+    },
+    theme::{Alignment, Margin},
+};
+
+const POINTER: char = '●';
+const MARK: char = '◆';
+
+/// A position in the terminal.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Position {
+    column: u16,
+    row: u16,
+}
+
+impl Position {
+    fn clamp(self, dimensions: &WindowSize) -> Self {
+        Self {
+            column: self.column.min(dimensions.columns.saturating_sub(1)),
+            row: self.row.min(dimensions.rows.saturating_sub(1)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Annotation {
+    Mark(Position),
+    Box { from: Position, to: Position },
+}
+
+/// The annotations drawn on top of the slide being presented.
+///
+/// Annotations only apply to a single slide and they're all removed as soon as the slide changes.
+#[derive(Debug, Default)]
+pub(crate) struct Annotations {
+    enabled: bool,
+    slide: usize,
+    pointer: Position,
+    anchor: Option<Position>,
+    annotations: Vec<Annotation>,
+}
+
+impl Annotations {
+    /// Whether the annotation mode is enabled.
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Start annotating a slide, placing the pointer in the middle of the screen.
+    pub(crate) fn enable(&mut self, slide: usize, dimensions: &WindowSize) {
+        *self = Self {
+            enabled: true,
+            slide,
+            pointer: Position { column: dimensions.columns / 2, row: dimensions.rows / 2 },
+            ..Default::default()
+        };
+    }
+
+    /// Stop annotating, removing every annotation.
+    pub(crate) fn disable(&mut self) {
+        *self = Default::default();
+    }
+
+    /// Remove every annotation if the slide being displayed is not the one they were drawn on.
+    pub(crate) fn follow_slide(&mut self, slide: usize) {
+        if self.slide != slide {
+            self.slide = slide;
+            self.anchor = None;
+            self.annotations.clear();
+        }
+    }
+
+    /// Apply a pointer event.
+    ///
+    /// Returns `true` if this caused the annotations to change.
+    pub(crate) fn apply(&mut self, event: PointerEvent, dimensions: &WindowSize) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        // Annotations are only ever added so comparing how many there are is enough to know if they changed.
+        let previous = (self.pointer, self.anchor, self.annotations.len());
+        match event {
+            PointerEvent::Move(direction) => self.move_pointer(direction, dimensions),
+            PointerEvent::MoveTo { column, row } => self.pointer = Position { column, row }.clamp(dimensions),
+            PointerEvent::Mark => self.annotations.push(Annotation::Mark(self.pointer)),
+            PointerEvent::Corner => match self.anchor.take() {
+                Some(anchor) => self.finish_box(anchor),
+                None => self.anchor = Some(self.pointer),
+            },
+            PointerEvent::Press { column, row } => {
+                self.pointer = Position { column, row }.clamp(dimensions);
+                self.anchor = Some(self.pointer);
+            }
+            PointerEvent::Release { column, row } => {
+                self.pointer = Position { column, row }.clamp(dimensions);
+                if let Some(anchor) = self.anchor.take() {
+                    self.finish_box(anchor);
+                }
+            }
+        };
+        (self.pointer, self.anchor, self.annotations.len()) != previous
+    }
+
+    /// Get the overlay that draws the annotations, if annotating.
+    pub(crate) fn overlay(&self, style: TextStyle) -> Option<AnnotationOverlay> {
+        if !self.enabled {
+            return None;
+        }
+        let mut annotations = self.annotations.clone();
+        // Preview the box being drawn.
+        if let Some(from) = self.anchor {
+            annotations.push(Annotation::Box { from, to: self.pointer });
+        }
+        Some(AnnotationOverlay { annotations, pointer: self.pointer, style })
+    }
+
+    fn move_pointer(&mut self, direction: Direction, dimensions: &WindowSize) {
+        let Position { column, row } = self.pointer;
+        let position = match direction {
+            Direction::Up => Position { column, row: row.saturating_sub(1) },
+            Direction::Down => Position { column, row: row + 1 },
+            Direction::Left => Position { column: column.saturating_sub(1), row },
+            Direction::Right => Position { column: column + 1, row },
+        };
+        self.pointer = position.clamp(dimensions);
+    }
+
+    fn finish_box(&mut self, from: Position) {
+        // A box without an area is a click on a single cell.
+        let annotation = match from == self.pointer {
+            true => Annotation::Mark(from),
+            false => Annotation::Box { from, to: self.pointer },
+        };
+        self.annotations.push(annotation);
+    }
+}
+
+/// Draws annotations on top of a slide.
+#[derive(Debug)]
+pub(crate) struct AnnotationOverlay {
+    annotations: Vec<Annotation>,
+    pointer: Position,
+    style: TextStyle,
+}
+
+impl AsRenderOperations for AnnotationOverlay {
+    fn as_render_operations(&self, dimensions: &WindowSize) -> Vec<RenderOperation> {
+        let mut canvas = Canvas { dimensions, style: self.style, operations: Vec::new() };
+        for annotation in &self.annotations {
+            match annotation {
+                Annotation::Mark(position) => canvas.draw(*position, MARK),
+                Annotation::Box { from, to } => canvas.draw_box(*from, *to),
+            }
+        }
+        canvas.draw(self.pointer, POINTER);
+        canvas.operations
+    }
+}
+
+struct Canvas<'a> {
+    dimensions: &'a WindowSize,
+    style: TextStyle,
+    operations: Vec<RenderOperation>,
+}
+
+impl Canvas<'_> {
+    fn draw(&mut self, position: Position, character: char) {
+        let Position { column, row } = position.clamp(self.dimensions);
+        // Alignments use the same margin on both sides so a cell in the right half of the screen can only be placed
+        // by aligning it to the right.
+        let alignment = if column * 2 < self.dimensions.columns {
+            Alignment::Left { margin: Margin::Fixed(column) }
+        } else {
+            Alignment::Right { margin: Margin::Fixed(self.dimensions.columns - column - 1) }
+        };
+        let line = WeightedLine::from(vec![Text::new(character.to_string(), self.style)]);
+        self.operations.push(RenderOperation::JumpToRow { index: row });
+        self.operations.push(RenderOperation::RenderText { line, alignment });
+    }
+
+    fn draw_box(&mut self, from: Position, to: Position) {
+        let (from, to) = (from.clamp(self.dimensions), to.clamp(self.dimensions));
+        let (left, right) = (from.column.min(to.column), from.column.max(to.column));
+        let (top, bottom) = (from.row.min(to.row), from.row.max(to.row));
+        for column in left + 1..right {
+            self.draw(Position { column, row: top }, '─');
+            self.draw(Position { column, row: bottom }, '─');
+        }
+        for row in top + 1..bottom {
+            self.draw(Position { column: left, row }, '│');
+            self.draw(Position { column: right, row }, '│');
+        }
+        // Boxes that are a single row or column tall are just lines.
+        let corners = match (left == right, top == bottom) {
+            (false, false) => ['┌', '┐', '└', '┘'],
+            (true, _) => ['│'; 4],
+            (false, true) => ['─'; 4],
+        };
+        self.draw(Position { column: left, row: top }, corners[0]);
+        self.draw(Position { column: right, row: top }, corners[1]);
+        self.draw(Position { column: left, row: bottom }, corners[2]);
+        self.draw(Position { column: right, row: bottom }, corners[3]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{render::engine::RenderEngine, terminal::virt::VirtualTerminal};
+
+    const DIMENSIONS: WindowSize = WindowSize { rows: 5, columns: 10, width: 0, height: 0 };
+
+    fn render(annotations: &Annotations) -> Vec<String> {
+        let mut term = VirtualTerminal::new(DIMENSIONS, Default::default());
+        let overlay = annotations.overlay(Default::default()).expect("not enabled");
+        let operations = overlay.as_render_operations(&DIMENSIONS);
+        let engine = RenderEngine::new(&mut term, DIMENSIONS, Default::default());
+        engine.render(operations.iter()).expect("render failed");
+        term.into_contents().rows.into_iter().map(|row| row.into_iter().map(|c| c.character).collect()).collect()
+    }
+
+    fn enabled() -> Annotations {
+        let mut annotations = Annotations::default();
+        annotations.enable(0, &DIMENSIONS);
+        annotations
+    }
+
+    #[test]
+    fn disabled() {
+        let mut annotations = Annotations::default();
+        assert!(!annotations.apply(PointerEvent::Mark, &DIMENSIONS));
+        assert!(annotations.overlay(Default::default()).is_none());
+    }
+
+    #[test]
+    fn keyboard() {
+        let mut annotations = enabled();
+        annotations.apply(PointerEvent::Corner, &DIMENSIONS);
+        for direction in [Direction::Left, Direction::Left, Direction::Down, Direction::Down] {
+            annotations.apply(PointerEvent::Move(direction), &DIMENSIONS);
+        }
+        annotations.apply(PointerEvent::Corner, &DIMENSIONS);
+        annotations.apply(PointerEvent::Move(Direction::Up), &DIMENSIONS);
+        annotations.apply(PointerEvent::Move(Direction::Right), &DIMENSIONS);
+        annotations.apply(PointerEvent::Mark, &DIMENSIONS);
+        // The pointer can't go off screen.
+        for _ in 0..20 {
+            annotations.apply(PointerEvent::Move(Direction::Right), &DIMENSIONS);
+        }
+        let expected = &["          ", "          ", "   ┌─┐    ", "   │◆│   ●", "   └─┘    "];
+        assert_eq!(render(&annotations), expected);
+    }
+
+    #[test]
+    fn mouse() {
+        let mut annotations = enabled();
+        // A click marks a cell and a drag draws a box.
+        annotations.apply(PointerEvent::Press { column: 0, row: 0 }, &DIMENSIONS);
+        annotations.apply(PointerEvent::Release { column: 0, row: 0 }, &DIMENSIONS);
+        annotations.apply(PointerEvent::Press { column: 6, row: 1 }, &DIMENSIONS);
+        annotations.apply(PointerEvent::MoveTo { column: 8, row: 3 }, &DIMENSIONS);
+        annotations.apply(PointerEvent::Release { column: 9, row: 3 }, &DIMENSIONS);
+        annotations.apply(PointerEvent::MoveTo { column: 2, row: 20 }, &DIMENSIONS);
+        let expected = &["◆         ", "      ┌──┐", "      │  │", "      └──┘", "  ●       "];
+        assert_eq!(render(&annotations), expected);
+    }
+
+    #[test]
+    fn unchanged() {
+        let mut annotations = enabled();
+        assert!(annotations.apply(PointerEvent::MoveTo { column: 1, row: 1 }, &DIMENSIONS));
+        assert!(!annotations.apply(PointerEvent::MoveTo { column: 1, row: 1 }, &DIMENSIONS));
+        // Positions outside of the screen are clamped to the same cell.
+        assert!(annotations.apply(PointerEvent::MoveTo { column: 20, row: 1 }, &DIMENSIONS));
+        assert!(!annotations.apply(PointerEvent::MoveTo { column: 30, row: 1 }, &DIMENSIONS));
+        assert!(!annotations.apply(PointerEvent::Move(Direction::Right), &DIMENSIONS));
+
+        assert!(annotations.apply(PointerEvent::Corner, &DIMENSIONS));
+        assert!(annotations.apply(PointerEvent::Mark, &DIMENSIONS));
+        assert!(annotations.apply(PointerEvent::Mark, &DIMENSIONS));
+    }
+
+    #[test]
+    fn cleared_on_slide_change() {
+        let mut annotations = enabled();
+        annotations.apply(PointerEvent::Mark, &DIMENSIONS);
+        annotations.follow_slide(0);
+        assert_eq!(annotations.annotations.len(), 1);
+
+        annotations.follow_slide(1);
+        assert!(annotations.annotations.is_empty());
+        assert!(annotations.is_enabled());
+    }
+}
@@ -39,7 +39,7 @@ impl RunImageSnippet {
         image_registry: ImageRegistry,
         colors: ExecutionStatusBlockStyle,
     ) -> Self {
-        let state = Arc::new(Mutex::new(State::NotStarted(executor)));
+        let state = Arc::new(Mutex::new(State::NotStarted(Box::new(executor))));
         Self { snippet, image_registry, colors, state }
     }
 }
@@ -56,6 +56,12 @@ impl RenderAsync for RunImageSnippet {
     fn start_policy(&self) -> RenderAsyncStartPolicy {
         RenderAsyncStartPolicy::Automatic
     }
+
+    fn kill(&self) {
+        if let State::Running(handle) = self.state.lock().unwrap().deref() {
+            handle.kill();
+        }
+    }
 }
 
 impl AsRenderOperations for RunImageSnippet {
@@ -130,7 +136,7 @@ impl Pollable for OperationPollable {
                         };
                         PollableState::Done
                     }
-                    ProcessStatus::Failure => {
+                    ProcessStatus::Failure | ProcessStatus::TimedOut | ProcessStatus::Killed => {
                         let mut lines = Vec::new();
                         for line in inner.output.lines() {
                             lines.push(line.unwrap_or_else(|_| String::new()));
@@ -149,7 +155,7 @@ impl Pollable for OperationPollable {
 
 #[derive(Debug)]
 enum State {
-    NotStarted(LanguageSnippetExecutor),
+    NotStarted(Box<LanguageSnippetExecutor>),
     Running(ExecutionHandle),
     Success(Image),
     Failure(Vec<String>),
@@ -0,0 +1,99 @@
+use crate::{
+    markdown::{elements::Text, text::WeightedLine, text_style::TextStyle},
+    render::{
+        operation::{AsRenderOperations, RenderOperation},
+        properties::WindowSize,
+    },
+    theme::{Alignment, Margin},
+    utils::tail_within_width,
+};
+use unicode_width::UnicodeWidthStr;
+
+/// The prompt where lines of input for a running snippet are typed.
+#[derive(Debug, Default)]
+pub(crate) struct SnippetInputPrompt {
+    input: Option<String>,
+}
+
+impl SnippetInputPrompt {
+    /// Open the prompt.
+    pub(crate) fn start(&mut self) {
+        self.input = Some(String::new());
+    }
+
+    /// Close the prompt.
+    pub(crate) fn cancel(&mut self) {
+        self.input = None;
+    }
+
+    pub(crate) fn is_active(&self) -> bool {
+        self.input.is_some()
+    }
+
+    /// The text typed so far, if the prompt is open.
+    pub(crate) fn input(&self) -> Option<&str> {
+        self.input.as_deref()
+    }
+
+    pub(crate) fn push(&mut self, c: char) {
+        if let Some(input) = &mut self.input {
+            input.push(c);
+        }
+    }
+
+    pub(crate) fn pop(&mut self) {
+        if let Some(input) = &mut self.input {
+            input.pop();
+        }
+    }
+
+    /// Take the line typed so far, leaving the prompt open so more lines can be typed.
+    pub(crate) fn take(&mut self) -> Option<String> {
+        self.input.as_mut().map(std::mem::take)
+    }
+}
+
+/// The bar at the bottom of the screen where input for a running snippet is typed.
+#[derive(Debug)]
+pub(crate) struct SnippetInputBar {
+    pub(crate) input: String,
+    pub(crate) style: TextStyle,
+}
+
+impl AsRenderOperations for SnippetInputBar {
+    fn as_render_operations(&self, dimensions: &WindowSize) -> Vec<RenderOperation> {
+        let text = format!("> {}█", self.input);
+        // Keep the end of the input visible if it doesn't fit.
+        let mut text = tail_within_width(&text, dimensions.columns as usize).to_string();
+        let padding = (dimensions.columns as usize).saturating_sub(text.width());
+        text.extend(std::iter::repeat_n(' ', padding));
+        let line = WeightedLine::from(vec![Text::new(text, self.style)]);
+        vec![
+            RenderOperation::JumpToBottomRow { index: 0 },
+            RenderOperation::RenderText { line, alignment: Alignment::Left { margin: Margin::Fixed(0) } },
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typing() {
+        let mut prompt = SnippetInputPrompt::default();
+        prompt.push('a');
+        assert_eq!(prompt.input(), None);
+
+        prompt.start();
+        for c in "hix".chars() {
+            prompt.push(c);
+        }
+        prompt.pop();
+        assert_eq!(prompt.take().as_deref(), Some("hi"));
+        assert_eq!(prompt.input(), Some(""));
+
+        prompt.cancel();
+        assert_eq!(prompt.take(), None);
+    }
+}
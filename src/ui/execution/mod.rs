@@ -1,6 +1,7 @@
 pub(crate) mod acquire_terminal;
 pub(crate) mod disabled;
 pub(crate) mod image;
+pub(crate) mod input;
 pub(crate) mod output;
 pub(crate) mod pty;
 pub(crate) mod validator;
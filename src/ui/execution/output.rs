@@ -1,6 +1,6 @@
 use crate::{
     code::{
-        execute::{ExecutionHandle, ExecutionState, LanguageSnippetExecutor, ProcessStatus},
+        execute::{ExecutionHandle, ExecutionState, LanguageSnippetExecutor, ProcessInput, ProcessStatus},
         snippet::Snippet,
     },
     markdown::{
@@ -234,6 +234,25 @@ impl RenderAsync for RunSnippetTrigger {
     fn start_policy(&self) -> RenderAsyncStartPolicy {
         self.0.lock().unwrap().policy
     }
+
+    fn accepts_input(&self) -> bool {
+        match &self.0.lock().unwrap().state {
+            State::Running(handle) => handle.accepts_input(),
+            _ => false,
+        }
+    }
+
+    fn send_input(&self, input: ProcessInput) {
+        if let State::Running(handle) = &self.0.lock().unwrap().state {
+            handle.send_input(input);
+        }
+    }
+
+    fn kill(&self) {
+        if let State::Running(handle) = &self.0.lock().unwrap().state {
+            handle.kill();
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -310,6 +329,8 @@ impl AsRenderOperations for ExecIndicator {
             Some(ProcessStatus::Running) => Text::new("running", self.theme.running_style),
             Some(ProcessStatus::Success) => Text::new("finished", self.theme.success_style),
             Some(ProcessStatus::Failure) => Text::new("finished with error", self.theme.failure_style),
+            Some(ProcessStatus::TimedOut) => Text::new("timed out", self.theme.timed_out_style),
+            Some(ProcessStatus::Killed) => Text::new("killed", self.theme.killed_style),
             None => Text::new("not started", self.theme.not_started_style),
         };
 
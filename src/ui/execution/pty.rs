@@ -16,11 +16,12 @@ use crate::{
     },
     theme::{Alignment, PtyOutputBlockStyle},
 };
-use portable_pty::{MasterPty, PtySize, native_pty_system};
+use portable_pty::{Child, MasterPty, PtySize, native_pty_system};
 use std::{
     fmt, io, iter, mem,
     sync::{Arc, Mutex},
     thread,
+    time::Instant,
 };
 use unicode_width::UnicodeWidthStr;
 
@@ -49,6 +50,16 @@ struct Inner {
     standby: bool,
     policy: RenderAsyncStartPolicy,
     state: State,
+    stop_reason: Option<ProcessStatus>,
+}
+
+impl Inner {
+    fn stop(&mut self, reason: ProcessStatus) {
+        if let State::Running { pty, .. } = &mut self.state {
+            self.stop_reason.get_or_insert(reason);
+            pty.kill();
+        }
+    }
 }
 
 impl fmt::Debug for Inner {
@@ -63,6 +74,7 @@ impl fmt::Debug for Inner {
             .field("parser", &"...")
             .field("policy", &self.policy)
             .field("state", &"...")
+            .field("stop_reason", &self.stop_reason)
             .finish()
     }
 }
@@ -196,6 +208,10 @@ impl RenderAsync for PtySnippetOutputOperation {
     fn pollable(&self) -> Box<dyn Pollable> {
         Box::new(OperationPollable { handle: self.handle.clone() })
     }
+
+    fn kill(&self) {
+        self.handle.kill();
+    }
 }
 
 #[derive(Debug)]
@@ -213,8 +229,8 @@ impl OperationPollable {
             pixel_height: dimensions.pixels_per_row() as u16,
         };
         let pair = pty_system.openpty(pty_size)?;
-        pair.slave.spawn_command(ctx.command.clone())?;
-        PtyMaster::new(pair.master, handle, ctx)
+        let child = pair.slave.spawn_command(ctx.command.clone())?;
+        PtyMaster::new(pair.master, child, handle, ctx)
     }
 }
 
@@ -224,6 +240,12 @@ impl Pollable for OperationPollable {
         let expected_size = inner.expected_size;
         let actual_size = inner.actual_size;
         inner.actual_size = expected_size;
+        let timeout = inner.executor.timeout();
+        if let (State::Running { pty, .. }, Some(timeout)) = (&inner.state, timeout) {
+            if pty.started.elapsed() >= timeout {
+                inner.stop(ProcessStatus::TimedOut);
+            }
+        }
         match &mut inner.state {
             State::Initial => match inner.executor.pty_execution_context(&inner.snippet) {
                 Ok(ctx) => match Self::spawn(ctx, expected_size, self.handle.clone()) {
@@ -265,14 +287,40 @@ impl Pollable for OperationPollable {
 
 pub(crate) struct PtyMaster {
     _master: Box<dyn MasterPty>,
+    child: Box<dyn Child + Send + Sync>,
+    started: Instant,
     _ctx: PtySnippetContext,
 }
 
 impl PtyMaster {
-    fn new(master: Box<dyn MasterPty>, handle: PtySnippetHandle, ctx: PtySnippetContext) -> anyhow::Result<Self> {
+    fn new(
+        master: Box<dyn MasterPty>,
+        child: Box<dyn Child + Send + Sync>,
+        handle: PtySnippetHandle,
+        ctx: PtySnippetContext,
+    ) -> anyhow::Result<Self> {
         let reader = master.try_clone_reader()?;
         thread::spawn(|| process_output(reader, handle));
-        Ok(Self { _master: master, _ctx: ctx })
+        Ok(Self { _master: master, child, started: Instant::now(), _ctx: ctx })
+    }
+
+    #[cfg(unix)]
+    fn kill(&mut self) {
+        // The process leads its own session so this takes down anything it spawned, which would otherwise keep the
+        // pty open.
+        match self.child.process_id().and_then(|pid| libc::pid_t::try_from(pid).ok()) {
+            Some(pid) => unsafe {
+                libc::kill(-pid, libc::SIGKILL);
+            },
+            None => {
+                let _ = self.child.kill();
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn kill(&mut self) {
+        let _ = self.child.kill();
     }
 }
 
@@ -298,7 +346,8 @@ fn process_output(mut reader: Box<dyn io::Read>, handle: PtySnippetHandle) {
             *dirty = true;
         };
     };
-    handle.0.lock().unwrap().state = State::ProcessTerminated(status);
+    let mut inner = handle.0.lock().unwrap();
+    inner.state = State::ProcessTerminated(inner.stop_reason.unwrap_or(status));
 }
 
 impl From<&vt100::Cell> for TextStyle {
@@ -354,6 +403,7 @@ impl PtySnippetHandle {
             update_size,
             standby: args.standby,
             state: Default::default(),
+            stop_reason: None,
             policy,
         };
         Self(Arc::new(Mutex::new(inner)))
@@ -370,6 +420,10 @@ impl PtySnippetHandle {
             State::ProcessTerminated(status) | State::Done(status) => Some(*status),
         }
     }
+
+    pub(crate) fn kill(&self) {
+        self.0.lock().unwrap().stop(ProcessStatus::Killed);
+    }
 }
 
 #[derive(Debug)]
@@ -395,4 +449,8 @@ impl RenderAsync for RunPtySnippetTrigger {
     fn start_policy(&self) -> RenderAsyncStartPolicy {
         self.0.0.lock().unwrap().policy
     }
+
+    fn kill(&self) {
+        self.0.kill();
+    }
 }
@@ -94,7 +94,7 @@ impl Pollable for OperationPollable {
                         State::Running(handle)
                     }
                     ProcessStatus::Success => State::Done(self.success_to_pollable_state()),
-                    ProcessStatus::Failure => {
+                    ProcessStatus::Failure | ProcessStatus::TimedOut | ProcessStatus::Killed => {
                         State::Done(self.error_to_pollable_state(String::from_utf8_lossy(&state.output)))
                     }
                 }
@@ -4,6 +4,10 @@ use crate::{
         parse::{MarkdownParser, ParseInlinesError},
         text_style::{TextStyle, UndefinedPaletteColorError},
     },
+    presentation::{
+        PresentationState,
+        clock::{RemainingTimeDisplay, TimeOfDay, TimerDisplay},
+    },
     render::{
         operation::{AsRenderOperations, ImagePosition, ImageRenderProperties, MarginProperties, RenderOperation},
         properties::WindowSize,
@@ -12,10 +16,10 @@ use crate::{
     theme::{Alignment, ColorPalette, FooterContent, FooterStyle, FooterTemplate, FooterTemplateChunk, Margin},
 };
 use comrak::Arena;
-use std::borrow::Cow;
+use std::{borrow::Cow, time::Duration};
 use unicode_width::UnicodeWidthStr;
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub(crate) struct FooterVariables {
     pub(crate) current_slide: usize,
     pub(crate) total_slides: usize,
@@ -25,6 +29,9 @@ pub(crate) struct FooterVariables {
     pub(crate) event: Option<String>,
     pub(crate) location: Option<String>,
     pub(crate) date: Option<String>,
+    pub(crate) duration: Option<Duration>,
+    pub(crate) elapsed: Duration,
+    pub(crate) time_of_day: TimeOfDay,
 }
 
 #[derive(Debug)]
@@ -32,6 +39,9 @@ pub(crate) struct FooterGenerator {
     current_slide: usize,
     total_slides: u64,
     style: RenderedFooterStyle,
+    behind_style: Option<RenderedFooterStyle>,
+    vars: FooterVariables,
+    state: PresentationState,
 }
 
 impl FooterGenerator {
@@ -39,11 +49,24 @@ impl FooterGenerator {
         style: FooterStyle,
         vars: &FooterVariables,
         palette: &ColorPalette,
+        state: PresentationState,
     ) -> Result<Self, InvalidFooterTemplateError> {
+        let behind_style = style.behind_schedule().map(|s| RenderedFooterStyle::new(s, vars, palette)).transpose()?;
         let style = RenderedFooterStyle::new(style, vars, palette)?;
         let current_slide = vars.current_slide;
         let total_slides = vars.total_slides as u64;
-        Ok(Self { current_slide, total_slides, style })
+        if style.is_time_dependent() || behind_style.is_some() {
+            state.mark_time_dependent();
+        }
+        Ok(Self { current_slide, total_slides, style, behind_style, vars: vars.clone(), state })
+    }
+
+    fn active_style(&self) -> &RenderedFooterStyle {
+        let behind = self.state.pacing().map(|pacing| pacing.behind).unwrap_or_default();
+        match &self.behind_style {
+            Some(style) if behind => style,
+            _ => &self.style,
+        }
     }
 
     fn render_line(line: &FooterLine, alignment: Alignment, height: u16, operations: &mut Vec<RenderOperation>) {
@@ -83,7 +106,7 @@ impl FooterGenerator {
 impl AsRenderOperations for FooterGenerator {
     fn as_render_operations(&self, dimensions: &WindowSize) -> Vec<RenderOperation> {
         use RenderedFooterStyle::*;
-        match &self.style {
+        match self.active_style() {
             Template { left, center, right, height } => {
                 // Crate a margin for ourselves so we can jump to top without stepping over slide
                 // text.
@@ -98,12 +121,23 @@ impl AsRenderOperations for FooterGenerator {
                     Alignment::Center { minimum_size: 0, minimum_margin: Default::default() },
                     Alignment::Right { margin: Default::default() },
                 ];
+                let vars = FooterVariables {
+                    elapsed: self.state.elapsed_time(),
+                    time_of_day: TimeOfDay::now(),
+                    ..self.vars.clone()
+                };
                 for (content, alignment) in [left, center, right].iter().zip(alignments) {
                     if let Some(content) = content {
                         match content {
                             RenderedFooterContent::Line(line) => {
                                 Self::render_line(line, alignment, *height, &mut operations);
                             }
+                            RenderedFooterContent::TimedLine(line) => {
+                                // This was validated when the footer was built so it can't fail.
+                                if let Ok(line) = line.render(&vars) {
+                                    Self::render_line(&line, alignment, *height, &mut operations);
+                                }
+                            }
                             RenderedFooterContent::Image(image) => {
                                 self.push_image(image, alignment, *height, &mut operations);
                             }
@@ -155,16 +189,25 @@ impl RenderedFooterStyle {
         palette: &ColorPalette,
     ) -> Result<Self, InvalidFooterTemplateError> {
         match style {
-            FooterStyle::Template { left, center, right, style, height } => {
+            FooterStyle::Template { left, center, right, style, height, .. } => {
                 let left = left.map(|c| RenderedFooterContent::new(c, &style, vars, palette)).transpose()?;
                 let center = center.map(|c| RenderedFooterContent::new(c, &style, vars, palette)).transpose()?;
                 let right = right.map(|c| RenderedFooterContent::new(c, &style, vars, palette)).transpose()?;
                 Ok(Self::Template { left, center, right, height })
             }
-            FooterStyle::ProgressBar { character, style } => Ok(Self::ProgressBar { character, style }),
+            FooterStyle::ProgressBar { character, style, .. } => Ok(Self::ProgressBar { character, style }),
             FooterStyle::Empty => Ok(Self::Empty),
         }
     }
+
+    fn is_time_dependent(&self) -> bool {
+        match self {
+            Self::Template { left, center, right, .. } => {
+                [left, center, right].into_iter().flatten().any(|c| matches!(c, RenderedFooterContent::TimedLine(_)))
+            }
+            Self::ProgressBar { .. } | Self::Empty => false,
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -178,7 +221,19 @@ impl FooterLine {
         palette: &ColorPalette,
     ) -> Result<Self, InvalidFooterTemplateError> {
         use FooterTemplateChunk::*;
-        let FooterVariables { current_slide, total_slides, author, title, sub_title, event, location, date } = vars;
+        let FooterVariables {
+            current_slide,
+            total_slides,
+            author,
+            title,
+            sub_title,
+            event,
+            location,
+            date,
+            duration,
+            elapsed,
+            time_of_day,
+        } = vars;
         let arena = Arena::default();
         let mut reassembled = String::new();
         for chunk in template.0 {
@@ -194,6 +249,12 @@ impl FooterLine {
                 Event => Self::extract_variable("event", event)?,
                 Location => Self::extract_variable("location", location)?,
                 Date => Self::extract_variable("date", date)?,
+                Elapsed => Cow::Owned(TimerDisplay(*elapsed).to_string()),
+                Remaining => {
+                    let target = duration.ok_or(InvalidFooterTemplateError::VariableNotSet("duration"))?;
+                    Cow::Owned(RemainingTimeDisplay { target, elapsed: *elapsed }.to_string())
+                }
+                Clock => Cow::Owned(time_of_day.to_string()),
             };
             if raw_text.lines().count() != 1 {
                 return Err(InvalidFooterTemplateError::NoNewlines);
@@ -227,9 +288,36 @@ impl FooterLine {
     }
 }
 
+/// A footer line that contains time dependent variables and needs to be re-rendered every time.
+#[derive(Clone, Debug)]
+struct TimedFooterLine {
+    template: FooterTemplate,
+    style: TextStyle,
+    palette: ColorPalette,
+}
+
+impl TimedFooterLine {
+    fn new(
+        template: FooterTemplate,
+        style: &TextStyle,
+        vars: &FooterVariables,
+        palette: &ColorPalette,
+    ) -> Result<Self, InvalidFooterTemplateError> {
+        let line = Self { template, style: *style, palette: palette.clone() };
+        // Make sure this is valid so rendering it later on can't fail.
+        line.render(vars)?;
+        Ok(line)
+    }
+
+    fn render(&self, vars: &FooterVariables) -> Result<FooterLine, InvalidFooterTemplateError> {
+        FooterLine::new(self.template.clone(), &self.style, vars, &self.palette)
+    }
+}
+
 #[derive(Clone, Debug)]
 enum RenderedFooterContent {
     Line(FooterLine),
+    TimedLine(Box<TimedFooterLine>),
     Image(Image),
 }
 
@@ -241,6 +329,9 @@ impl RenderedFooterContent {
         palette: &ColorPalette,
     ) -> Result<Self, InvalidFooterTemplateError> {
         Ok(match content {
+            FooterContent::Template(template) if template.0.iter().any(FooterTemplateChunk::is_time_dependent) => {
+                Self::TimedLine(Box::new(TimedFooterLine::new(template, style, vars, palette)?))
+            }
             FooterContent::Template(template) => Self::Line(FooterLine::new(template, style, vars, palette)?),
             FooterContent::Image(image) => Self::Image(image),
         })
@@ -264,7 +355,10 @@ pub(crate) enum InvalidFooterTemplateError {
 
 #[cfg(test)]
 mod tests {
-    use crate::markdown::text_style::Color;
+    use crate::{
+        markdown::{text::WeightedLine, text_style::Color},
+        presentation::clock::PacingStatus,
+    };
 
     use super::*;
     use once_cell::sync::Lazy;
@@ -279,8 +373,18 @@ mod tests {
         event: Some("test".into()),
         location: Some("here".into()),
         date: Some("now".into()),
+        duration: Some(Duration::from_secs(600)),
+        elapsed: Duration::from_secs(125),
+        time_of_day: TimeOfDay { hours: 13, minutes: 7 },
     });
 
+    fn find_text(operations: Vec<RenderOperation>) -> Option<WeightedLine> {
+        operations.into_iter().find_map(|operation| match operation {
+            RenderOperation::RenderText { line, .. } => Some(line),
+            _ => None,
+        })
+    }
+
     static PALETTE: Lazy<ColorPalette> = Lazy::new(|| ColorPalette {
         colors: [("red".into(), Color::new(255, 0, 0))].into(),
         classes: Default::default(),
@@ -295,6 +399,9 @@ mod tests {
     #[case::event(FooterTemplateChunk::Event, &["test".into()])]
     #[case::location(FooterTemplateChunk::Location, &["here".into()])]
     #[case::date(FooterTemplateChunk::Date, &["now".into()])]
+    #[case::elapsed(FooterTemplateChunk::Elapsed, &["02:05".into()])]
+    #[case::remaining(FooterTemplateChunk::Remaining, &["07:55".into()])]
+    #[case::clock(FooterTemplateChunk::Clock, &["13:07".into()])]
     #[case::bold(
         FooterTemplateChunk::Literal("**hi** mom".into()),
         &[Text::new("hi", TextStyle::default().bold()), " mom".into()]
@@ -322,6 +429,59 @@ mod tests {
         FooterLine::new(template, &Default::default(), &VARIABLES, &PALETTE).expect_err("render succeeded");
     }
 
+    #[test]
+    fn remaining_without_duration() {
+        let template = FooterTemplate(vec![FooterTemplateChunk::Remaining]);
+        let vars = FooterVariables { duration: None, ..VARIABLES.clone() };
+        let result = FooterLine::new(template, &Default::default(), &vars, &PALETTE);
+        assert!(matches!(result, Err(InvalidFooterTemplateError::VariableNotSet("duration"))));
+    }
+
+    #[test]
+    fn timed_footer_rerendered() {
+        let template: FooterTemplate = "{elapsed}".parse().unwrap();
+        let style = FooterStyle::Template {
+            left: Some(FooterContent::Template(template)),
+            center: None,
+            right: None,
+            style: Default::default(),
+            behind_style: None,
+            height: 3,
+        };
+        let state = PresentationState::default();
+        let generator = FooterGenerator::new(style, &VARIABLES, &PALETTE, state.clone()).expect("invalid footer");
+        assert!(state.is_time_dependent());
+
+        let dimensions = WindowSize { rows: 10, columns: 10, height: 100, width: 100 };
+        state.set_elapsed_time(Duration::from_secs(3));
+        let line = find_text(generator.as_render_operations(&dimensions)).expect("no text");
+        assert_eq!(line, WeightedLine::from(vec![Text::from("00:03")]));
+
+        state.set_elapsed_time(Duration::from_secs(61));
+        let line = find_text(generator.as_render_operations(&dimensions)).expect("no text");
+        assert_eq!(line, WeightedLine::from(vec![Text::from("01:01")]));
+    }
+
+    #[test]
+    fn progress_bar_pacing() {
+        let style = FooterStyle::ProgressBar {
+            character: '#',
+            style: TextStyle::default(),
+            behind_style: Some(TextStyle::default().fg_color(Color::Red)),
+        };
+        let state = PresentationState::default();
+        let generator = FooterGenerator::new(style, &VARIABLES, &PALETTE, state.clone()).expect("invalid footer");
+        assert!(state.is_time_dependent());
+
+        let dimensions = WindowSize { rows: 10, columns: 10, height: 100, width: 100 };
+        let line = find_text(generator.as_render_operations(&dimensions)).expect("no text");
+        assert_eq!(line, WeightedLine::from(vec![Text::new("####", TextStyle::default())]));
+
+        state.set_pacing(Some(PacingStatus { behind: true, ..Default::default() }));
+        let line = find_text(generator.as_render_operations(&dimensions)).expect("no text");
+        assert_eq!(line, WeightedLine::from(vec![Text::new("####", TextStyle::default().fg_color(Color::Red))]));
+    }
+
     #[test]
     fn interleaved_spans() {
         let chunks = vec![
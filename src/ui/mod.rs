@@ -1,4 +1,8 @@
+pub(crate) mod annotations;
 pub(crate) mod execution;
 pub(crate) mod footer;
 pub(crate) mod modals;
+pub(crate) mod palette;
+pub(crate) mod search;
 pub(crate) mod separator;
+pub(crate) mod speaker_view;
@@ -5,23 +5,44 @@ use crate::{
     markdown::{
         elements::{Line, Text},
         text::WeightedLine,
-        text_style::TextStyle,
+        text_style::{Color, Colors, TextStyle},
     },
-    presentation::PresentationState,
+    presentation::{Presentation, PresentationState, Slide, search::chunk_lines},
     render::{
+        RenderError,
+        engine::{RenderEngine, RenderEngineOptions},
         operation::{
             AsRenderOperations, ImagePosition, ImageRenderProperties, ImageSize, MarginProperties, RenderOperation,
         },
         properties::WindowSize,
     },
-    terminal::image::Image,
+    terminal::{
+        image::Image,
+        virt::{ImageBehavior, StyledChar, TerminalGrid, VirtualTerminal},
+    },
     theme::{Margin, PresentationTheme},
+    ui::search::{IndexEntry, IndexMatch, SlideIndexSearch, fuzzy_match},
+};
+use std::{
+    cell::RefCell,
+    cmp::Reverse,
+    collections::{HashMap, hash_map::Entry},
+    iter,
+    ops::Range,
+    rc::Rc,
 };
-use std::{iter, rc::Rc};
-use unicode_width::UnicodeWidthStr;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 static MODAL_Z_INDEX: i32 = -1;
 
+static OVERVIEW_HEADER_ROWS: usize = 2;
+static OVERVIEW_MIN_TILE_COLUMNS: u16 = 30;
+static OVERVIEW_MAX_TILES_PER_ROW: u16 = 4;
+
+// How many characters to display before a match in a slide's body, when the line needs to be cut.
+static BODY_MATCH_CONTEXT: usize = 10;
+static BODY_INDENT: &str = "    ";
+
 #[derive(Default)]
 pub(crate) struct IndexBuilder {
     titles: Vec<Line>,
@@ -37,12 +58,34 @@ impl IndexBuilder {
         self.background = Some(background);
     }
 
-    pub(crate) fn build(self, theme: &PresentationTheme, state: PresentationState) -> Vec<RenderOperation> {
+    /// Build the slide index modal.
+    ///
+    /// The text in the given slides is used to allow searching for them in the index.
+    pub(crate) fn build(
+        self,
+        theme: &PresentationTheme,
+        state: PresentationState,
+        slides: &[Slide],
+    ) -> (SlideIndex, SlideIndexSearch) {
+        let entries = self
+            .titles
+            .iter()
+            .zip(slides)
+            .map(|(title, slide)| IndexEntry {
+                title: Self::plain_text(&title.0),
+                body: slide.iter_chunks().flat_map(chunk_lines).collect(),
+            })
+            .collect();
+        let search = SlideIndexSearch::new(entries);
+
         let mut builder = ModalBuilder::new("Slides");
         let padder = NumberPadder::new(self.titles.len());
-        for (index, mut title) in self.titles.into_iter().enumerate() {
+        let mut prefixed_titles = Vec::new();
+        for (index, title) in self.titles.iter().enumerate() {
             let index = padder.pad_right(index + 1);
+            let mut title = title.clone();
             title.0.insert(0, format!("{index}: ").into());
+            prefixed_titles.push(title.clone());
             builder.content.push(title);
         }
         let base_style = theme.modals.style;
@@ -51,13 +94,40 @@ impl IndexBuilder {
         let drawer = IndexDrawer {
             prefix,
             rows: content,
+            titles: prefixed_titles,
             suffix,
             state,
+            search: search.clone(),
             content_width,
             selection_style,
             background: self.background,
         };
-        vec![RenderOperation::RenderDynamicTopLevel(Rc::new(drawer))]
+        let drawer = Rc::new(drawer);
+        let operations = vec![RenderOperation::RenderDynamicTopLevel(drawer.clone())];
+        (SlideIndex { operations, drawer: Some(drawer) }, search)
+    }
+
+    fn plain_text(texts: &[Text]) -> String {
+        texts.iter().map(|text| text.content.as_str()).collect()
+    }
+}
+
+/// The slide index modal.
+#[derive(Debug, Default)]
+pub(crate) struct SlideIndex {
+    operations: Vec<RenderOperation>,
+    drawer: Option<Rc<IndexDrawer>>,
+}
+
+impl SlideIndex {
+    /// The operations that render the slide index.
+    pub(crate) fn operations(&self) -> &[RenderOperation] {
+        &self.operations
+    }
+
+    /// Find the slide whose entry is displayed at the given position, if any.
+    pub(crate) fn slide_at(&self, dimensions: &WindowSize, column: u16, row: u16) -> Option<usize> {
+        self.drawer.as_ref()?.slide_at(dimensions, column, row)
     }
 }
 
@@ -65,15 +135,54 @@ impl IndexBuilder {
 struct IndexDrawer {
     prefix: Vec<RenderOperation>,
     rows: Vec<ContentRow>,
+    titles: Vec<Line>,
     suffix: Vec<RenderOperation>,
     content_width: u16,
     state: PresentationState,
+    search: SlideIndexSearch,
     selection_style: TextStyle,
     background: Option<Image>,
 }
 
-impl AsRenderOperations for IndexDrawer {
-    fn as_render_operations(&self, dimensions: &WindowSize) -> Vec<RenderOperation> {
+// The rows displayed in the slide index.
+struct IndexRows {
+    // The rows displayed above the entries, like the search query.
+    header: Vec<ContentRow>,
+
+    // The visible rows along with the slide each of them belongs to.
+    entries: Vec<(Option<usize>, ContentRow)>,
+}
+
+impl IndexRows {
+    // The height of the header, including the separator below it.
+    fn header_height(&self) -> usize {
+        match self.header.is_empty() {
+            true => 0,
+            false => self.header.len() + 1,
+        }
+    }
+}
+
+impl IndexDrawer {
+    fn slide_at(&self, dimensions: &WindowSize, column: u16, row: u16) -> Option<usize> {
+        let left = dimensions.columns.saturating_sub(self.content_width) / 2;
+        if column < left || column >= left + self.content_width {
+            return None;
+        }
+        let rows = self.rows(dimensions);
+        let header_height = rows.header_height();
+        let top = CenterModalContent::top_row(dimensions, header_height + rows.entries.len());
+        // Skip the top border, the heading, and the separator below it.
+        let first_entry = top as usize + 3 + header_height;
+        let index = (row as usize).checked_sub(first_entry)?;
+        rows.entries.get(index)?.0
+    }
+
+    fn rows(&self, dimensions: &WindowSize) -> IndexRows {
+        if self.search.is_active() { self.search_rows(dimensions) } else { self.index_rows(dimensions) }
+    }
+
+    fn index_rows(&self, dimensions: &WindowSize) -> IndexRows {
         let current_slide_index = self.state.current_slide_index();
         let max_rows = (dimensions.rows as f64 * 0.8) as u16;
         let (skip, take) = match self.rows.len() as u16 > max_rows {
@@ -85,15 +194,166 @@ impl AsRenderOperations for IndexDrawer {
             false => (0, self.rows.len()),
         };
         let visible_rows = self.rows.iter().enumerate().skip(skip).take(take);
-        let mut operations = vec![CenterModalContent::new(self.content_width, take, self.background.clone()).into()];
-        operations.extend(self.prefix.iter().cloned());
+        let mut entries = Vec::new();
         for (index, row) in visible_rows {
             let mut row = row.clone();
             if index == current_slide_index {
                 row = row.with_style(self.selection_style);
             }
-            let operation = RenderOperation::RenderText { line: row.build(), alignment: Default::default() };
-            operations.extend([operation, RenderOperation::RenderLineBreak]);
+            entries.push((Some(index), row));
+        }
+        IndexRows { header: Vec::new(), entries }
+    }
+
+    fn search_rows(&self, dimensions: &WindowSize) -> IndexRows {
+        let max_width = self.content_width.saturating_sub(6) as usize;
+        let match_style = TextStyle::default().bold().underlined();
+        let selected = self.search.selected();
+
+        let mut rows = Vec::new();
+        let mut selected_row = 0;
+        let matches = self.search.matches();
+        for (index, index_match) in matches.iter().enumerate() {
+            if index == selected {
+                selected_row = rows.len();
+            }
+            let mut title_rows = self.build_match_rows(index_match, match_style, max_width);
+            if index == selected {
+                title_rows = title_rows.into_iter().map(|row| row.with_style(self.selection_style)).collect();
+            }
+            rows.extend(title_rows.into_iter().map(|row| (Some(index_match.slide), row)));
+        }
+        if matches.is_empty() {
+            let text = Text::new("no matches", TextStyle::default().italics());
+            rows.push((None, ModalBuilder::build_line(vec![text], self.content_width)));
+        }
+
+        // Leave room for the search query and the separator below it.
+        let max_rows = ((dimensions.rows as f64 * 0.8) as usize).saturating_sub(2).max(1);
+        let skip = match rows.len() > max_rows {
+            true => selected_row.saturating_sub(max_rows / 2).min(rows.len() - max_rows),
+            false => 0,
+        };
+        let entries = rows.into_iter().skip(skip).take(max_rows).collect();
+
+        let query = format!("/{}█", self.search.query());
+        let query = Self::truncate(vec![Text::from(query)], max_width);
+        let query_row = ModalBuilder::build_line(query, self.content_width);
+        IndexRows { header: vec![query_row], entries }
+    }
+
+    fn build_match_rows(&self, index_match: &IndexMatch, match_style: TextStyle, max_width: usize) -> Vec<ContentRow> {
+        let IndexMatch { slide, title_positions, body } = index_match;
+        let Some(title) = self.titles.get(*slide) else {
+            return Vec::new();
+        };
+        // Titles are prefixed with the slide number so skip past it.
+        let prefix_length = title.0.first().map(|text| text.content.chars().count()).unwrap_or_default();
+        let positions: Vec<_> = title_positions.iter().map(|position| position + prefix_length).collect();
+        let title = Self::highlight(title.0.clone(), &positions, match_style);
+        let mut rows = vec![ModalBuilder::build_line(Self::truncate(title, max_width), self.content_width)];
+
+        if let Some((line, positions)) = body {
+            // Cut the beginning of the line if the match wouldn't be visible otherwise.
+            let first = positions.first().copied().unwrap_or_default();
+            let skip = match line.width() + BODY_INDENT.len() > max_width {
+                true => first.saturating_sub(BODY_MATCH_CONTEXT),
+                false => 0,
+            };
+            let mut snippet: String = line.chars().skip(skip).collect();
+            let mut positions: Vec<_> = positions.iter().map(|position| position - skip).collect();
+            if skip > 0 {
+                snippet.insert(0, '…');
+                positions.iter_mut().for_each(|position| *position += 1);
+            }
+            let snippet = vec![Text::new(snippet, TextStyle::default().italics())];
+            let mut texts = vec![Text::from(BODY_INDENT)];
+            texts.extend(Self::highlight(snippet, &positions, match_style));
+            rows.push(ModalBuilder::build_line(Self::truncate(texts, max_width), self.content_width));
+        }
+        rows
+    }
+
+    // Apply a style to the characters at the given positions.
+    fn highlight(texts: Vec<Text>, positions: &[usize], style: TextStyle) -> Vec<Text> {
+        let mut output = Vec::new();
+        let mut offset = 0;
+        for text in texts {
+            let mut current = Text::new("", text.style);
+            let mut current_highlighted = false;
+            for c in text.content.chars() {
+                let highlighted = positions.contains(&offset);
+                if highlighted != current_highlighted && !current.content.is_empty() {
+                    output.push(current);
+                    current = Text::new("", text.style);
+                }
+                if highlighted {
+                    current.style = text.style;
+                    current.style.merge(&style);
+                }
+                current_highlighted = highlighted;
+                current.content.push(c);
+                offset += 1;
+            }
+            if !current.content.is_empty() {
+                output.push(current);
+            }
+        }
+        output
+    }
+
+    // Cut texts so they take up at most the given width.
+    fn truncate(texts: Vec<Text>, max_width: usize) -> Vec<Text> {
+        let total_width: usize = texts.iter().map(Text::width).sum();
+        if total_width <= max_width {
+            return texts;
+        }
+        // Leave room for the ellipsis.
+        let mut available = max_width.saturating_sub(1);
+        let mut output = Vec::new();
+        for mut text in texts {
+            let mut content = String::new();
+            for c in text.content.chars() {
+                let width = c.width().unwrap_or(0);
+                if width > available {
+                    available = 0;
+                    break;
+                }
+                available -= width;
+                content.push(c);
+            }
+            text.content = content;
+            output.push(text);
+            if available == 0 {
+                break;
+            }
+        }
+        output.push(Text::from("…"));
+        output
+    }
+}
+
+impl AsRenderOperations for IndexDrawer {
+    fn as_render_operations(&self, dimensions: &WindowSize) -> Vec<RenderOperation> {
+        let rows = self.rows(dimensions);
+        let content_height = rows.header_height() + rows.entries.len();
+        let mut operations =
+            vec![CenterModalContent::new(self.content_width, content_height, self.background.clone()).into()];
+        operations.extend(self.prefix.iter().cloned());
+        for row in &rows.header {
+            operations.extend([
+                RenderOperation::RenderText { line: row.clone().build(), alignment: Default::default() },
+                RenderOperation::RenderLineBreak,
+            ]);
+        }
+        if !rows.header.is_empty() {
+            operations.extend(Border::Separator.render_line(self.content_width));
+        }
+        for (_, row) in rows.entries {
+            operations.extend([
+                RenderOperation::RenderText { line: row.build(), alignment: Default::default() },
+                RenderOperation::RenderLineBreak,
+            ]);
         }
         operations.extend(self.suffix.iter().cloned());
         operations
@@ -123,6 +383,13 @@ impl KeyBindingsModalBuilder {
             Self::build_line("Execute code", &config.execute_code),
             Self::build_line("Reload", &config.reload),
             Self::build_line("Toggle slide index", &config.toggle_slide_index),
+            Self::build_line("Toggle slide overview", &config.toggle_overview),
+            Self::build_line("Toggle annotations", &config.toggle_annotations),
+            Self::build_line("Toggle theme picker", &config.toggle_theme_picker),
+            Self::build_line("Command palette", &config.command_palette),
+            Self::build_line("Search", &config.search),
+            Self::build_line("Next search match", &config.search_next),
+            Self::build_line("Previous search match", &config.search_previous),
             Self::build_line("Close modal", &config.close_modal),
             Self::build_line("Exit", &config.exit),
         ]);
@@ -147,6 +414,183 @@ impl KeyBindingsModalBuilder {
     }
 }
 
+#[derive(Default)]
+pub(crate) struct ThemePickerBuilder {
+    background: Option<Image>,
+}
+
+impl ThemePickerBuilder {
+    pub(crate) fn set_background(&mut self, background: Image) {
+        self.background = Some(background);
+    }
+
+    /// Build the theme picker modal, listing the given theme names.
+    pub(crate) fn build(self, theme: &PresentationTheme, mut names: Vec<String>) -> ThemePicker {
+        names.sort();
+        // Every name is added so the modal's width doesn't change while filtering.
+        let mut builder = ModalBuilder::new("Themes");
+        builder.content.extend(names.iter().map(|name| Line::from(name.clone())));
+        let ModalContent { prefix, suffix, content_width, .. } = builder.build(theme.modals.style);
+        let selection = ThemeSelection { names: Rc::new(names), state: Default::default() };
+        let drawer = ThemePickerDrawer {
+            prefix,
+            suffix,
+            content_width,
+            selection: selection.clone(),
+            selection_style: theme.modals.selection_style,
+            background: self.background,
+        };
+        let operations = vec![RenderOperation::RenderDynamicTopLevel(Rc::new(drawer))];
+        ThemePicker { selection, operations }
+    }
+}
+
+#[derive(Debug, Default)]
+struct ThemeSelectionState {
+    query: String,
+    selected: usize,
+}
+
+/// The themes that can be picked, along with the query used to filter them.
+#[derive(Clone, Debug, Default)]
+struct ThemeSelection {
+    names: Rc<Vec<String>>,
+    state: Rc<RefCell<ThemeSelectionState>>,
+}
+
+impl ThemeSelection {
+    // Find the themes that match the current query, best matches first.
+    //
+    // An empty query matches every theme.
+    fn matches(&self) -> Vec<String> {
+        let query = self.state.borrow().query.clone();
+        if query.trim().is_empty() {
+            return self.names.to_vec();
+        }
+        let mut matches: Vec<_> =
+            self.names.iter().filter_map(|name| fuzzy_match(&query, name).map(|m| (m.score, name.clone()))).collect();
+        // This is a stable sort so themes with the same score stay in order.
+        matches.sort_by_key(|(score, _)| Reverse(*score));
+        matches.into_iter().map(|(_, name)| name).collect()
+    }
+}
+
+/// The theme picker modal.
+///
+/// This is shared between the presenter, which feeds the query into it, and the modal's drawer, which displays the
+/// themes that match it.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ThemePicker {
+    selection: ThemeSelection,
+    operations: Vec<RenderOperation>,
+}
+
+impl ThemePicker {
+    /// The operations that render the theme picker.
+    pub(crate) fn operations(&self) -> &[RenderOperation] {
+        &self.operations
+    }
+
+    /// Clear the query and go back to the first theme.
+    pub(crate) fn reset(&self) {
+        *self.selection.state.borrow_mut() = Default::default();
+    }
+
+    pub(crate) fn push(&self, c: char) {
+        let mut state = self.selection.state.borrow_mut();
+        state.query.push(c);
+        state.selected = 0;
+    }
+
+    pub(crate) fn pop(&self) {
+        let mut state = self.selection.state.borrow_mut();
+        state.query.pop();
+        state.selected = 0;
+    }
+
+    pub(crate) fn select_next(&self) {
+        let total = self.selection.matches().len();
+        let mut state = self.selection.state.borrow_mut();
+        state.selected = (state.selected + 1).min(total.saturating_sub(1));
+    }
+
+    pub(crate) fn select_previous(&self) {
+        let mut state = self.selection.state.borrow_mut();
+        state.selected = state.selected.saturating_sub(1);
+    }
+
+    /// The name of the theme that's currently selected, if any.
+    pub(crate) fn selected_theme(&self) -> Option<String> {
+        let selected = self.selection.state.borrow().selected;
+        self.selection.matches().into_iter().nth(selected)
+    }
+}
+
+#[derive(Debug)]
+struct ThemePickerDrawer {
+    prefix: Vec<RenderOperation>,
+    suffix: Vec<RenderOperation>,
+    content_width: u16,
+    selection: ThemeSelection,
+    selection_style: TextStyle,
+    background: Option<Image>,
+}
+
+impl ThemePickerDrawer {
+    fn entries(&self, dimensions: &WindowSize) -> Vec<ContentRow> {
+        let matches = self.selection.matches();
+        if matches.is_empty() {
+            let text = Text::new("no matches", TextStyle::default().italics());
+            return vec![ModalBuilder::build_line(vec![text], self.content_width)];
+        }
+        let selected = self.selection.state.borrow().selected;
+        // Leave room for the query and the separator below it.
+        let max_rows = ((dimensions.rows as f64 * 0.8) as usize).saturating_sub(2).max(1);
+        let skip = match matches.len() > max_rows {
+            true => selected.saturating_sub(max_rows / 2).min(matches.len() - max_rows),
+            false => 0,
+        };
+        let mut rows = Vec::new();
+        for (index, name) in matches.into_iter().enumerate().skip(skip).take(max_rows) {
+            let mut row = ModalBuilder::build_line(vec![Text::from(name)], self.content_width);
+            if index == selected {
+                row = row.with_style(self.selection_style);
+            }
+            rows.push(row);
+        }
+        rows
+    }
+}
+
+impl AsRenderOperations for ThemePickerDrawer {
+    fn as_render_operations(&self, dimensions: &WindowSize) -> Vec<RenderOperation> {
+        let entries = self.entries(dimensions);
+        // The query and the separator below it go on top of the entries.
+        let content_height = entries.len() + 2;
+        let mut operations =
+            vec![CenterModalContent::new(self.content_width, content_height, self.background.clone()).into()];
+        operations.extend(self.prefix.iter().cloned());
+
+        let max_width = self.content_width.saturating_sub(6) as usize;
+        let query = format!("/{}█", self.selection.state.borrow().query);
+        let query = IndexDrawer::truncate(vec![Text::from(query)], max_width);
+        let query_row = ModalBuilder::build_line(query, self.content_width);
+        operations.extend([
+            RenderOperation::RenderText { line: query_row.build(), alignment: Default::default() },
+            RenderOperation::RenderLineBreak,
+        ]);
+        operations.extend(Border::Separator.render_line(self.content_width));
+        for row in entries {
+            operations.extend([
+                RenderOperation::RenderText { line: row.build(), alignment: Default::default() },
+                RenderOperation::RenderLineBreak,
+            ]);
+        }
+        operations.extend(self.suffix.iter().cloned());
+        operations
+    }
+}
+
 struct ModalBuilder {
     heading: String,
     content: Vec<Line>,
@@ -291,15 +735,21 @@ impl CenterModalContent {
     fn new(content_width: u16, content_height: usize, background: Option<Image>) -> Self {
         Self { content_width, content_height, background }
     }
+
+    // The row where a modal with the given content height starts.
+    fn top_row(dimensions: &WindowSize, content_height: usize) -> u16 {
+        // However many we see + 3 for the title and 1 at the bottom.
+        let content_height = (content_height + 4) as u16;
+        dimensions.rows.saturating_sub(content_height) / 2
+    }
 }
 
 impl AsRenderOperations for CenterModalContent {
     fn as_render_operations(&self, dimensions: &WindowSize) -> Vec<RenderOperation> {
         let margin = dimensions.columns.saturating_sub(self.content_width) / 2;
         let properties = MarginProperties { horizontal: Margin::Fixed(margin), top: 0, bottom: 0 };
-        // However many we see + 3 for the title and 1 at the bottom.
         let content_height = (self.content_height + 4) as u16;
-        let target_row = dimensions.rows.saturating_sub(content_height) / 2;
+        let target_row = Self::top_row(dimensions, self.content_height);
 
         let mut operations =
             vec![RenderOperation::ApplyMargin(properties), RenderOperation::JumpToRow { index: target_row }];
@@ -322,3 +772,540 @@ impl From<CenterModalContent> for RenderOperation {
         Self::RenderDynamicTopLevel(Rc::new(op))
     }
 }
+
+#[derive(Default)]
+pub(crate) struct OverviewBuilder {
+    titles: Vec<String>,
+}
+
+impl OverviewBuilder {
+    pub(crate) fn add_title(&mut self, title: &Line) {
+        self.titles.push(title.0.iter().map(|text| text.content.as_str()).collect());
+    }
+
+    pub(crate) fn build(self, theme: &PresentationTheme) -> SlideOverview {
+        SlideOverview {
+            titles: self.titles,
+            style: theme.modals.style,
+            selection_style: theme.modals.selection_style,
+            thumbnails: Default::default(),
+        }
+    }
+}
+
+/// An overview of the presentation that shows every slide as a thumbnail in a grid.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SlideOverview {
+    titles: Vec<String>,
+    style: TextStyle,
+    selection_style: TextStyle,
+    thumbnails: RefCell<ThumbnailCache>,
+}
+
+impl SlideOverview {
+    /// The number of thumbnails displayed in every row for the given number of columns.
+    pub(crate) fn tiles_per_row(columns: u16) -> usize {
+        (columns / OVERVIEW_MIN_TILE_COLUMNS).clamp(1, OVERVIEW_MAX_TILES_PER_ROW) as usize
+    }
+
+    /// Render the overview into a grid with the given dimensions, selecting the current slide.
+    pub(crate) fn render(
+        &self,
+        presentation: &Presentation,
+        dimensions: WindowSize,
+        options: &RenderEngineOptions,
+    ) -> Result<TerminalGrid, RenderError> {
+        let background = StyledChar { character: ' ', style: self.style };
+        let mut grid = TerminalGrid {
+            rows: vec![vec![background; dimensions.columns as usize]; dimensions.rows as usize],
+            background_color: self.style.colors.background,
+            images: Default::default(),
+            links: Default::default(),
+        };
+        let total_slides = presentation.iter_slides().count();
+        let selected = presentation.current_slide_index();
+        let header = format!("Slide {}/{total_slides}", selected + 1);
+        Self::print(&mut grid, 0, 1, &header, self.style.bold());
+
+        let layout = OverviewLayout::new(total_slides, selected, &dimensions);
+        let mut thumbnails = self.thumbnails.borrow_mut();
+        thumbnails.resize(dimensions);
+        let slides = presentation.iter_slides().enumerate().skip(layout.first_slide).take(layout.visible_slides);
+        for (index, slide) in slides {
+            let (row, column) = layout.tile_position(index);
+            if !layout.fits(row, column, &dimensions) {
+                continue;
+            }
+            let style = if index == selected { self.selection_style } else { self.style };
+            let title = self.titles.get(index).map(String::as_str).unwrap_or_default();
+            let title = format!("{}: {title}", index + 1);
+            self.draw_border(&mut grid, (row, column), (layout.tile_rows, layout.tile_columns), &title, style);
+
+            let thumbnail = match thumbnails.rows.entry(index) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let contents = Self::virtual_render(slide, dimensions, options)?;
+                    entry.insert(Thumbnail::new(&contents, layout.thumbnail_columns, layout.thumbnail_rows).rows)
+                }
+            };
+            for (offset, cells) in thumbnail.iter().enumerate() {
+                let target = &mut grid.rows[row + 1 + offset][column + 1..];
+                for (target, cell) in target.iter_mut().zip(cells) {
+                    *target = *cell;
+                }
+            }
+        }
+        Ok(grid)
+    }
+
+    /// Forget the thumbnail for a slide because its contents changed.
+    pub(crate) fn invalidate(&self, slide: usize) {
+        self.thumbnails.borrow_mut().rows.remove(&slide);
+    }
+
+    /// Find the slide whose thumbnail is displayed at the given position, if any.
+    pub(crate) fn slide_at(
+        &self,
+        presentation: &Presentation,
+        dimensions: &WindowSize,
+        column: u16,
+        row: u16,
+    ) -> Option<usize> {
+        let total_slides = presentation.iter_slides().count();
+        let layout = OverviewLayout::new(total_slides, presentation.current_slide_index(), dimensions);
+        let (column, row) = (column as usize, row as usize);
+        let last_slide = (layout.first_slide + layout.visible_slides).min(total_slides);
+        (layout.first_slide..last_slide).find(|index| {
+            let (top, left) = layout.tile_position(*index);
+            (top..top + layout.tile_rows).contains(&row) && (left..left + layout.tile_columns).contains(&column)
+        })
+    }
+
+    fn draw_border(
+        &self,
+        grid: &mut TerminalGrid,
+        (row, column): (usize, usize),
+        (rows, columns): (usize, usize),
+        title: &str,
+        style: TextStyle,
+    ) {
+        let inner = columns.saturating_sub(2);
+        let title: String = title.chars().take(inner.saturating_sub(2)).collect();
+        let top = format!("┌ {title} {}┐", "─".repeat(inner.saturating_sub(title.width() + 2)));
+        let bottom = format!("└{}┘", "─".repeat(inner));
+        Self::print(grid, row, column, &top, style);
+        for offset in 1..rows - 1 {
+            Self::print(grid, row + offset, column, "│", style);
+            Self::print(grid, row + offset, column + columns - 1, "│", style);
+        }
+        Self::print(grid, row + rows - 1, column, &bottom, style);
+    }
+
+    fn virtual_render(
+        slide: &Slide,
+        dimensions: WindowSize,
+        options: &RenderEngineOptions,
+    ) -> Result<TerminalGrid, RenderError> {
+        let mut term = VirtualTerminal::new(dimensions, ImageBehavior::PrintAscii);
+        let engine = RenderEngine::new(&mut term, dimensions, options.clone());
+        // Show the entire slide rather than the chunks that happen to be visible.
+        engine.render(slide.iter_operations())?;
+        Ok(term.into_contents())
+    }
+
+    fn print(grid: &mut TerminalGrid, row: usize, column: usize, text: &str, style: TextStyle) {
+        let Some(row) = grid.rows.get_mut(row) else {
+            return;
+        };
+        for (cell, character) in row.iter_mut().skip(column).zip(text.chars()) {
+            *cell = StyledChar { character, style };
+        }
+    }
+}
+
+// Where every tile is placed in the overview.
+struct OverviewLayout {
+    tiles_per_row: usize,
+    tile_columns: usize,
+    tile_rows: usize,
+    thumbnail_columns: usize,
+    thumbnail_rows: usize,
+    first_slide: usize,
+    visible_slides: usize,
+}
+
+impl OverviewLayout {
+    fn new(total_slides: usize, selected: usize, dimensions: &WindowSize) -> Self {
+        // Every tile is a thumbnail surrounded by a border and there's a gap before every tile.
+        let tiles_per_row = SlideOverview::tiles_per_row(dimensions.columns);
+        let tile_columns = ((dimensions.columns as usize).saturating_sub(1) / tiles_per_row).saturating_sub(1);
+        let thumbnail_columns = tile_columns.saturating_sub(2).max(1);
+        let body_rows = (dimensions.rows as usize).saturating_sub(OVERVIEW_HEADER_ROWS);
+        // Keep the slide's aspect ratio but make sure at least one row of tiles fits.
+        let scale = thumbnail_columns as f64 / dimensions.columns.max(1) as f64;
+        let thumbnail_rows =
+            ((dimensions.rows as f64 * scale).round() as usize).clamp(1, body_rows.saturating_sub(3).max(1));
+        let tile_rows = thumbnail_rows + 2;
+
+        let total_rows = total_slides.div_ceil(tiles_per_row);
+        let visible_rows = (body_rows / (tile_rows + 1)).max(1);
+        let selected_row = selected / tiles_per_row;
+        let first_row = selected_row.saturating_sub(visible_rows / 2).min(total_rows.saturating_sub(visible_rows));
+        Self {
+            tiles_per_row,
+            tile_columns,
+            tile_rows,
+            thumbnail_columns,
+            thumbnail_rows,
+            first_slide: first_row * tiles_per_row,
+            visible_slides: visible_rows * tiles_per_row,
+        }
+    }
+
+    // Whether a tile starting at the given position fits in the window.
+    //
+    // Tiles need at least one column for the thumbnail besides their borders.
+    fn fits(&self, row: usize, column: usize, dimensions: &WindowSize) -> bool {
+        self.tile_columns >= 3
+            && row + self.tile_rows <= dimensions.rows as usize
+            && column + self.tile_columns <= dimensions.columns as usize
+    }
+
+    // The row and column where the tile for a slide starts.
+    fn tile_position(&self, index: usize) -> (usize, usize) {
+        let row = OVERVIEW_HEADER_ROWS + ((index - self.first_slide) / self.tiles_per_row) * (self.tile_rows + 1);
+        let column = 1 + (index % self.tiles_per_row) * (self.tile_columns + 1);
+        (row, column)
+    }
+}
+
+// The thumbnails drawn in the overview so they're only rendered once for every slide.
+#[derive(Clone, Debug, Default)]
+struct ThumbnailCache {
+    dimensions: Option<WindowSize>,
+    rows: HashMap<usize, Vec<Vec<StyledChar>>>,
+}
+
+impl ThumbnailCache {
+    // Thumbnails are scaled based on the window size so they need to be rendered again when it changes.
+    fn resize(&mut self, dimensions: WindowSize) {
+        if self.dimensions != Some(dimensions) {
+            self.dimensions = Some(dimensions);
+            self.rows.clear();
+        }
+    }
+}
+
+// A pixel in a thumbnail.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Pixel {
+    // Some text was drawn here, using the given color.
+    Ink(Option<Color>),
+
+    // Nothing was drawn here.
+    Paper(Option<Color>),
+}
+
+/// A grid scaled down to a smaller size.
+///
+/// Every cell in the thumbnail holds 2 pixels, one on top of the other, each of which represents an area in the
+/// original grid. This means text can't be read but the shape and colors of the slide are kept.
+struct Thumbnail {
+    rows: Vec<Vec<StyledChar>>,
+}
+
+impl Thumbnail {
+    fn new(grid: &TerminalGrid, columns: usize, rows: usize) -> Self {
+        let source_rows = grid.rows.len();
+        let source_columns = grid.rows.first().map(Vec::len).unwrap_or_default();
+        let span = |index: usize, total: usize, source_total: usize| {
+            let start = index * source_total / total;
+            let end = ((index + 1) * source_total / total).max(start + 1).min(source_total);
+            start..end
+        };
+        let mut output = Vec::new();
+        for row in 0..rows {
+            let mut cells = Vec::new();
+            for column in 0..columns {
+                let column_span = span(column, columns, source_columns);
+                let top = Self::pixel(grid, span(row * 2, rows * 2, source_rows), column_span.clone());
+                let bottom = Self::pixel(grid, span(row * 2 + 1, rows * 2, source_rows), column_span);
+                cells.push(Self::cell(top, bottom));
+            }
+            output.push(cells);
+        }
+        Self { rows: output }
+    }
+
+    fn pixel(grid: &TerminalGrid, rows: Range<usize>, columns: Range<usize>) -> Pixel {
+        let cells =
+            grid.rows.get(rows).unwrap_or_default().iter().flat_map(|row| row.get(columns.clone()).unwrap_or_default());
+        let mut paper = None;
+        for cell in cells {
+            if !cell.character.is_whitespace() {
+                return Pixel::Ink(cell.style.colors.foreground);
+            }
+            paper = paper.or(cell.style.colors.background);
+        }
+        Pixel::Paper(paper.or(grid.background_color))
+    }
+
+    // Merge 2 pixels into a single cell.
+    //
+    // Pixels without a color are drawn using the terminal's default colors so text without a color ends up as the
+    // foreground and empty space without a color ends up as the background.
+    fn cell(top: Pixel, bottom: Pixel) -> StyledChar {
+        let (character, foreground, background) = match (top, bottom) {
+            (Pixel::Paper(color), Pixel::Paper(other)) if color == other => (' ', None, color),
+            (Pixel::Ink(color), Pixel::Ink(other)) if color == other => ('█', color, None),
+            (Pixel::Paper(None), _) | (_, Pixel::Ink(None)) => ('▄', bottom.color(), top.color()),
+            _ => ('▀', top.color(), bottom.color()),
+        };
+        let style = TextStyle::colored(Colors { foreground, background });
+        StyledChar { character, style }
+    }
+}
+
+impl Pixel {
+    fn color(&self) -> Option<Color> {
+        match self {
+            Self::Ink(color) | Self::Paper(color) => *color,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        presentation::builder::utils::Test,
+        render::engine::RenderEngine,
+        resource::Resources,
+        terminal::virt::{TerminalGrid, VirtualTerminal},
+    };
+    use rstest::rstest;
+
+    fn render_index(presentation: &crate::presentation::Presentation) -> Vec<String> {
+        let dimensions = WindowSize { rows: 12, columns: 40, width: 0, height: 0 };
+        let mut term = VirtualTerminal::new(dimensions, Default::default());
+        let engine = RenderEngine::new(&mut term, dimensions, Default::default());
+        engine.render(presentation.iter_slide_index_operations()).expect("render failed");
+        let TerminalGrid { rows, .. } = term.into_contents();
+        rows.into_iter()
+            .map(|row| row.into_iter().map(|c| c.character).collect::<String>().trim_end().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn search_index() {
+        let input = "
+Intro
+===
+
+hello
+
+<!-- end_slide -->
+
+Parsing
+===
+
+the lexer
+";
+        let presentation = Test::new(input).build();
+        let search = presentation.slide_index_search();
+        search.start();
+        for c in "lex".chars() {
+            search.push(c);
+        }
+        let lines = render_index(&presentation);
+        let lines: Vec<_> = lines.iter().map(|line| line.trim()).filter(|line| !line.is_empty()).collect();
+        // The modal's width is based on the titles, so the body snippet gets truncated.
+        let expected = &[
+            "┌────────────────┐",
+            "│     Slides     │",
+            "├────────────────┤",
+            "│  /lex█         │",
+            "├────────────────┤",
+            "│  2: Parsing    │",
+            "│      the lex…  │",
+            "└────────────────┘",
+        ];
+        assert_eq!(lines, expected);
+    }
+
+    #[test]
+    fn overview() {
+        let input = "
+Intro
+===
+
+hello
+
+<!-- end_slide -->
+
+Parsing
+===
+
+the lexer
+
+<!-- end_slide -->
+
+End
+===
+";
+        let mut presentation = Test::new(input).build();
+        presentation.go_to_slide(1);
+        let dimensions = WindowSize { rows: 16, columns: 60, width: 0, height: 0 };
+        let grid =
+            presentation.overview().render(&presentation, dimensions, &Default::default()).expect("render failed");
+        let lines: Vec<String> = grid
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|c| c.character).collect::<String>().trim_end().to_string())
+            .collect();
+        // Only one row of tiles fits so the last slide is not visible.
+        let expected = &[
+            " Slide 2/3",
+            "",
+            " ┌ 1: Intro ────────────────┐ ┌ 2: Parsing ──────────────┐",
+            " │▄▄▄                       │ │▄▄▄▄                      │",
+            " │▄▄▄                       │ │▄▄▄▄                      │",
+            " │                          │ │                          │",
+        ];
+        assert_eq!(&lines[0..6], expected);
+        assert_eq!(lines[10], " └──────────────────────────┘ └──────────────────────────┘");
+        assert!(lines[11..].iter().all(String::is_empty));
+    }
+
+    #[rstest]
+    #[case::empty(0, 0)]
+    #[case::single_cell(1, 1)]
+    #[case::header_only(2, 60)]
+    #[case::short(3, 60)]
+    #[case::one_tile_row(5, 60)]
+    #[case::narrow(16, 4)]
+    fn overview_tiny_window(#[case] rows: u16, #[case] columns: u16) {
+        let presentation = Test::new("hi\n<!-- end_slide -->\nbye").build();
+        let dimensions = WindowSize { rows, columns, width: 0, height: 0 };
+        let grid =
+            presentation.overview().render(&presentation, dimensions, &Default::default()).expect("render failed");
+        assert_eq!(grid.rows.len(), rows as usize);
+        assert!(grid.rows.iter().all(|row| row.len() == columns as usize));
+    }
+
+    #[test]
+    fn overview_thumbnail_cache() {
+        let presentation = Test::new("hi\n<!-- end_slide -->\nbye").build();
+        let overview = presentation.overview();
+        let dimensions = WindowSize { rows: 16, columns: 60, width: 0, height: 0 };
+        overview.render(&presentation, dimensions, &Default::default()).expect("render failed");
+        assert_eq!(overview.thumbnails.borrow().rows.len(), 2);
+
+        overview.invalidate(1);
+        assert_eq!(overview.thumbnails.borrow().rows.len(), 1);
+
+        // A different size means every thumbnail is scaled differently.
+        let dimensions = WindowSize { rows: 20, columns: 60, width: 0, height: 0 };
+        overview.render(&presentation, dimensions, &Default::default()).expect("render failed");
+        assert_eq!(overview.thumbnails.borrow().dimensions, Some(dimensions));
+        assert_eq!(overview.thumbnails.borrow().rows.len(), 2);
+    }
+
+    #[test]
+    fn theme_picker() {
+        let names = ["light", "dark", "catppuccin-latte", "tokyonight-storm"].map(String::from).to_vec();
+        let resources = Resources::new("/tmp/foo", "/tmp/foo", Default::default());
+        let theme =
+            PresentationTheme::new(&Default::default(), &resources, &Default::default()).expect("invalid theme");
+        let picker = ThemePickerBuilder::default().build(&theme, names);
+        // Themes are sorted by name.
+        assert_eq!(picker.selected_theme().as_deref(), Some("catppuccin-latte"));
+        picker.select_previous();
+        assert_eq!(picker.selected_theme().as_deref(), Some("catppuccin-latte"));
+
+        for c in "lt".chars() {
+            picker.push(c);
+        }
+        // Consecutive characters score higher.
+        assert_eq!(picker.selection.matches(), &["catppuccin-latte", "light"]);
+        picker.select_next();
+        picker.select_next();
+        assert_eq!(picker.selected_theme().as_deref(), Some("light"));
+
+        picker.pop();
+        picker.pop();
+        picker.push('x');
+        assert_eq!(picker.selected_theme(), None);
+
+        picker.reset();
+        assert_eq!(picker.selection.matches().len(), 4);
+    }
+
+    #[rstest]
+    #[case::empty(Pixel::Paper(None), Pixel::Paper(None), ' ')]
+    #[case::text(Pixel::Ink(None), Pixel::Ink(None), '█')]
+    #[case::top_text(Pixel::Ink(None), Pixel::Paper(None), '▀')]
+    #[case::bottom_text(Pixel::Paper(None), Pixel::Ink(None), '▄')]
+    #[case::colored_background(Pixel::Paper(Some(Color::Red)), Pixel::Ink(None), '▄')]
+    fn thumbnail_cell(#[case] top: Pixel, #[case] bottom: Pixel, #[case] expected: char) {
+        let cell = Thumbnail::cell(top, bottom);
+        assert_eq!(cell.character, expected);
+    }
+
+    fn find_row(lines: &[String], text: &str) -> u16 {
+        lines.iter().position(|line| line.contains(text)).expect("text not found") as u16
+    }
+
+    #[rstest]
+    #[case::index("")]
+    #[case::search("pars")]
+    fn click_index(#[case] query: &str) {
+        let input = "
+Intro
+===
+
+<!-- end_slide -->
+
+Parsing
+===
+
+the parser
+";
+        let presentation = Test::new(input).build();
+        if !query.is_empty() {
+            let search = presentation.slide_index_search();
+            search.start();
+            query.chars().for_each(|c| search.push(c));
+        }
+        let dimensions = WindowSize { rows: 12, columns: 40, width: 0, height: 0 };
+        let lines = render_index(&presentation);
+        let row = find_row(&lines, "2: Parsing");
+        let index = presentation.slide_index();
+        assert_eq!(index.slide_at(&dimensions, 20, row), Some(1));
+        // Outside of the modal.
+        assert_eq!(index.slide_at(&dimensions, 0, row), None);
+        // The modal's heading.
+        assert_eq!(index.slide_at(&dimensions, 20, find_row(&lines, "Slides")), None);
+    }
+
+    #[test]
+    fn click_overview() {
+        let input = "
+Intro
+===
+
+<!-- end_slide -->
+
+Parsing
+===
+";
+        let presentation = Test::new(input).build();
+        let dimensions = WindowSize { rows: 16, columns: 60, width: 0, height: 0 };
+        let overview = presentation.overview();
+        // See the `overview` test for where tiles are.
+        assert_eq!(overview.slide_at(&presentation, &dimensions, 5, 5), Some(0));
+        assert_eq!(overview.slide_at(&presentation, &dimensions, 40, 2), Some(1));
+        assert_eq!(overview.slide_at(&presentation, &dimensions, 29, 5), None);
+        assert_eq!(overview.slide_at(&presentation, &dimensions, 5, 12), None);
+    }
+}
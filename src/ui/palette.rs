@@ -0,0 +1,390 @@
+use crate::{
+    commands::listener::{Command, CommandDiscriminants},
+    markdown::{elements::Text, text::WeightedLine, text_style::TextStyle},
+    render::{
+        operation::{AsRenderOperations, RenderOperation},
+        properties::WindowSize,
+    },
+    theme::{Alignment, Margin},
+    utils::tail_within_width,
+};
+use strum::IntoEnumIterator;
+use unicode_width::UnicodeWidthStr;
+
+// The commands that don't map directly to a [Command].
+const GOTO: &str = "goto";
+const THEME: &str = "theme";
+const EXEC: &str = "exec";
+const RELOAD: &str = "reload";
+const NOTES: &str = "notes";
+
+/// A command typed in the command palette.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum PaletteCommand {
+    /// Run a command.
+    Run(Command),
+
+    /// Switch to the theme with the given name.
+    Theme(String),
+
+    /// Reload the presentation, even if it's not in development mode.
+    Reload,
+
+    /// Toggle between displaying the slides and their speaker notes.
+    ToggleNotes,
+}
+
+impl PaletteCommand {
+    /// Parse the text typed in the palette.
+    pub(crate) fn parse(input: &str) -> Result<Self, PaletteError> {
+        let mut parts = input.split_whitespace();
+        let name = parts.next().ok_or(PaletteError::Empty)?;
+        let argument = parts.next();
+        if let Some(extra) = parts.next() {
+            return Err(PaletteError::UnexpectedArgument(extra.into()));
+        }
+        // `:12` is a shorthand for `:goto 12`.
+        if name.parse::<u32>().is_ok() {
+            return Self::parse_slide(Some(name)).map(Self::Run);
+        }
+        let command = match name {
+            GOTO => return Self::parse_slide(argument).map(Self::Run),
+            THEME => return argument.map(|name| Self::Theme(name.into())).ok_or(PaletteError::MissingArgument(THEME)),
+            EXEC => Self::Run(Command::RenderAsyncOperations),
+            RELOAD => Self::Reload,
+            NOTES => Self::ToggleNotes,
+            _ => Self::Run(find_command(name).ok_or_else(|| PaletteError::UnknownCommand(name.into()))?),
+        };
+        match argument {
+            Some(argument) => Err(PaletteError::UnexpectedArgument(argument.into())),
+            None => Ok(command),
+        }
+    }
+
+    fn parse_slide(argument: Option<&str>) -> Result<Command, PaletteError> {
+        let argument = argument.ok_or(PaletteError::MissingArgument(GOTO))?;
+        let slide = argument.parse().map_err(|_| PaletteError::InvalidSlide(argument.into()))?;
+        Ok(Command::GoToSlide(slide))
+    }
+}
+
+fn find_command(name: &str) -> Option<Command> {
+    CommandDiscriminants::iter()
+        .find(|discriminant| <&str>::from(discriminant) == name)
+        .and_then(Command::from_discriminant)
+}
+
+/// The names of every command that can be typed in the palette, sorted alphabetically.
+pub(crate) fn command_names() -> Vec<&'static str> {
+    let commands = CommandDiscriminants::iter()
+        .filter(|discriminant| Command::from_discriminant(*discriminant).is_some())
+        .filter(|discriminant| *discriminant != CommandDiscriminants::OpenCommandPalette)
+        .map(<&str>::from);
+    let mut names: Vec<_> = [GOTO, THEME, EXEC, RELOAD, NOTES].into_iter().chain(commands).collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub(crate) enum PaletteError {
+    #[error("no command given")]
+    Empty,
+
+    #[error("unknown command: {0}")]
+    UnknownCommand(String),
+
+    #[error("{0} requires an argument")]
+    MissingArgument(&'static str),
+
+    #[error("unexpected argument: {0}")]
+    UnexpectedArgument(String),
+
+    #[error("invalid slide number: {0}")]
+    InvalidSlide(String),
+
+    #[error("unknown theme: {0}")]
+    UnknownTheme(String),
+}
+
+/// The text being typed in the command palette.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PalettePrompt {
+    pub(crate) input: String,
+
+    /// The candidates for the word being completed.
+    pub(crate) completions: Vec<String>,
+
+    /// The candidate in [PalettePrompt::completions] that's currently in the input.
+    pub(crate) selected: usize,
+
+    /// The error caused by the last submitted command, if any.
+    pub(crate) error: Option<String>,
+
+    // The input before the word being completed.
+    completion_base: String,
+}
+
+/// A vim-like command palette, where commands are typed after a `:`.
+#[derive(Debug, Default)]
+pub(crate) struct CommandPalette {
+    prompt: Option<PalettePrompt>,
+}
+
+impl CommandPalette {
+    /// Open the palette.
+    pub(crate) fn start(&mut self) {
+        self.prompt = Some(Default::default());
+    }
+
+    /// Close the palette.
+    pub(crate) fn cancel(&mut self) {
+        self.prompt = None;
+    }
+
+    pub(crate) fn is_active(&self) -> bool {
+        self.prompt.is_some()
+    }
+
+    pub(crate) fn prompt(&self) -> Option<&PalettePrompt> {
+        self.prompt.as_ref()
+    }
+
+    pub(crate) fn push(&mut self, c: char) {
+        if let Some(prompt) = &mut self.prompt {
+            prompt.input.push(c);
+            Self::reset_completions(prompt);
+        }
+    }
+
+    pub(crate) fn pop(&mut self) {
+        if let Some(prompt) = &mut self.prompt {
+            prompt.input.pop();
+            Self::reset_completions(prompt);
+        }
+    }
+
+    /// Complete the word being typed.
+    ///
+    /// The first time this is called the word is replaced with the first candidate. Calling it again cycles through
+    /// the rest of them.
+    pub(crate) fn complete(&mut self, themes: &[String]) {
+        let Some(prompt) = &mut self.prompt else {
+            return;
+        };
+        if !prompt.completions.is_empty() {
+            prompt.selected = (prompt.selected + 1) % prompt.completions.len();
+            prompt.input = format!("{}{}", prompt.completion_base, prompt.completions[prompt.selected]);
+            return;
+        }
+        let (base, word) = match prompt.input.rsplit_once(' ') {
+            Some((base, word)) => (format!("{base} "), word),
+            None => (String::new(), prompt.input.as_str()),
+        };
+        let candidates: Vec<String> = match base.split_whitespace().collect::<Vec<_>>().as_slice() {
+            [] => command_names().into_iter().filter(|name| name.starts_with(word)).map(String::from).collect(),
+            [THEME] => themes.iter().filter(|name| name.starts_with(word)).cloned().collect(),
+            _ => Vec::new(),
+        };
+        match candidates.as_slice() {
+            [] => (),
+            [candidate] => {
+                prompt.input = format!("{base}{candidate}");
+                if [GOTO, THEME].contains(&candidate.as_str()) {
+                    prompt.input.push(' ');
+                }
+            }
+            [first, ..] => {
+                prompt.input = format!("{base}{first}");
+                prompt.completion_base = base;
+                prompt.completions = candidates;
+                prompt.selected = 0;
+            }
+        };
+    }
+
+    /// Submit the command typed so far.
+    ///
+    /// If the command is invalid, the palette stays open and displays the error.
+    pub(crate) fn submit(&mut self) -> Option<PaletteCommand> {
+        let prompt = self.prompt.as_mut()?;
+        match PaletteCommand::parse(&prompt.input) {
+            Ok(command) => {
+                self.prompt = None;
+                Some(command)
+            }
+            Err(PaletteError::Empty) => {
+                self.prompt = None;
+                None
+            }
+            Err(e) => {
+                self.fail(e);
+                None
+            }
+        }
+    }
+
+    /// Display an error that happened when running a command.
+    pub(crate) fn fail(&mut self, error: PaletteError) {
+        let prompt = self.prompt.get_or_insert_default();
+        prompt.error = Some(error.to_string());
+        Self::reset_completions(prompt);
+    }
+
+    fn reset_completions(prompt: &mut PalettePrompt) {
+        prompt.completions.clear();
+        prompt.selected = 0;
+    }
+}
+
+/// The bar at the bottom of the screen where commands are typed.
+#[derive(Debug)]
+pub(crate) struct PaletteBar {
+    pub(crate) prompt: PalettePrompt,
+    pub(crate) style: TextStyle,
+    pub(crate) selection_style: TextStyle,
+}
+
+impl PaletteBar {
+    fn fill(texts: &mut Vec<Text>, style: TextStyle, columns: u16) {
+        let width: usize = texts.iter().map(Text::width).sum();
+        let padding = (columns as usize).saturating_sub(width);
+        texts.push(Text::new(" ".repeat(padding), style));
+    }
+}
+
+impl AsRenderOperations for PaletteBar {
+    fn as_render_operations(&self, dimensions: &WindowSize) -> Vec<RenderOperation> {
+        let alignment = Alignment::Left { margin: Margin::Fixed(0) };
+        let mut operations = Vec::new();
+        if !self.prompt.completions.is_empty() {
+            let mut texts = Vec::new();
+            let mut width = 0;
+            for (index, candidate) in self.prompt.completions.iter().enumerate() {
+                let text = format!(" {candidate} ");
+                width += text.width();
+                if width > dimensions.columns as usize {
+                    break;
+                }
+                let style = match index == self.prompt.selected {
+                    true => self.selection_style.merged(&self.style),
+                    false => self.style,
+                };
+                texts.push(Text::new(text, style));
+            }
+            Self::fill(&mut texts, self.style, dimensions.columns);
+            operations.push(RenderOperation::JumpToBottomRow { index: 1 });
+            operations.push(RenderOperation::RenderText { line: WeightedLine::from(texts), alignment });
+        }
+        let mut text = format!(":{}█", self.prompt.input);
+        if let Some(error) = &self.prompt.error {
+            text.push_str(&format!("  ({error})"));
+        }
+        // Keep the end of the input visible if it doesn't fit.
+        let text = tail_within_width(&text, dimensions.columns as usize);
+        let mut texts = vec![Text::new(text, self.style)];
+        Self::fill(&mut texts, self.style, dimensions.columns);
+        operations.push(RenderOperation::JumpToBottomRow { index: 0 });
+        operations.push(RenderOperation::RenderText { line: WeightedLine::from(texts), alignment });
+        operations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::goto("goto 12", PaletteCommand::Run(Command::GoToSlide(12)))]
+    #[case::number("12", PaletteCommand::Run(Command::GoToSlide(12)))]
+    #[case::theme("theme dark", PaletteCommand::Theme("dark".into()))]
+    #[case::exec("exec", PaletteCommand::Run(Command::RenderAsyncOperations))]
+    #[case::reload(" reload ", PaletteCommand::Reload)]
+    #[case::notes("notes", PaletteCommand::ToggleNotes)]
+    #[case::discriminant("toggle_overview", PaletteCommand::Run(Command::ToggleOverview))]
+    fn parse(#[case] input: &str, #[case] expected: PaletteCommand) {
+        assert_eq!(PaletteCommand::parse(input), Ok(expected));
+    }
+
+    #[rstest]
+    #[case::empty("", PaletteError::Empty)]
+    #[case::unknown("potato", PaletteError::UnknownCommand("potato".into()))]
+    #[case::has_data("click", PaletteError::UnknownCommand("click".into()))]
+    #[case::missing_slide("goto", PaletteError::MissingArgument(GOTO))]
+    #[case::invalid_slide("goto x", PaletteError::InvalidSlide("x".into()))]
+    #[case::missing_theme("theme", PaletteError::MissingArgument(THEME))]
+    #[case::extra_argument("next 1", PaletteError::UnexpectedArgument("1".into()))]
+    #[case::too_many_arguments("goto 1 2", PaletteError::UnexpectedArgument("2".into()))]
+    fn parse_error(#[case] input: &str, #[case] expected: PaletteError) {
+        assert_eq!(PaletteCommand::parse(input), Err(expected));
+    }
+
+    fn type_text(palette: &mut CommandPalette, text: &str) {
+        for c in text.chars() {
+            palette.push(c);
+        }
+    }
+
+    fn input(palette: &CommandPalette) -> &str {
+        &palette.prompt().expect("not active").input
+    }
+
+    #[test]
+    fn complete_command() {
+        let mut palette = CommandPalette::default();
+        palette.start();
+        type_text(&mut palette, "go");
+        palette.complete(&[]);
+        assert_eq!(input(&palette), "goto ");
+
+        palette.cancel();
+        palette.start();
+        type_text(&mut palette, "toggle_");
+        palette.complete(&[]);
+        assert_eq!(input(&palette), "toggle_annotations");
+        palette.complete(&[]);
+        assert_eq!(input(&palette), "toggle_key_bindings_config");
+
+        // Typing starts over.
+        palette.pop();
+        palette.complete(&[]);
+        assert_eq!(input(&palette), "toggle_key_bindings_config");
+        assert!(palette.prompt().unwrap().completions.is_empty());
+    }
+
+    #[test]
+    fn complete_theme() {
+        let themes = &["dark".to_string(), "catppuccin-latte".to_string(), "catppuccin-mocha".to_string()];
+        let mut palette = CommandPalette::default();
+        palette.start();
+        type_text(&mut palette, "theme cat");
+        palette.complete(themes);
+        assert_eq!(input(&palette), "theme catppuccin-latte");
+        palette.complete(themes);
+        palette.complete(themes);
+        assert_eq!(input(&palette), "theme catppuccin-latte");
+        assert_eq!(palette.submit(), Some(PaletteCommand::Theme("catppuccin-latte".into())));
+        assert!(!palette.is_active());
+    }
+
+    #[test]
+    fn submit_error() {
+        let mut palette = CommandPalette::default();
+        palette.start();
+        type_text(&mut palette, "potato");
+        assert_eq!(palette.submit(), None);
+        assert!(palette.is_active());
+        assert_eq!(palette.prompt().unwrap().error.as_deref(), Some("unknown command: potato"));
+    }
+
+    #[test]
+    fn command_names_from_discriminants() {
+        let names = command_names();
+        assert!(names.contains(&"first_slide"));
+        assert!(names.contains(&"toggle_slide_index"));
+        assert!(!names.contains(&"go_to_slide"));
+        assert!(!names.contains(&"open_command_palette"));
+        assert!(names.is_sorted());
+    }
+}
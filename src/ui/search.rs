@@ -0,0 +1,323 @@
+use crate::{
+    markdown::{elements::Text, text::WeightedLine, text_style::TextStyle},
+    presentation::search::SearchPrompt,
+    render::{
+        operation::{AsRenderOperations, RenderOperation},
+        properties::WindowSize,
+    },
+    theme::{Alignment, Margin},
+};
+use std::{cell::RefCell, cmp::Reverse, rc::Rc};
+use unicode_width::UnicodeWidthStr;
+
+// Matching characters in a title is worth a lot more than matching them in the slide's body.
+const TITLE_BONUS: i64 = 1000;
+const CONSECUTIVE_BONUS: i64 = 5;
+const WORD_START_BONUS: i64 = 3;
+
+/// A fuzzy match of a query against some text.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct FuzzyMatch {
+    /// How good this match is. Higher is better.
+    pub(crate) score: i64,
+
+    /// The positions, in characters, of every matched character in the text.
+    pub(crate) positions: Vec<usize>,
+}
+
+/// Match a query against a text, case insensitively.
+///
+/// Every character in the query must appear in the text in the same order, although not necessarily next to each
+/// other. Whitespace in the query is ignored.
+pub(crate) fn fuzzy_match(query: &str, text: &str) -> Option<FuzzyMatch> {
+    let query: Vec<char> = query.chars().filter(|c| !c.is_whitespace()).map(lowercase).collect();
+    let text: Vec<char> = text.chars().collect();
+    let first = *query.first()?;
+    let mut best: Option<FuzzyMatch> = None;
+    // Try every starting point and keep the best one, otherwise "ab" would match the first "a" in "a cab".
+    for start in (0..text.len()).filter(|index| lowercase(text[*index]) == first) {
+        let Some(candidate) = match_from(&query, &text, start) else {
+            // If it can't match from here it won't match from any later starting point either.
+            break;
+        };
+        if best.as_ref().is_none_or(|best| candidate.score > best.score) {
+            best = Some(candidate);
+        }
+    }
+    best
+}
+
+fn match_from(query: &[char], text: &[char], start: usize) -> Option<FuzzyMatch> {
+    let mut positions = Vec::with_capacity(query.len());
+    let mut score = 0;
+    let mut text_index = start;
+    for query_char in query {
+        let offset = text[text_index..].iter().position(|c| lowercase(*c) == *query_char)?;
+        let position = text_index + offset;
+        score += 1;
+        match positions.last() {
+            Some(last) if last + 1 == position => score += CONSECUTIVE_BONUS,
+            Some(last) => score -= (position - last - 1).min(WORD_START_BONUS as usize * 2) as i64,
+            None => (),
+        };
+        if position == 0 || !text[position - 1].is_alphanumeric() {
+            score += WORD_START_BONUS;
+        }
+        positions.push(position);
+        text_index = position + 1;
+    }
+    Some(FuzzyMatch { score, positions })
+}
+
+fn lowercase(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// A slide that can be searched for in the slide index.
+#[derive(Clone, Debug)]
+pub(crate) struct IndexEntry {
+    pub(crate) title: String,
+    pub(crate) body: Vec<String>,
+}
+
+/// A slide that matched a search in the slide index.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct IndexMatch {
+    /// The index of the slide that matched.
+    pub(crate) slide: usize,
+
+    /// The positions of the matched characters in the slide's title.
+    pub(crate) title_positions: Vec<usize>,
+
+    /// The line in the slide's body that matched, if the title didn't.
+    pub(crate) body: Option<(String, Vec<usize>)>,
+}
+
+#[derive(Debug, Default)]
+struct SearchState {
+    query: String,
+    selected: usize,
+    active: bool,
+}
+
+/// A search over the slides in the slide index.
+///
+/// This is shared between the presenter, which feeds the query into it, and the slide index modal, which
+/// displays the results.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SlideIndexSearch {
+    entries: Rc<Vec<IndexEntry>>,
+    state: Rc<RefCell<SearchState>>,
+}
+
+impl SlideIndexSearch {
+    pub(crate) fn new(entries: Vec<IndexEntry>) -> Self {
+        Self { entries: Rc::new(entries), state: Default::default() }
+    }
+
+    /// Start a new search.
+    pub(crate) fn start(&self) {
+        *self.state.borrow_mut() = SearchState { active: true, ..Default::default() };
+    }
+
+    /// Stop searching.
+    pub(crate) fn stop(&self) {
+        *self.state.borrow_mut() = SearchState::default();
+    }
+
+    pub(crate) fn is_active(&self) -> bool {
+        self.state.borrow().active
+    }
+
+    pub(crate) fn query(&self) -> String {
+        self.state.borrow().query.clone()
+    }
+
+    pub(crate) fn push(&self, c: char) {
+        let mut state = self.state.borrow_mut();
+        state.query.push(c);
+        state.selected = 0;
+    }
+
+    pub(crate) fn pop(&self) {
+        let mut state = self.state.borrow_mut();
+        state.query.pop();
+        state.selected = 0;
+    }
+
+    pub(crate) fn select_next(&self) {
+        let total = self.matches().len();
+        let mut state = self.state.borrow_mut();
+        state.selected = (state.selected + 1).min(total.saturating_sub(1));
+    }
+
+    pub(crate) fn select_previous(&self) {
+        let mut state = self.state.borrow_mut();
+        state.selected = state.selected.saturating_sub(1);
+    }
+
+    /// The position of the selected match within [SlideIndexSearch::matches].
+    pub(crate) fn selected(&self) -> usize {
+        self.state.borrow().selected
+    }
+
+    /// The index of the slide that's currently selected, if any.
+    pub(crate) fn selected_slide(&self) -> Option<usize> {
+        self.matches().get(self.selected()).map(|m| m.slide)
+    }
+
+    /// Find the slides that match the current query, best matches first.
+    ///
+    /// An empty query matches every slide.
+    pub(crate) fn matches(&self) -> Vec<IndexMatch> {
+        let query = self.query();
+        let mut matches = Vec::new();
+        for (slide, entry) in self.entries.iter().enumerate() {
+            if query.trim().is_empty() {
+                matches.push((0, IndexMatch { slide, title_positions: Vec::new(), body: None }));
+                continue;
+            }
+            if let Some(title_match) = fuzzy_match(&query, &entry.title) {
+                let score = title_match.score + TITLE_BONUS;
+                matches.push((score, IndexMatch { slide, title_positions: title_match.positions, body: None }));
+                continue;
+            }
+            let body_match = entry
+                .body
+                .iter()
+                .filter_map(|line| fuzzy_match(&query, line).map(|m| (m, line)))
+                .max_by_key(|(m, _)| m.score);
+            if let Some((body_match, line)) = body_match {
+                let body = Some((line.clone(), body_match.positions));
+                matches.push((body_match.score, IndexMatch { slide, title_positions: Vec::new(), body }));
+            }
+        }
+        // This is a stable sort so slides with the same score stay in order.
+        matches.sort_by_key(|(score, _)| Reverse(*score));
+        matches.into_iter().map(|(_, m)| m).collect()
+    }
+}
+
+/// The styles used when searching through a presentation.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SearchStyles {
+    /// The style of the prompt where the query is typed.
+    pub(crate) prompt: TextStyle,
+
+    /// The style applied on top of any text that matches the query.
+    pub(crate) highlight: TextStyle,
+}
+
+/// The bar at the bottom of the screen where a search query is typed.
+#[derive(Debug)]
+pub(crate) struct SearchBar {
+    pub(crate) prompt: SearchPrompt,
+    pub(crate) style: TextStyle,
+}
+
+impl AsRenderOperations for SearchBar {
+    fn as_render_operations(&self, dimensions: &WindowSize) -> Vec<RenderOperation> {
+        let mut text = format!("/{}█", self.prompt.query);
+        if self.prompt.no_matches {
+            text.push_str("  (no matches)");
+        }
+        // Keep the end of the query visible if it doesn't fit.
+        while text.width() > dimensions.columns as usize {
+            text.remove(0);
+        }
+        let padding = dimensions.columns as usize - text.width();
+        text.extend(std::iter::repeat_n(' ', padding));
+        let line = WeightedLine::from(vec![Text::new(text, self.style)]);
+        vec![
+            RenderOperation::JumpToBottomRow { index: 0 },
+            RenderOperation::RenderText { line, alignment: Alignment::Left { margin: Margin::Fixed(0) } },
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::exact("hello", "hello", &[0, 1, 2, 3, 4])]
+    #[case::case_insensitive("HeL", "hello", &[0, 1, 2])]
+    #[case::gaps("hlo", "hello", &[0, 2, 4])]
+    #[case::best_start("ab", "a cab", &[3, 4])]
+    #[case::ignores_whitespace("h o", "hello", &[0, 4])]
+    fn matches(#[case] query: &str, #[case] text: &str, #[case] expected: &[usize]) {
+        let fuzzy = fuzzy_match(query, text).expect("no match");
+        assert_eq!(fuzzy.positions, expected);
+    }
+
+    #[rstest]
+    #[case::missing_char("hellx", "hello")]
+    #[case::wrong_order("oh", "hello")]
+    #[case::empty_query("", "hello")]
+    fn no_match(#[case] query: &str, #[case] text: &str) {
+        assert_eq!(fuzzy_match(query, text), None);
+    }
+
+    #[test]
+    fn consecutive_is_better() {
+        let consecutive = fuzzy_match("ab", "xxabxx").unwrap();
+        let scattered = fuzzy_match("ab", "xaxxbx").unwrap();
+        assert!(consecutive.score > scattered.score);
+    }
+
+    fn make_search() -> SlideIndexSearch {
+        let entry = |title: &str, body: &[&str]| IndexEntry {
+            title: title.into(),
+            body: body.iter().map(|line| line.to_string()).collect(),
+        };
+        SlideIndexSearch::new(vec![
+            entry("Introduction", &["welcome to the talk"]),
+            entry("Architecture", &["the parser builds a tree"]),
+            entry("Parser internals", &["tokens"]),
+        ])
+    }
+
+    #[test]
+    fn empty_query_matches_all() {
+        let search = make_search();
+        search.start();
+        let slides: Vec<_> = search.matches().into_iter().map(|m| m.slide).collect();
+        assert_eq!(slides, &[0, 1, 2]);
+    }
+
+    #[test]
+    fn title_matches_first() {
+        let search = make_search();
+        search.start();
+        for c in "parser".chars() {
+            search.push(c);
+        }
+        let matches = search.matches();
+        let slides: Vec<_> = matches.iter().map(|m| m.slide).collect();
+        assert_eq!(slides, &[2, 1]);
+        assert_eq!(matches[0].title_positions, &[0, 1, 2, 3, 4, 5]);
+        assert_eq!(matches[1].body, Some(("the parser builds a tree".into(), vec![4, 5, 6, 7, 8, 9])));
+    }
+
+    #[test]
+    fn selection() {
+        let search = make_search();
+        search.start();
+        search.push('t');
+        assert_eq!(search.selected_slide(), Some(0));
+
+        search.select_next();
+        search.select_next();
+        search.select_next();
+        assert_eq!(search.selected(), 2);
+
+        // Typing resets the selection.
+        search.push('a');
+        assert_eq!(search.selected(), 0);
+
+        search.stop();
+        assert!(!search.is_active());
+        assert_eq!(search.query(), "");
+    }
+}
@@ -0,0 +1,304 @@
+use crate::{
+    markdown::text_style::{Color, TextStyle},
+    presentation::{
+        Slide,
+        clock::{PacingStatus, TimerDisplay},
+    },
+    render::{
+        RenderError,
+        engine::{RenderEngine, RenderEngineOptions},
+        properties::WindowSize,
+    },
+    terminal::virt::{ImageBehavior, StyledChar, TerminalGrid, VirtualTerminal},
+};
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    time::Duration,
+};
+use unicode_width::UnicodeWidthChar;
+
+const HEADER_ROWS: u16 = 2;
+
+/// A speaker view that shows the current slide's notes along with a preview of the next slide.
+pub(crate) struct SpeakerView<'a> {
+    pub(crate) notes: &'a Slide,
+    pub(crate) next_slide: Option<&'a TerminalGrid>,
+    pub(crate) current_slide: usize,
+    pub(crate) total_slides: usize,
+    pub(crate) elapsed: Duration,
+    pub(crate) pacing: Option<PacingStatus>,
+}
+
+impl SpeakerView<'_> {
+    /// Render this view into a grid with the given dimensions.
+    pub(crate) fn render(
+        &self,
+        dimensions: WindowSize,
+        options: &RenderEngineOptions,
+    ) -> Result<TerminalGrid, RenderError> {
+        let notes_dimensions = Self::notes_dimensions(dimensions);
+        let notes = Self::virtual_render(self.notes, notes_dimensions, options)?;
+        let mut grid = TerminalGrid {
+            rows: vec![vec![StyledChar::default(); dimensions.columns as usize]; dimensions.rows as usize],
+            background_color: notes.background_color,
+            images: Default::default(),
+            links: Default::default(),
+        };
+        Self::blit(&mut grid, &notes, HEADER_ROWS, 0);
+
+        let bold = TextStyle::default().bold();
+        Self::print(&mut grid, 0, 1, &self.counters(), bold);
+        let (timer, timer_style) = self.timer();
+        let timer_column = dimensions.columns.saturating_sub(timer.len() as u16 + 1);
+        Self::print(&mut grid, 0, timer_column, &timer, timer_style);
+        Self::print(&mut grid, 1, 0, &"─".repeat(dimensions.columns as usize), TextStyle::default());
+        for row in HEADER_ROWS..dimensions.rows {
+            Self::print(&mut grid, row, notes_dimensions.columns, "│", TextStyle::default());
+        }
+
+        let preview_column = notes_dimensions.columns + 1;
+        match self.next_slide {
+            Some(preview) => {
+                Self::print(&mut grid, HEADER_ROWS, preview_column + 1, "Next slide", bold);
+                Self::blit(&mut grid, preview, HEADER_ROWS + 1, preview_column);
+            }
+            None => Self::print(&mut grid, HEADER_ROWS, preview_column + 1, "End of presentation", bold),
+        };
+        Ok(grid)
+    }
+
+    /// Render a preview of a slide that fits in a speaker view with the given dimensions.
+    pub(crate) fn render_preview(
+        slide: &Slide,
+        dimensions: WindowSize,
+        options: &RenderEngineOptions,
+    ) -> Result<TerminalGrid, RenderError> {
+        let body = dimensions.shrink_rows(HEADER_ROWS);
+        // Leave one column for the separator between both panes.
+        let columns = body.columns.saturating_sub(Self::notes_dimensions(dimensions).columns + 1);
+        let mut preview = Self::virtual_render(slide, Self::scale(dimensions, columns), options)?;
+        Self::fill_background(&mut preview);
+        Ok(preview)
+    }
+
+    fn notes_dimensions(dimensions: WindowSize) -> WindowSize {
+        let body = dimensions.shrink_rows(HEADER_ROWS);
+        body.set_columns(body.columns * 3 / 5)
+    }
+
+    fn timer(&self) -> (String, TextStyle) {
+        let elapsed = TimerDisplay(self.elapsed);
+        let style = TextStyle::default().bold();
+        match self.pacing {
+            Some(pacing) => {
+                let style = if pacing.behind { style.fg_color(Color::Red) } else { style };
+                (format!("{elapsed} / {}", TimerDisplay(pacing.budget)), style)
+            }
+            None => (elapsed.to_string(), style),
+        }
+    }
+
+    fn counters(&self) -> String {
+        let mut counters = format!("Slide {}/{}", self.current_slide, self.total_slides);
+        let total_chunks = self.notes.iter_chunks().count();
+        if total_chunks > 1 {
+            let current_chunk = self.notes.current_chunk_index() + 1;
+            counters.push_str(&format!(" · Chunk {current_chunk}/{total_chunks}"));
+        }
+        counters
+    }
+
+    // Scale the window down to the given number of columns, keeping its aspect ratio.
+    fn scale(dimensions: WindowSize, columns: u16) -> WindowSize {
+        let ratio = columns as f64 / dimensions.columns.max(1) as f64;
+        WindowSize {
+            rows: (dimensions.rows as f64 * ratio) as u16,
+            columns,
+            height: (dimensions.height as f64 * ratio) as u16,
+            width: (dimensions.width as f64 * ratio) as u16,
+        }
+    }
+
+    fn virtual_render(
+        slide: &Slide,
+        dimensions: WindowSize,
+        options: &RenderEngineOptions,
+    ) -> Result<TerminalGrid, RenderError> {
+        let mut term = VirtualTerminal::new(dimensions, ImageBehavior::PrintAscii);
+        let engine = RenderEngine::new(&mut term, dimensions, options.clone());
+        engine.render(slide.iter_visible_operations())?;
+        Ok(term.into_contents())
+    }
+
+    // Make the slide's background explicit so it's kept when this is merged into another grid.
+    fn fill_background(grid: &mut TerminalGrid) {
+        let Some(color) = grid.background_color else {
+            return;
+        };
+        for cell in grid.rows.iter_mut().flatten() {
+            if cell.style.colors.background.is_none() {
+                cell.style = cell.style.bg_color(color);
+            }
+        }
+    }
+
+    fn blit(target: &mut TerminalGrid, source: &TerminalGrid, row: u16, column: u16) {
+        for (target_row, source_row) in target.rows.iter_mut().skip(row as usize).zip(&source.rows) {
+            for (target_cell, source_cell) in target_row.iter_mut().skip(column as usize).zip(source_row) {
+                *target_cell = *source_cell;
+            }
+        }
+    }
+
+    fn print(grid: &mut TerminalGrid, row: u16, column: u16, text: &str, style: TextStyle) {
+        let Some(row) = grid.rows.get_mut(row as usize) else {
+            return;
+        };
+        let mut column = column as usize;
+        for character in text.chars() {
+            let Some(cell) = row.get_mut(column) else {
+                return;
+            };
+            *cell = StyledChar { character, style };
+            column += character.width().unwrap_or(1);
+        }
+    }
+}
+
+/// Keeps the slide previews shown in a speaker view so they're only rendered once.
+#[derive(Default)]
+pub(crate) struct SlidePreviews {
+    dimensions: Option<WindowSize>,
+    previews: HashMap<usize, TerminalGrid>,
+}
+
+impl SlidePreviews {
+    /// Get the preview for a slide, rendering it if it hasn't been rendered for these dimensions yet.
+    pub(crate) fn get_or_render(
+        &mut self,
+        index: usize,
+        slide: &Slide,
+        dimensions: WindowSize,
+        options: &RenderEngineOptions,
+    ) -> Result<&TerminalGrid, RenderError> {
+        if self.dimensions != Some(dimensions) {
+            self.previews.clear();
+            self.dimensions = Some(dimensions);
+        }
+        let preview = match self.previews.entry(index) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(SpeakerView::render_preview(slide, dimensions, options)?),
+        };
+        Ok(preview)
+    }
+
+    /// Forget all previews, e.g. because the presentation changed.
+    pub(crate) fn clear(&mut self) {
+        self.previews.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        markdown::{elements::Text, text::WeightedLine},
+        presentation::SlideChunk,
+        render::operation::RenderOperation,
+        theme::Alignment,
+    };
+    use rstest::rstest;
+
+    fn make_slide(text: &str) -> Slide {
+        let operations = vec![RenderOperation::RenderText {
+            line: WeightedLine::from(vec![Text::from(text)]),
+            alignment: Alignment::Left { margin: Default::default() },
+        }];
+        Slide::new(vec![SlideChunk::new(operations, vec![])], vec![])
+    }
+
+    fn grid_lines(grid: &TerminalGrid) -> Vec<String> {
+        grid.rows.iter().map(|row| row.iter().map(|c| c.character).collect()).collect()
+    }
+
+    #[test]
+    fn layout() {
+        let notes = make_slide("note");
+        let dimensions = WindowSize { rows: 8, columns: 30, height: 160, width: 300 };
+        let next = SpeakerView::render_preview(&make_slide("next"), dimensions, &Default::default()).unwrap();
+        let view = SpeakerView {
+            notes: &notes,
+            next_slide: Some(&next),
+            current_slide: 2,
+            total_slides: 3,
+            elapsed: Duration::from_secs(65),
+            pacing: None,
+        };
+        let grid = view.render(dimensions, &Default::default()).expect("render failed");
+        let lines = grid_lines(&grid);
+        let expected = &[
+            " Slide 2/3              01:05 ",
+            "──────────────────────────────",
+            "note              │ Next slide",
+            "                  │next       ",
+            "                  │           ",
+            "                  │           ",
+            "                  │           ",
+            "                  │           ",
+        ];
+        assert_eq!(lines, expected);
+    }
+
+    #[test]
+    fn last_slide() {
+        let notes = make_slide("note");
+        let view = SpeakerView {
+            notes: &notes,
+            next_slide: None,
+            current_slide: 3,
+            total_slides: 3,
+            elapsed: Duration::ZERO,
+            pacing: None,
+        };
+        let dimensions = WindowSize { rows: 4, columns: 60, height: 80, width: 600 };
+        let grid = view.render(dimensions, &Default::default()).expect("render failed");
+        let lines = grid_lines(&grid);
+        assert_eq!(lines[2].trim_end(), format!("note{}│ End of presentation", " ".repeat(32)));
+    }
+
+    #[rstest]
+    #[case::on_track(false, None)]
+    #[case::behind(true, Some(Color::Red))]
+    fn pacing(#[case] behind: bool, #[case] color: Option<Color>) {
+        let notes = make_slide("note");
+        let pacing = PacingStatus { budget: Duration::from_secs(120), behind, ..Default::default() };
+        let view = SpeakerView {
+            notes: &notes,
+            next_slide: None,
+            current_slide: 1,
+            total_slides: 3,
+            elapsed: Duration::from_secs(65),
+            pacing: Some(pacing),
+        };
+        let dimensions = WindowSize { rows: 4, columns: 30, height: 80, width: 300 };
+        let grid = view.render(dimensions, &Default::default()).expect("render failed");
+        assert_eq!(grid_lines(&grid)[0], " Slide 1/3      01:05 / 02:00 ");
+        assert_eq!(grid.rows[0][16].style.colors.foreground, color);
+    }
+
+    #[test]
+    fn cached_previews() {
+        let mut previews = SlidePreviews::default();
+        let options = RenderEngineOptions::default();
+        let dimensions = WindowSize { rows: 8, columns: 30, height: 160, width: 300 };
+        previews.get_or_render(1, &make_slide("first"), dimensions, &options).unwrap();
+
+        // The slide is only rendered the first time.
+        let preview = previews.get_or_render(1, &make_slide("second"), dimensions, &options).unwrap();
+        assert_eq!(grid_lines(preview)[0].trim_end(), "first");
+
+        previews.clear();
+        let preview = previews.get_or_render(1, &make_slide("second"), dimensions, &options).unwrap();
+        assert_eq!(grid_lines(preview)[0].trim_end(), "second");
+    }
+}
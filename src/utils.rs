@@ -3,7 +3,9 @@ use std::{
     fmt::{self, Display},
     marker::PhantomData,
     str::FromStr,
+    time::Duration,
 };
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 macro_rules! impl_deserialize_from_str {
     ($ty:ty) => {
@@ -73,3 +75,232 @@ where
 {
     serializer.serialize_str(&value.to_string())
 }
+
+/// A duration that can be expressed in a human friendly way, like `90s`, `2m`, or `1h30m`.
+///
+/// A number without a unit is interpreted as seconds.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct HumanDuration(pub(crate) Duration);
+
+impl_deserialize_from_str!(HumanDuration);
+impl_serialize_from_display!(HumanDuration);
+
+impl FromStr for HumanDuration {
+    type Err = ParseDurationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(ParseDurationError::Empty);
+        }
+        if let Ok(seconds) = s.parse::<u64>() {
+            return Ok(Self(Duration::from_secs(seconds)));
+        }
+        let mut total = Duration::ZERO;
+        let mut input = s;
+        while !input.is_empty() {
+            let number_length = input.find(|c: char| !c.is_ascii_digit()).unwrap_or(input.len());
+            if number_length == 0 {
+                return Err(ParseDurationError::Invalid(s.to_string()));
+            }
+            let value: u64 = input[..number_length].parse().map_err(|_| ParseDurationError::Invalid(s.to_string()))?;
+            input = &input[number_length..];
+            let unit_length = input.find(|c: char| c.is_ascii_digit()).unwrap_or(input.len());
+            let duration = match &input[..unit_length] {
+                "h" => value.checked_mul(3600).map(Duration::from_secs),
+                "m" => value.checked_mul(60).map(Duration::from_secs),
+                "s" => Some(Duration::from_secs(value)),
+                "ms" => Some(Duration::from_millis(value)),
+                unit => return Err(ParseDurationError::InvalidUnit(unit.to_string())),
+            };
+            total = duration
+                .and_then(|duration| total.checked_add(duration))
+                .ok_or_else(|| ParseDurationError::Invalid(s.to_string()))?;
+            input = &input[unit_length..];
+        }
+        Ok(Self(total))
+    }
+}
+
+impl Display for HumanDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let seconds = self.0.as_secs();
+        let millis = self.0.subsec_millis();
+        let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+        if hours > 0 {
+            write!(f, "{hours}h")?;
+        }
+        if minutes > 0 {
+            write!(f, "{minutes}m")?;
+        }
+        if seconds > 0 || (hours == 0 && minutes == 0 && millis == 0) {
+            write!(f, "{seconds}s")?;
+        }
+        if millis > 0 {
+            write!(f, "{millis}ms")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum ParseDurationError {
+    #[error("duration is empty")]
+    Empty,
+
+    #[error("invalid duration: '{0}'")]
+    Invalid(String),
+
+    #[error("invalid duration unit '{0}', expected one of 'h', 'm', 's', 'ms'")]
+    InvalidUnit(String),
+}
+
+/// An amount of bytes that can be expressed in a human friendly way, like `512M` or `2G`.
+///
+/// Units are powers of 1024 and a number without a unit is interpreted as bytes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct ByteSize(pub(crate) u64);
+
+impl_deserialize_from_str!(ByteSize);
+impl_serialize_from_display!(ByteSize);
+
+impl ByteSize {
+    const UNITS: [(&'static str, u64); 3] = [("G", 1 << 30), ("M", 1 << 20), ("K", 1 << 10)];
+}
+
+impl FromStr for ByteSize {
+    type Err = ParseByteSizeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let number_length = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let value: u64 = s[..number_length].parse().map_err(|_| ParseByteSizeError::Invalid(s.to_string()))?;
+        let unit = s[number_length..].trim_start();
+        let unit = unit.strip_suffix("iB").or_else(|| unit.strip_suffix('B')).unwrap_or(unit);
+        if unit.is_empty() {
+            return Ok(Self(value));
+        }
+        let (_, multiplier) = Self::UNITS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(unit))
+            .ok_or_else(|| ParseByteSizeError::InvalidUnit(unit.to_string()))?;
+        value.checked_mul(*multiplier).map(Self).ok_or_else(|| ParseByteSizeError::Invalid(s.to_string()))
+    }
+}
+
+impl Display for ByteSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, multiplier) in Self::UNITS {
+            if self.0 > 0 && self.0.is_multiple_of(multiplier) {
+                return write!(f, "{}{name}", self.0 / multiplier);
+            }
+        }
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum ParseByteSizeError {
+    #[error("invalid size: '{0}'")]
+    Invalid(String),
+
+    #[error("invalid size unit '{0}', expected one of 'K', 'M', 'G'")]
+    InvalidUnit(String),
+}
+
+/// Get the longest suffix of `text` that fits in `max_width` columns.
+pub(crate) fn tail_within_width(text: &str, max_width: usize) -> &str {
+    let mut excess = text.width().saturating_sub(max_width);
+    for (index, c) in text.char_indices() {
+        if excess == 0 {
+            return &text[index..];
+        }
+        excess = excess.saturating_sub(c.width().unwrap_or(0));
+    }
+    ""
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::seconds("90", Duration::from_secs(90))]
+    #[case::seconds_unit("90s", Duration::from_secs(90))]
+    #[case::minutes("2m", Duration::from_secs(120))]
+    #[case::hours("1h", Duration::from_secs(3600))]
+    #[case::millis("500ms", Duration::from_millis(500))]
+    #[case::combined("1h30m15s", Duration::from_secs(5415))]
+    #[case::whitespace(" 5m ", Duration::from_secs(300))]
+    fn parse_duration(#[case] input: &str, #[case] expected: Duration) {
+        let duration: HumanDuration = input.parse().expect("parse failed");
+        assert_eq!(duration.0, expected);
+    }
+
+    #[rstest]
+    #[case::empty("")]
+    #[case::no_number("m")]
+    #[case::unknown_unit("5y")]
+    #[case::negative("-5s")]
+    #[case::hours_overflow("18446744073709551615h")]
+    #[case::minutes_overflow("18446744073709551615m")]
+    #[case::sum_overflow("18446744073709551615s1s")]
+    fn parse_invalid_duration(#[case] input: &str) {
+        HumanDuration::from_str(input).expect_err("parse succeeded");
+    }
+
+    #[rstest]
+    #[case::zero(Duration::ZERO, "0s")]
+    #[case::seconds(Duration::from_secs(42), "42s")]
+    #[case::minutes(Duration::from_secs(120), "2m")]
+    #[case::combined(Duration::from_secs(5415), "1h30m15s")]
+    #[case::millis(Duration::from_millis(1500), "1s500ms")]
+    fn format_duration(#[case] duration: Duration, #[case] expected: &str) {
+        let duration = HumanDuration(duration);
+        assert_eq!(duration.to_string(), expected);
+        assert_eq!(expected.parse::<HumanDuration>().unwrap(), duration);
+    }
+
+    #[rstest]
+    #[case::bytes("100", 100)]
+    #[case::kilobytes("2K", 2048)]
+    #[case::megabytes("512M", 512 << 20)]
+    #[case::megabytes_suffix("512MB", 512 << 20)]
+    #[case::binary_suffix("1GiB", 1 << 30)]
+    #[case::lowercase("4k", 4096)]
+    fn parse_byte_size(#[case] input: &str, #[case] expected: u64) {
+        let size: ByteSize = input.parse().expect("parse failed");
+        assert_eq!(size.0, expected);
+    }
+
+    #[rstest]
+    #[case::empty("")]
+    #[case::no_number("M")]
+    #[case::unknown_unit("5T")]
+    #[case::overflow("99999999999999999G")]
+    fn parse_invalid_byte_size(#[case] input: &str) {
+        ByteSize::from_str(input).expect_err("parse succeeded");
+    }
+
+    #[rstest]
+    #[case::bytes(100, "100")]
+    #[case::megabytes(512 << 20, "512M")]
+    #[case::mixed((1 << 20) + 1024, "1025K")]
+    fn format_byte_size(#[case] size: u64, #[case] expected: &str) {
+        let size = ByteSize(size);
+        assert_eq!(size.to_string(), expected);
+        assert_eq!(expected.parse::<ByteSize>().unwrap(), size);
+    }
+
+    #[rstest]
+    #[case::fits("hello", 10, "hello")]
+    #[case::exact("hello", 5, "hello")]
+    #[case::trimmed("hello", 3, "llo")]
+    #[case::empty("hello", 0, "")]
+    #[case::wide_chars("ああa", 3, "あa")]
+    #[case::wide_chars_boundary("ああa", 2, "a")]
+    fn tail_within_width(#[case] text: &str, #[case] max_width: usize, #[case] expected: &str) {
+        assert_eq!(super::tail_within_width(text, max_width), expected);
+    }
+}